@@ -0,0 +1,28 @@
+import { PublicKey } from '@solana/web3.js';
+
+// Address derivation for the AMM program's PDAs. These mirror the seeds in
+// contracts/src/programs/amm/lib.rs.
+
+// One pool per property mint and quote mint
+export function findPoolAddress(
+  programId: PublicKey,
+  propertyMint: PublicKey,
+  quoteMint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('pool'), propertyMint.toBuffer(), quoteMint.toBuffer()],
+    programId
+  );
+}
+
+export function findPoolLpMintAddress(programId: PublicKey, pool: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('pool_lp'), pool.toBuffer()], programId);
+}
+
+export function findPoolPropertyVaultAddress(programId: PublicKey, pool: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('pool_property'), pool.toBuffer()], programId);
+}
+
+export function findPoolQuoteVaultAddress(programId: PublicKey, pool: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('pool_quote'), pool.toBuffer()], programId);
+}
@@ -0,0 +1,191 @@
+import { Connection, PublicKey } from '@solana/web3.js';
+import BN from 'bn.js';
+
+// Address derivation for the marketplace program's PDAs. These mirror the
+// seeds in contracts/src/programs/marketplace/lib.rs.
+
+export function findMarketplaceAddress(programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('marketplace')], programId);
+}
+
+export function findSellerListingsAddress(
+  programId: PublicKey,
+  seller: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('seller_listings'), seller.toBuffer()],
+    programId
+  );
+}
+
+// Listings are numbered per seller from 0, in creation order
+export function findListingAddress(
+  programId: PublicKey,
+  seller: PublicKey,
+  index: number | BN
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('listing'), seller.toBuffer(), new BN(index).toArrayLike(Buffer, 'le', 8)],
+    programId
+  );
+}
+
+export function findEscrowAddress(programId: PublicKey, listing: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('escrow'), listing.toBuffer()], programId);
+}
+
+// Fee override and royalty for one property mint, if the authority set one
+export function findMintFeeConfigAddress(programId: PublicKey, mint: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('mint_fee'), mint.toBuffer()], programId);
+}
+
+// Volume a trader has bought and sold in one quote mint, for fee tiers and
+// per-trader stats
+export function findTraderVolumeAddress(
+  programId: PublicKey,
+  trader: PublicKey,
+  quoteMint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('trader_volume'), trader.toBuffer(), quoteMint.toBuffer()],
+    programId
+  );
+}
+
+// Market data for a property mint's listing fills in one quote mint
+export function findMintStatsAddress(
+  programId: PublicKey,
+  mint: PublicKey,
+  quoteMint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('mint_stats'), mint.toBuffer(), quoteMint.toBuffer()],
+    programId
+  );
+}
+
+// Ring buffer of cumulative prices for a property mint's listing fills in one
+// quote mint, for TWAP reads
+export function findPriceOracleAddress(
+  programId: PublicKey,
+  mint: PublicKey,
+  quoteMint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('price_oracle'), mint.toBuffer(), quoteMint.toBuffer()],
+    programId
+  );
+}
+
+// One open offer per buyer per listing
+export function findOfferAddress(
+  programId: PublicKey,
+  listing: PublicKey,
+  buyer: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('offer'), listing.toBuffer(), buyer.toBuffer()],
+    programId
+  );
+}
+
+export function findOfferVaultAddress(programId: PublicKey, offer: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([Buffer.from('offer_escrow'), offer.toBuffer()], programId);
+}
+
+// Auctions take their index from the same per-seller counter as listings
+export function findAuctionAddress(
+  programId: PublicKey,
+  seller: PublicKey,
+  index: number | BN
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('auction'), seller.toBuffer(), new BN(index).toArrayLike(Buffer, 'le', 8)],
+    programId
+  );
+}
+
+export function findAuctionQuoteVaultAddress(
+  programId: PublicKey,
+  auction: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('auction_quote'), auction.toBuffer()],
+    programId
+  );
+}
+
+export function findAuctionBidAddress(
+  programId: PublicKey,
+  auction: PublicKey,
+  bidder: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('auction_bid'), auction.toBuffer(), bidder.toBuffer()],
+    programId
+  );
+}
+
+// One order book per property mint and quote mint
+export function findOrderBookAddress(
+  programId: PublicKey,
+  mint: PublicKey,
+  quoteMint: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('order_book'), mint.toBuffer(), quoteMint.toBuffer()],
+    programId
+  );
+}
+
+export function findOrderBookVaultAddresses(
+  programId: PublicKey,
+  orderBook: PublicKey
+): { baseVault: PublicKey; quoteVault: PublicKey } {
+  const [baseVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from('order_book_base'), orderBook.toBuffer()],
+    programId
+  );
+  const [quoteVault] = PublicKey.findProgramAddressSync(
+    [Buffer.from('order_book_quote'), orderBook.toBuffer()],
+    programId
+  );
+  return { baseVault, quoteVault };
+}
+
+// Balances a trader can withdraw from one order book with `settle_funds`
+export function findOpenOrdersAddress(
+  programId: PublicKey,
+  orderBook: PublicKey,
+  owner: PublicKey
+): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('open_orders'), orderBook.toBuffer(), owner.toBuffer()],
+    programId
+  );
+}
+
+// Index the seller's next listing will get: `listing_count` on their
+// SellerListings account, or 0 before their first listing.
+export async function fetchNextListingIndex(
+  connection: Connection,
+  programId: PublicKey,
+  seller: PublicKey
+): Promise<BN> {
+  const [sellerListings] = findSellerListingsAddress(programId, seller);
+  const account = await connection.getAccountInfo(sellerListings);
+  if (!account) {
+    return new BN(0);
+  }
+  // discriminator (8) + seller (32), then listing_count as a little-endian u64
+  return new BN(account.data.subarray(40, 48), 'le');
+}
+
+export async function findNextListingAddress(
+  connection: Connection,
+  programId: PublicKey,
+  seller: PublicKey
+): Promise<[PublicKey, number]> {
+  const index = await fetchNextListingIndex(connection, programId, seller);
+  return findListingAddress(programId, seller, index);
+}
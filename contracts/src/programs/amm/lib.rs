@@ -0,0 +1,945 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, spl_token::native_mint, Burn, Mint, MintTo, Token, TokenAccount, Transfer};
+use marketplace::Marketplace;
+use registry::{PropertyRecord, VerificationStatus};
+
+declare_id!("Amm11111111111111111111111111111111111111");
+
+/// Highest swap fee a pool can charge, in basis points
+pub const MAX_POOL_FEE: u16 = 1000;
+
+/// LP units minted on the first deposit that are never issued to anyone, so
+/// a pool's LP supply (and its reserves) can never be drained back to zero
+pub const MINIMUM_LIQUIDITY: u64 = 1000;
+
+#[program]
+pub mod amm {
+    use super::*;
+
+    /// Only the marketplace authority opens pools, so nobody can claim a
+    /// property's one pool per quote mint and set its fee first
+    pub fn initialize_pool(
+        ctx: Context<InitializePool>,
+        fee: u16, // in basis points, paid to liquidity providers
+    ) -> Result<()> {
+        require!(fee <= MAX_POOL_FEE, AmmError::FeeTooHigh);
+        
+        // Pools quote in the same mints as the marketplace: native SOL, or
+        // an SPL mint the marketplace allows
+        let quote_mint = ctx.accounts.quote_mint.key();
+        require!(
+            quote_mint == native_mint::ID
+                || ctx.accounts.marketplace.allowed_quote_mints.contains(&quote_mint),
+            AmmError::QuoteMintNotAllowed
+        );
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.property_mint = ctx.accounts.property_token_mint.key();
+        pool.quote_mint = ctx.accounts.quote_mint.key();
+        pool.lp_mint = ctx.accounts.lp_mint.key();
+        pool.property_vault = ctx.accounts.property_vault.key();
+        pool.quote_vault = ctx.accounts.quote_vault.key();
+        pool.property_decimals = ctx.accounts.property_token_mint.decimals;
+        pool.fee = fee;
+        pool.property_reserve = 0;
+        pool.quote_reserve = 0;
+        pool.lp_supply = 0;
+        pool.total_volume = 0;
+        pool.bump = *ctx.bumps.get("pool")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        pool.created_at = Clock::get()?.unix_timestamp;
+        pool.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(PoolInitialized {
+            pool: pool.key(),
+            authority: pool.authority,
+            property_mint: pool.property_mint,
+            quote_mint: pool.quote_mint,
+            lp_mint: pool.lp_mint,
+            fee,
+            timestamp: pool.created_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        max_property_amount: u64,
+        max_quote_amount: u64,
+        min_lp_amount: u64,
+    ) -> Result<()> {
+        require!(
+            max_property_amount > 0 && max_quote_amount > 0,
+            AmmError::InvalidAmount
+        );
+        
+        let pool = &ctx.accounts.pool;
+        require_trading(&ctx.accounts.marketplace, &pool.property_mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &pool.property_mint,
+        )?;
+        
+        // The first deposit sets the price; later deposits must match the
+        // reserve ratio, so only the part of each maximum that fits is taken
+        let (property_amount, quote_amount, lp_amount, lp_supply) = if pool.lp_supply == 0 {
+            let liquidity = isqrt(
+                (max_property_amount as u128)
+                    .checked_mul(max_quote_amount as u128)
+                    .ok_or(AmmError::ArithmeticError)?,
+            );
+            let liquidity = u64::try_from(liquidity)
+                .map_err(|_| error!(AmmError::ArithmeticError))?;
+            
+            require!(
+                liquidity > MINIMUM_LIQUIDITY,
+                AmmError::InsufficientLiquidity
+            );
+            
+            (max_property_amount, max_quote_amount, liquidity - MINIMUM_LIQUIDITY, liquidity)
+        } else {
+            let lp_amount = mul_div(max_property_amount, pool.lp_supply, pool.property_reserve)?
+                .min(mul_div(max_quote_amount, pool.lp_supply, pool.quote_reserve)?);
+            
+            require!(lp_amount > 0, AmmError::InsufficientLiquidity);
+            
+            let property_amount = mul_div_ceil(lp_amount, pool.property_reserve, pool.lp_supply)?;
+            let quote_amount = mul_div_ceil(lp_amount, pool.quote_reserve, pool.lp_supply)?;
+            let lp_supply = pool.lp_supply
+                .checked_add(lp_amount)
+                .ok_or(AmmError::ArithmeticError)?;
+            
+            (property_amount, quote_amount, lp_amount, lp_supply)
+        };
+        
+        require!(lp_amount >= min_lp_amount, AmmError::SlippageExceeded);
+        
+        // Deposit both sides into the pool's vaults
+        let property_accounts = Transfer {
+            from: ctx.accounts.provider_property_account.to_account_info(),
+            to: ctx.accounts.property_vault.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        
+        let property_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            property_accounts,
+        );
+        
+        token::transfer(property_ctx, property_amount)?;
+        
+        let quote_accounts = Transfer {
+            from: ctx.accounts.provider_quote_account.to_account_info(),
+            to: ctx.accounts.quote_vault.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        
+        let quote_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            quote_accounts,
+        );
+        
+        token::transfer(quote_ctx, quote_amount)?;
+        
+        // Mint the provider's share of the pool
+        let property_mint_key = pool.property_mint;
+        let quote_mint_key = pool.quote_mint;
+        let seeds = &[
+            b"pool".as_ref(),
+            property_mint_key.as_ref(),
+            quote_mint_key.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let mint_accounts = MintTo {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            to: ctx.accounts.provider_lp_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        
+        let mint_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            mint_accounts,
+            signer,
+        );
+        
+        token::mint_to(mint_ctx, lp_amount)?;
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.property_reserve = pool.property_reserve
+            .checked_add(property_amount)
+            .ok_or(AmmError::ArithmeticError)?;
+        pool.quote_reserve = pool.quote_reserve
+            .checked_add(quote_amount)
+            .ok_or(AmmError::ArithmeticError)?;
+        pool.lp_supply = lp_supply;
+        pool.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(LiquidityAdded {
+            pool: pool.key(),
+            provider: ctx.accounts.provider.key(),
+            property_amount,
+            quote_amount,
+            lp_amount,
+            property_reserve: pool.property_reserve,
+            quote_reserve: pool.quote_reserve,
+            lp_supply: pool.lp_supply,
+            timestamp: pool.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        lp_amount: u64,
+        min_property_amount: u64,
+        min_quote_amount: u64,
+    ) -> Result<()> {
+        require!(lp_amount > 0, AmmError::InvalidAmount);
+        
+        let pool = &ctx.accounts.pool;
+        
+        // Withdrawals are paid pro rata from both reserves, rounding down
+        let property_amount = mul_div(lp_amount, pool.property_reserve, pool.lp_supply)?;
+        let quote_amount = mul_div(lp_amount, pool.quote_reserve, pool.lp_supply)?;
+        
+        require!(
+            property_amount >= min_property_amount && quote_amount >= min_quote_amount,
+            AmmError::SlippageExceeded
+        );
+        
+        let burn_accounts = Burn {
+            mint: ctx.accounts.lp_mint.to_account_info(),
+            from: ctx.accounts.provider_lp_account.to_account_info(),
+            authority: ctx.accounts.provider.to_account_info(),
+        };
+        
+        let burn_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            burn_accounts,
+        );
+        
+        token::burn(burn_ctx, lp_amount)?;
+        
+        let property_mint_key = pool.property_mint;
+        let quote_mint_key = pool.quote_mint;
+        let seeds = &[
+            b"pool".as_ref(),
+            property_mint_key.as_ref(),
+            quote_mint_key.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let property_accounts = Transfer {
+            from: ctx.accounts.property_vault.to_account_info(),
+            to: ctx.accounts.provider_property_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        
+        let property_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            property_accounts,
+            signer,
+        );
+        
+        token::transfer(property_ctx, property_amount)?;
+        
+        let quote_accounts = Transfer {
+            from: ctx.accounts.quote_vault.to_account_info(),
+            to: ctx.accounts.provider_quote_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        
+        let quote_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            quote_accounts,
+            signer,
+        );
+        
+        token::transfer(quote_ctx, quote_amount)?;
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.property_reserve = pool.property_reserve
+            .checked_sub(property_amount)
+            .ok_or(AmmError::ArithmeticError)?;
+        pool.quote_reserve = pool.quote_reserve
+            .checked_sub(quote_amount)
+            .ok_or(AmmError::ArithmeticError)?;
+        pool.lp_supply = pool.lp_supply
+            .checked_sub(lp_amount)
+            .ok_or(AmmError::ArithmeticError)?;
+        pool.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(LiquidityRemoved {
+            pool: pool.key(),
+            provider: ctx.accounts.provider.key(),
+            property_amount,
+            quote_amount,
+            lp_amount,
+            property_reserve: pool.property_reserve,
+            quote_reserve: pool.quote_reserve,
+            lp_supply: pool.lp_supply,
+            timestamp: pool.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn swap(
+        ctx: Context<Swap>,
+        side: SwapSide,
+        amount_in: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        require!(amount_in > 0, AmmError::InvalidAmount);
+        
+        let pool = &ctx.accounts.pool;
+        require_trading(&ctx.accounts.marketplace, &pool.property_mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &pool.property_mint,
+        )?;
+        let (amount_out, fee_amount) = pool.swap_output(side, amount_in)
+            .ok_or(AmmError::InsufficientLiquidity)?;
+        
+        require!(amount_out > 0, AmmError::InsufficientLiquidity);
+        require!(amount_out >= min_amount_out, AmmError::SlippageExceeded);
+        
+        // Buys pay quote tokens for property tokens; sells the reverse
+        let (trader_in, vault_in, vault_out, trader_out) = match side {
+            SwapSide::Buy => (
+                ctx.accounts.trader_quote_account.to_account_info(),
+                ctx.accounts.quote_vault.to_account_info(),
+                ctx.accounts.property_vault.to_account_info(),
+                ctx.accounts.trader_property_account.to_account_info(),
+            ),
+            SwapSide::Sell => (
+                ctx.accounts.trader_property_account.to_account_info(),
+                ctx.accounts.property_vault.to_account_info(),
+                ctx.accounts.quote_vault.to_account_info(),
+                ctx.accounts.trader_quote_account.to_account_info(),
+            ),
+        };
+        
+        let in_accounts = Transfer {
+            from: trader_in,
+            to: vault_in,
+            authority: ctx.accounts.trader.to_account_info(),
+        };
+        
+        let in_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            in_accounts,
+        );
+        
+        token::transfer(in_ctx, amount_in)?;
+        
+        let property_mint_key = pool.property_mint;
+        let quote_mint_key = pool.quote_mint;
+        let seeds = &[
+            b"pool".as_ref(),
+            property_mint_key.as_ref(),
+            quote_mint_key.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let out_accounts = Transfer {
+            from: vault_out,
+            to: trader_out,
+            authority: pool.to_account_info(),
+        };
+        
+        let out_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            out_accounts,
+            signer,
+        );
+        
+        token::transfer(out_ctx, amount_out)?;
+        
+        // The fee stays in the input reserve, growing the value of every LP
+        // token
+        let pool = &mut ctx.accounts.pool;
+        let quote_volume = match side {
+            SwapSide::Buy => {
+                pool.quote_reserve = pool.quote_reserve
+                    .checked_add(amount_in)
+                    .ok_or(AmmError::ArithmeticError)?;
+                pool.property_reserve = pool.property_reserve
+                    .checked_sub(amount_out)
+                    .ok_or(AmmError::ArithmeticError)?;
+                amount_in
+            }
+            SwapSide::Sell => {
+                pool.property_reserve = pool.property_reserve
+                    .checked_add(amount_in)
+                    .ok_or(AmmError::ArithmeticError)?;
+                pool.quote_reserve = pool.quote_reserve
+                    .checked_sub(amount_out)
+                    .ok_or(AmmError::ArithmeticError)?;
+                amount_out
+            }
+        };
+        pool.total_volume = pool.total_volume
+            .checked_add(quote_volume)
+            .ok_or(AmmError::ArithmeticError)?;
+        pool.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(Swapped {
+            pool: pool.key(),
+            trader: ctx.accounts.trader.key(),
+            side,
+            amount_in,
+            amount_out,
+            fee_amount,
+            property_reserve: pool.property_reserve,
+            quote_reserve: pool.quote_reserve,
+            timestamp: pool.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Checked against the current marketplace authority, so a handover of
+    /// the marketplace also hands over every pool's fee
+    pub fn update_pool_fee(
+        ctx: Context<UpdatePool>,
+        fee: u16,
+    ) -> Result<()> {
+        require!(fee <= MAX_POOL_FEE, AmmError::FeeTooHigh);
+        
+        let pool = &mut ctx.accounts.pool;
+        pool.fee = fee;
+        pool.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(PoolFeeUpdated {
+            pool: pool.key(),
+            fee,
+            timestamp: pool.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Spot price of one whole property token in quote base units. Read it
+    /// with a simulated transaction, or call `Pool::price` on the account.
+    pub fn get_price(
+        ctx: Context<QueryPool>,
+    ) -> Result<u64> {
+        let price = ctx.accounts.pool.price()
+            .ok_or(AmmError::EmptyPool)?;
+        
+        Ok(price)
+    }
+
+    /// Tokens a swap of `amount_in` would return right now, after the fee
+    pub fn quote_swap(
+        ctx: Context<QueryPool>,
+        side: SwapSide,
+        amount_in: u64,
+    ) -> Result<u64> {
+        let (amount_out, _) = ctx.accounts.pool.swap_output(side, amount_in)
+            .ok_or(AmmError::InsufficientLiquidity)?;
+        
+        Ok(amount_out)
+    }
+}
+
+/// `a * b / c`, rounding down
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    require!(c > 0, AmmError::EmptyPool);
+    
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(AmmError::ArithmeticError)?
+        / c as u128;
+    
+    u64::try_from(result).map_err(|_| error!(AmmError::ArithmeticError))
+}
+
+/// `a * b / c`, rounding up so deposits never take less than their share
+fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64> {
+    require!(c > 0, AmmError::EmptyPool);
+    
+    let result = (a as u128)
+        .checked_mul(b as u128)
+        .ok_or(AmmError::ArithmeticError)?
+        .checked_add(c as u128 - 1)
+        .ok_or(AmmError::ArithmeticError)?
+        / c as u128;
+    
+    u64::try_from(result).map_err(|_| error!(AmmError::ArithmeticError))
+}
+
+/// Integer square root, rounding down
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+
+    let mut x = value;
+    let mut y = x / 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+
+    x
+}
+
+/// Fails if the marketplace is paused or `mint` has been delisted, as the
+/// marketplace's own trading paths do
+fn require_trading(marketplace: &Marketplace, mint: &Pubkey) -> Result<()> {
+    require!(!marketplace.paused, AmmError::MarketplacePaused);
+    require!(
+        !marketplace.delisted_mints.contains(mint),
+        AmmError::MintDelisted
+    );
+    
+    Ok(())
+}
+
+/// With the marketplace's `require_verified` set, `mint` needs a registry
+/// `PropertyRecord` that is verified and whose verification has not expired
+fn require_verified_property(
+    marketplace: &Marketplace,
+    property_record: Option<&PropertyRecord>,
+    mint: &Pubkey,
+) -> Result<()> {
+    if !marketplace.require_verified {
+        return Ok(());
+    }
+    
+    let property_record = property_record.ok_or(AmmError::PropertyNotVerified)?;
+    require_keys_eq!(
+        property_record.token_mint,
+        *mint,
+        AmmError::InvalidPropertyRecord
+    );
+    
+    let now = Clock::get()?.unix_timestamp;
+    let unexpired = match property_record.verification_details.as_ref() {
+        Some(details) => details.verification_expiry > now,
+        None => false,
+    };
+    require!(
+        property_record.verification_status == VerificationStatus::Verified && unexpired,
+        AmmError::PropertyNotVerified
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        seeds::program = marketplace::ID,
+        has_one = authority @ AmmError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub property_token_mint: Account<'info, Mint>,
+    
+    #[account(
+        constraint = quote_mint.key() != property_token_mint.key() @ AmmError::InvalidPoolMints
+    )]
+    pub quote_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Pool::LEN,
+        seeds = [
+            b"pool",
+            property_token_mint.key().as_ref(),
+            quote_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = property_token_mint.decimals,
+        mint::authority = pool,
+        seeds = [b"pool_lp", pool.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = property_token_mint,
+        token::authority = pool,
+        seeds = [b"pool_property", pool.key().as_ref()],
+        bump
+    )]
+    pub property_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = quote_mint,
+        token::authority = pool,
+        seeds = [b"pool_quote", pool.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AddLiquidity<'info> {
+    pub provider: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        seeds::program = marketplace::ID
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// Registry record for the pool's property mint; required when the
+    /// marketplace only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.property_mint.as_ref(), pool.quote_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(mut, address = pool.property_vault)]
+    pub property_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = pool.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = provider_property_account.mint == pool.property_mint,
+        constraint = provider_property_account.owner == provider.key()
+    )]
+    pub provider_property_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = provider_quote_account.mint == pool.quote_mint,
+        constraint = provider_quote_account.owner == provider.key()
+    )]
+    pub provider_quote_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = provider_lp_account.mint == pool.lp_mint
+    )]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveLiquidity<'info> {
+    pub provider: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.property_mint.as_ref(), pool.quote_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: Account<'info, Mint>,
+    
+    #[account(mut, address = pool.property_vault)]
+    pub property_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = pool.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = provider_lp_account.mint == pool.lp_mint,
+        constraint = provider_lp_account.owner == provider.key()
+    )]
+    pub provider_lp_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = provider_property_account.mint == pool.property_mint
+    )]
+    pub provider_property_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = provider_quote_account.mint == pool.quote_mint
+    )]
+    pub provider_quote_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub trader: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        seeds::program = marketplace::ID
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// Registry record for the pool's property mint; required when the
+    /// marketplace only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        mut,
+        seeds = [b"pool", pool.property_mint.as_ref(), pool.quote_mint.as_ref()],
+        bump = pool.bump
+    )]
+    pub pool: Account<'info, Pool>,
+    
+    #[account(mut, address = pool.property_vault)]
+    pub property_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = pool.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = trader_property_account.mint == pool.property_mint
+    )]
+    pub trader_property_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = trader_quote_account.mint == pool.quote_mint
+    )]
+    pub trader_quote_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePool<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        seeds::program = marketplace::ID,
+        has_one = authority @ AmmError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+}
+
+#[derive(Accounts)]
+pub struct QueryPool<'info> {
+    pub pool: Account<'info, Pool>,
+}
+
+#[account]
+pub struct Pool {
+    pub authority: Pubkey, // marketplace authority that opened the pool
+    pub property_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub property_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub property_decimals: u8,
+    pub fee: u16, // in basis points
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub lp_supply: u64, // includes the locked MINIMUM_LIQUIDITY
+    pub total_volume: u64, // in quote tokens
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Pool {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority pubkey
+        32 + // property_mint pubkey
+        32 + // quote_mint pubkey
+        32 + // lp_mint pubkey
+        32 + // property_vault pubkey
+        32 + // quote_vault pubkey
+        1 + // property_decimals
+        2 + // fee
+        8 + // property_reserve
+        8 + // quote_reserve
+        8 + // lp_supply
+        8 + // total_volume
+        1 + // bump
+        8 + // created_at
+        8; // updated_at
+
+    /// Constant-product output for `amount_in`, with the fee taken from the
+    /// input first. Returns `(amount_out, fee_amount)`, or `None` for an
+    /// empty pool.
+    pub fn swap_output(&self, side: SwapSide, amount_in: u64) -> Option<(u64, u64)> {
+        let (reserve_in, reserve_out) = match side {
+            SwapSide::Buy => (self.quote_reserve, self.property_reserve),
+            SwapSide::Sell => (self.property_reserve, self.quote_reserve),
+        };
+        if reserve_in == 0 || reserve_out == 0 {
+            return None;
+        }
+        
+        let fee_amount = (amount_in as u128 * self.fee as u128 / 10000) as u64;
+        let net_in = (amount_in - fee_amount) as u128;
+        let amount_out = (reserve_out as u128)
+            .checked_mul(net_in)?
+            .checked_div((reserve_in as u128).checked_add(net_in)?)?;
+        
+        Some((u64::try_from(amount_out).ok()?, fee_amount))
+    }
+
+    /// Quote base units per whole property token implied by the reserves
+    pub fn price(&self) -> Option<u64> {
+        if self.property_reserve == 0 {
+            return None;
+        }
+        
+        let scale = 10u128.checked_pow(self.property_decimals as u32)?;
+        let price = (self.quote_reserve as u128)
+            .checked_mul(scale)?
+            / self.property_reserve as u128;
+        
+        u64::try_from(price).ok()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum SwapSide {
+    Buy, // quote tokens in, property tokens out
+    Sell, // property tokens in, quote tokens out
+}
+
+#[event]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub property_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub property_amount: u64,
+    pub quote_amount: u64,
+    pub lp_amount: u64,
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub lp_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub property_amount: u64,
+    pub quote_amount: u64,
+    pub lp_amount: u64,
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub lp_supply: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct Swapped {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub side: SwapSide,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64, // part of amount_in kept by the pool
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolFeeUpdated {
+    pub pool: Pubkey,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Unauthorized")]
+    Unauthorized,
+    
+    #[msg("Arithmetic error")]
+    ArithmeticError,
+    
+    #[msg("Fee too high")]
+    FeeTooHigh,
+    
+    #[msg("Property and quote mints must differ")]
+    InvalidPoolMints,
+    
+    #[msg("Amount must be positive")]
+    InvalidAmount,
+    
+    #[msg("Pool has no liquidity")]
+    EmptyPool,
+    
+    #[msg("Not enough liquidity for this amount")]
+    InsufficientLiquidity,
+    
+    #[msg("Pool terms are worse than the caller's limits")]
+    SlippageExceeded,
+    
+    #[msg("Quote mint is not allowed by the marketplace")]
+    QuoteMintNotAllowed,
+    
+    #[msg("Marketplace is paused")]
+    MarketplacePaused,
+    
+    #[msg("Trading of this property mint is suspended")]
+    MintDelisted,
+    
+    #[msg("Property is not verified by the registry")]
+    PropertyNotVerified,
+    
+    #[msg("Property record does not match the mint")]
+    InvalidPropertyRecord,
+}
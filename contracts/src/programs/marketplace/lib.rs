@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::system_program;
+use anchor_spl::token::{self, spl_token::native_mint, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use registry::{PropertyRecord, VerificationStatus};
 
 declare_id!("Mrkt1111111111111111111111111111111111111");
 
@@ -15,11 +17,26 @@ pub mod marketplace {
         marketplace.authority = ctx.accounts.authority.key();
         marketplace.treasury = ctx.accounts.treasury.key();
         marketplace.fee = marketplace_fee;
+        marketplace.referrer_fee_share = 0;
+        marketplace.fee_tiers = Vec::new();
+        marketplace.allowed_quote_mints = Vec::new();
+        marketplace.pending_authority = Pubkey::default();
+        marketplace.paused = false;
+        marketplace.delisted_mints = Vec::new();
+        marketplace.require_verified = false;
         marketplace.active_listings = 0;
         marketplace.total_volume = 0;
         marketplace.created_at = Clock::get()?.unix_timestamp;
         marketplace.updated_at = Clock::get()?.unix_timestamp;
 
+        emit!(MarketplaceInitialized {
+            marketplace: marketplace.key(),
+            authority: marketplace.authority,
+            treasury: marketplace.treasury,
+            fee: marketplace.fee,
+            timestamp: marketplace.created_at,
+        });
+
         Ok(())
     }
 
@@ -27,35 +44,127 @@ pub mod marketplace {
         ctx: Context<CreateListing>,
         price_per_token: u64,
         token_amount: u64,
+        expires_at: i64, // 0 for a listing that never expires
     ) -> Result<()> {
+        require_trading(&ctx.accounts.marketplace, &ctx.accounts.property_token_mint.key())?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &ctx.accounts.property_token_mint.key(),
+        )?;
+        
+        require!(
+            token_amount > 0,
+            MarketplaceError::InsufficientTokenAmount
+        );
+        
+        require!(
+            expires_at == 0 || expires_at > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidListingExpiry
+        );
+        
+        // Native SOL is always accepted; SPL quote mints must be allowed by
+        // the marketplace
+        let quote_mint = ctx.accounts.quote_mint.key();
+        require!(
+            quote_mint == native_mint::ID
+                || ctx.accounts.marketplace.allowed_quote_mints.contains(&quote_mint),
+            MarketplaceError::QuoteMintNotAllowed
+        );
+        
+        // Move the listed tokens into the listing's escrow so every fill can
+        // be settled without the seller
+        let token_accounts = Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        
+        let token_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_accounts,
+        );
+        
+        token::transfer(token_ctx, token_amount)?;
+        
+        let seller_listings = &mut ctx.accounts.seller_listings;
+        if seller_listings.seller == Pubkey::default() {
+            seller_listings.seller = ctx.accounts.seller.key();
+            seller_listings.bump = *ctx.bumps.get("seller_listings")
+                .ok_or(ErrorCode::ConstraintSeeds)?;
+        }
+        let index = seller_listings.listing_count;
+        seller_listings.listing_count = index.checked_add(1)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
         let listing = &mut ctx.accounts.listing;
         let seller = &ctx.accounts.seller;
         let property_token_mint = &ctx.accounts.property_token_mint;
         
         listing.seller = seller.key();
+        listing.index = index;
         listing.mint = property_token_mint.key();
+        listing.mint_decimals = property_token_mint.decimals;
+        listing.quote_mint = quote_mint;
+        listing.escrow = ctx.accounts.escrow_token_account.key();
         listing.price_per_token = price_per_token;
         listing.token_amount = token_amount;
+        listing.expires_at = expires_at;
         listing.status = ListingStatus::Active;
+        listing.bump = *ctx.bumps.get("listing")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
         listing.created_at = Clock::get()?.unix_timestamp;
         listing.updated_at = Clock::get()?.unix_timestamp;
         
+        // Sweeps fill listings without the seller's signature, so the volume
+        // account they are credited to has to exist before the listing fills
+        let seller_volume = &mut ctx.accounts.seller_volume;
+        if seller_volume.trader == Pubkey::default() {
+            seller_volume.trader = ctx.accounts.seller.key();
+            seller_volume.quote_mint = quote_mint;
+            seller_volume.bump = *ctx.bumps.get("seller_volume")
+                .ok_or(ErrorCode::ConstraintSeeds)?;
+            seller_volume.updated_at = Clock::get()?.unix_timestamp;
+        }
+        
         // Update marketplace stats
         let marketplace = &mut ctx.accounts.marketplace;
         marketplace.active_listings = marketplace.active_listings.checked_add(1)
             .ok_or(MarketplaceError::ArithmeticError)?;
         marketplace.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(ListingCreated {
+            listing: listing.key(),
+            seller: listing.seller,
+            index: listing.index,
+            mint: listing.mint,
+            quote_mint: listing.quote_mint,
+            price_per_token: listing.price_per_token,
+            token_amount: listing.token_amount,
+            expires_at: listing.expires_at,
+            timestamp: listing.created_at,
+        });
+        
         Ok(())
     }
 
     pub fn execute_trade(
         ctx: Context<ExecuteTrade>,
         token_amount: u64,
+        max_price_per_token: u64,
+        min_token_amount: u64,
+        allow_partial_fill: bool,
     ) -> Result<()> {
         let listing = &mut ctx.accounts.listing;
         let marketplace = &mut ctx.accounts.marketplace;
         
+        require_trading(marketplace, &listing.mint)?;
+        require_verified_property(
+            marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &listing.mint,
+        )?;
+        
         // Check if listing is active and amount is valid
         require!(
             listing.status == ListingStatus::Active,
@@ -63,65 +172,308 @@ pub mod marketplace {
         );
         
         require!(
-            token_amount <= listing.token_amount,
-            MarketplaceError::InsufficientTokenAmount
+            !listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingExpired
+        );
+        
+        // Slippage protection: the buyer's limits must still hold against the
+        // listing as it is now, not as it was when they signed
+        require!(
+            listing.price_per_token <= max_price_per_token,
+            MarketplaceError::SlippageExceeded
+        );
+        
+        require!(
+            token_amount > 0 && min_token_amount <= token_amount,
+            MarketplaceError::InvalidTradeLimits
+        );
+        
+        let token_amount = if token_amount <= listing.token_amount {
+            token_amount
+        } else {
+            require!(
+                allow_partial_fill,
+                MarketplaceError::InsufficientTokenAmount
+            );
+            listing.token_amount
+        };
+        
+        require!(
+            token_amount >= min_token_amount,
+            MarketplaceError::SlippageExceeded
         );
         
         // Calculate transaction amounts
-        let total_price = listing.price_per_token
-            .checked_mul(token_amount)
-            .ok_or(MarketplaceError::ArithmeticError)?;
+        let total_price = quote_total(listing.price_per_token, token_amount, listing.mint_decimals)?;
         
-        let fee_amount = (total_price as u128)
-            .checked_mul(marketplace.fee as u128)
-            .ok_or(MarketplaceError::ArithmeticError)?
-            .checked_div(10000)
-            .ok_or(MarketplaceError::ArithmeticError)? as u64;
+        // The fee comes out of the seller's proceeds, so the seller's volume
+        // decides the tier
+        let mint_fee_config = load_mint_fee_config(&ctx.accounts.mint_fee_config)?;
+        let fees = fee_split(
+            marketplace,
+            mint_fee_config.as_ref(),
+            listing.quote_mint,
+            ctx.accounts.seller_volume.volume,
+            total_price,
+        )?;
+        let FeeSplit {
+            fee,
+            fee_amount,
+            seller_amount,
+            royalty_recipient,
+            royalty_amount,
+            ..
+        } = fees;
+        
+        // The referrer is always named by `referrer`, whatever the quote mint;
+        // SPL trades pay it through a quote account it owns
+        let referrer = match (&ctx.accounts.referrer, &ctx.accounts.referrer_quote_account) {
+            (Some(referrer), _) => referrer.key(),
+            (None, Some(_)) => return err!(MarketplaceError::MissingFeeAccounts),
+            (None, None) => Pubkey::default(),
+        };
+        require!(
+            referrer != ctx.accounts.buyer.key() && referrer != listing.seller,
+            MarketplaceError::InvalidReferrer
+        );
+        let referrer_share = if referrer == Pubkey::default() {
+            0
+        } else {
+            marketplace.referrer_fee_share
+        };
         
-        let seller_amount = total_price
-            .checked_sub(fee_amount)
+        // The referrer is paid out of the treasury's part of the fee
+        let referrer_amount = share_of(fee_amount, referrer_share)?;
+        let treasury_amount = fees.treasury_amount
+            .checked_sub(referrer_amount)
             .ok_or(MarketplaceError::ArithmeticError)?;
         
-        // Transfer tokens from seller to buyer
+        // Release tokens from escrow to buyer, signed by the listing PDA
+        let seller_key = listing.seller;
+        let index = listing.index.to_le_bytes();
+        let seeds = &[
+            b"listing".as_ref(),
+            seller_key.as_ref(),
+            index.as_ref(),
+            &[listing.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
         let token_accounts = Transfer {
-            from: ctx.accounts.seller_token_account.to_account_info(),
+            from: ctx.accounts.escrow_token_account.to_account_info(),
             to: ctx.accounts.buyer_token_account.to_account_info(),
-            authority: ctx.accounts.seller_authority.to_account_info(),
+            authority: listing.to_account_info(),
         };
         
-        let token_ctx = CpiContext::new(
+        let token_ctx = CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             token_accounts,
+            signer,
         );
         
         token::transfer(token_ctx, token_amount)?;
         
-        // Transfer funds from buyer to seller
-        if seller_amount > 0 {
-            let buyer_lamports = **ctx.accounts.buyer.to_account_info().lamports.borrow();
+        if listing.quote_mint == native_mint::ID {
             require!(
-                buyer_lamports >= seller_amount,
+                ctx.accounts.buyer.lamports() >= total_price,
                 MarketplaceError::InsufficientFunds
             );
             
-            // Transfer to seller
-            **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? -= seller_amount;
-            **ctx.accounts.seller.to_account_info().try_borrow_mut_lamports()? += seller_amount;
-        }
-        
-        // Transfer fee to marketplace treasury if applicable
-        if fee_amount > 0 {
-            let buyer_lamports = **ctx.accounts.buyer.to_account_info().lamports.borrow();
+            // The buyer is a system account, so its lamports can only be moved
+            // by the system program
+            if seller_amount > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: ctx.accounts.seller.to_account_info(),
+                        },
+                    ),
+                    seller_amount,
+                )?;
+            }
+            
+            // Transfer the treasury's part of the fee if applicable
+            if treasury_amount > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: ctx.accounts.marketplace_treasury.to_account_info(),
+                        },
+                    ),
+                    treasury_amount,
+                )?;
+            }
+            
+            if royalty_amount > 0 {
+                let royalty_account = royalty_account(ctx.accounts.royalty_recipient.as_ref(), royalty_recipient)?;
+                
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: royalty_account.to_account_info(),
+                        },
+                    ),
+                    royalty_amount,
+                )?;
+            }
+            
+            if referrer_amount > 0 {
+                let referrer_account = ctx.accounts.referrer.as_ref()
+                    .ok_or(MarketplaceError::MissingFeeAccounts)?;
+                
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: referrer_account.to_account_info(),
+                        },
+                    ),
+                    referrer_amount,
+                )?;
+            }
+        } else {
+            let buyer_quote_account = ctx.accounts.buyer_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let seller_quote_account = ctx.accounts.seller_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let treasury_quote_account = ctx.accounts.treasury_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            require!(
+                buyer_quote_account.mint == listing.quote_mint
+                    && seller_quote_account.mint == listing.quote_mint
+                    && treasury_quote_account.mint == listing.quote_mint,
+                MarketplaceError::InvalidQuoteAccount
+            );
+            
             require!(
-                buyer_lamports >= fee_amount,
+                buyer_quote_account.amount >= total_price,
                 MarketplaceError::InsufficientFunds
             );
             
-            // Transfer to treasury
-            **ctx.accounts.buyer.to_account_info().try_borrow_mut_lamports()? -= fee_amount;
-            **ctx.accounts.marketplace_treasury.to_account_info().try_borrow_mut_lamports()? += fee_amount;
+            if seller_amount > 0 {
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: seller_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, seller_amount)?;
+            }
+            
+            if treasury_amount > 0 {
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: treasury_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, treasury_amount)?;
+            }
+            
+            if royalty_amount > 0 {
+                let royalty_quote_account = royalty_quote_account(
+                    ctx.accounts.royalty_quote_account.as_ref(),
+                    royalty_recipient,
+                    listing.quote_mint,
+                )?;
+                
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: royalty_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, royalty_amount)?;
+            }
+            
+            if referrer_amount > 0 {
+                let referrer_quote_account = ctx.accounts.referrer_quote_account.as_ref()
+                    .ok_or(MarketplaceError::MissingFeeAccounts)?;
+                require!(
+                    referrer_quote_account.owner == referrer
+                        && referrer_quote_account.mint == listing.quote_mint,
+                    MarketplaceError::InvalidFeeAccount
+                );
+                
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: referrer_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, referrer_amount)?;
+            }
         }
         
+        // Both sides of the trade count towards their volume tier
+        let now = Clock::get()?.unix_timestamp;
+        let fill = Fill {
+            mint: listing.mint,
+            quote_mint: listing.quote_mint,
+            mint_decimals: listing.mint_decimals,
+            token_amount,
+            total_price,
+            price_per_token: listing.price_per_token,
+        };
+        record_trader_volume(
+            &mut ctx.accounts.buyer_volume,
+            ctx.accounts.buyer.key(),
+            listing.quote_mint,
+            *ctx.bumps.get("buyer_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            token_amount,
+            true,
+        )?;
+        record_trader_volume(
+            &mut ctx.accounts.seller_volume,
+            listing.seller,
+            listing.quote_mint,
+            *ctx.bumps.get("seller_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            token_amount,
+            false,
+        )?;
+        record_mint_trade(
+            &mut ctx.accounts.mint_stats,
+            &fill,
+            *ctx.bumps.get("mint_stats").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        record_price_observation(
+            &mut ctx.accounts.price_oracle,
+            &fill,
+            *ctx.bumps.get("price_oracle").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        
         // Update listing state
         listing.token_amount = listing.token_amount
             .checked_sub(token_amount)
@@ -130,6 +482,21 @@ pub mod marketplace {
         if listing.token_amount == 0 {
             listing.status = ListingStatus::Completed;
             
+            // The escrow is empty, so close it and refund its rent to the seller
+            let close_accounts = CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: listing.to_account_info(),
+            };
+            
+            let close_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                signer,
+            );
+            
+            token::close_account(close_ctx)?;
+            
             // Update marketplace stats
             marketplace.active_listings = marketplace.active_listings
                 .checked_sub(1)
@@ -144,9 +511,452 @@ pub mod marketplace {
         listing.updated_at = Clock::get()?.unix_timestamp;
         marketplace.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(TradeExecuted {
+            listing: listing.key(),
+            seller: listing.seller,
+            buyer: ctx.accounts.buyer.key(),
+            mint: listing.mint,
+            quote_mint: listing.quote_mint,
+            token_amount,
+            price_per_token: listing.price_per_token,
+            total_price,
+            fee_amount,
+            seller_amount,
+            remaining_amount: listing.token_amount,
+            timestamp: listing.updated_at,
+        });
+        
+        if fee_amount > 0 {
+            emit!(TradeFeeDistributed {
+                listing: listing.key(),
+                quote_mint: listing.quote_mint,
+                fee,
+                fee_amount,
+                treasury_amount,
+                royalty_recipient,
+                royalty_amount,
+                referrer,
+                referrer_amount,
+                timestamp: listing.updated_at,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Buys up to `token_amount` tokens of one property mint across several
+    /// listings, cheapest first, spending at most `max_total_price`. Each
+    /// listing is passed in the remaining accounts as `[listing, escrow,
+    /// seller, seller_volume]`, followed by the seller's quote account for
+    /// SPL listings.
+    /// Listings that are no longer active or have expired are skipped.
+    pub fn sweep_listings<'info>(
+        ctx: Context<'_, '_, '_, 'info, SweepListings<'info>>,
+        token_amount: u64,
+        max_total_price: u64,
+        min_token_amount: u64,
+    ) -> Result<()> {
+        let buyer_key = ctx.accounts.buyer.key();
+        let mint = ctx.accounts.property_token_mint.key();
+        let quote_mint = ctx.accounts.quote_mint.key();
+        let is_sol = quote_mint == native_mint::ID;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require_trading(&ctx.accounts.marketplace, &mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &mint,
+        )?;
+        
+        require!(
+            token_amount > 0 && min_token_amount <= token_amount,
+            MarketplaceError::InvalidTradeLimits
+        );
+        
+        let group_len = if is_sol { 4 } else { 5 };
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.len() % group_len == 0,
+            MarketplaceError::InvalidSweepAccounts
+        );
+        
+        let mut fills: Vec<(Account<'info, Listing>, &[AccountInfo<'info>], usize)> =
+            Vec::with_capacity(ctx.remaining_accounts.len() / group_len);
+        let mut seller_volumes: Vec<Account<'info, TraderVolume>> = Vec::new();
+        for accounts in ctx.remaining_accounts.chunks(group_len) {
+            let listing = Account::<Listing>::try_from(&accounts[0])?;
+            require!(
+                listing.mint == mint
+                    && listing.quote_mint == quote_mint
+                    && accounts[1].key() == listing.escrow
+                    && accounts[2].key() == listing.seller,
+                MarketplaceError::InvalidSweepAccounts
+            );
+            require!(
+                listing.seller != buyer_key,
+                MarketplaceError::SelfTrade
+            );
+            // Passing a listing twice would sell its escrow twice
+            require!(
+                !fills.iter().any(|(filled, _, _)| filled.key() == listing.key()),
+                MarketplaceError::InvalidSweepAccounts
+            );
+            
+            // A seller with several listings passes the same volume account
+            // with each of them, but it is loaded once so no fill's update is
+            // overwritten when the copies are written back
+            let volume_index = match seller_volumes.iter().position(|loaded| loaded.key() == accounts[3].key()) {
+                Some(index) => index,
+                None => {
+                    // Only this program creates volume accounts, at the
+                    // trader's own address, so matching fields identify the
+                    // right one
+                    let seller_volume = Account::<TraderVolume>::try_from(&accounts[3])?;
+                    seller_volumes.push(seller_volume);
+                    seller_volumes.len() - 1
+                }
+            };
+            require!(
+                seller_volumes[volume_index].trader == listing.seller
+                    && seller_volumes[volume_index].quote_mint == quote_mint,
+                MarketplaceError::InvalidSweepAccounts
+            );
+            
+            if listing.status == ListingStatus::Active && !listing.is_expired(now) {
+                fills.push((listing, accounts, volume_index));
+            }
+        }
+        fills.sort_by_key(|(listing, _, _)| listing.price_per_token);
+        
+        // Sweeps pay the mint's fee to the treasury and royalty recipient,
+        // at each seller's volume tier. There are no referrals.
+        let mint_fee_config = load_mint_fee_config(&ctx.accounts.mint_fee_config)?;
+        let royalty_recipient = match mint_fee_config.as_ref() {
+            Some(config) => config.royalty_recipient,
+            None => Pubkey::default(),
+        };
+        
+        let buyer_volume_bump = *ctx.bumps.get("buyer_volume").ok_or(ErrorCode::ConstraintSeeds)?;
+        let mint_stats_bump = *ctx.bumps.get("mint_stats").ok_or(ErrorCode::ConstraintSeeds)?;
+        let price_oracle_bump = *ctx.bumps.get("price_oracle").ok_or(ErrorCode::ConstraintSeeds)?;
+        
+        let mut filled: u64 = 0;
+        let mut spent: u64 = 0;
+        let mut total_treasury: u64 = 0;
+        let mut total_royalty: u64 = 0;
+        let mut listings_filled: u32 = 0;
+        
+        for (listing, accounts, volume_index) in fills.iter_mut() {
+            let wanted = token_amount - filled;
+            let amount = wanted
+                .min(listing.token_amount)
+                .min(affordable_amount(listing.price_per_token, max_total_price - spent, listing.mint_decimals)?);
+            // Listings are sorted by price, so later ones cannot fit either
+            if amount == 0 {
+                break;
+            }
+            
+            let total_price = quote_total(listing.price_per_token, amount, listing.mint_decimals)?;
+            let FeeSplit {
+                fee,
+                fee_amount,
+                seller_amount,
+                treasury_amount,
+                royalty_amount,
+                ..
+            } = fee_split(
+                &ctx.accounts.marketplace,
+                mint_fee_config.as_ref(),
+                quote_mint,
+                seller_volumes[*volume_index].volume,
+                total_price,
+            )?;
+            
+            let seller_key = listing.seller;
+            let index = listing.index.to_le_bytes();
+            let seeds = &[
+                b"listing".as_ref(),
+                seller_key.as_ref(),
+                index.as_ref(),
+                &[listing.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let token_accounts = Transfer {
+                from: accounts[1].clone(),
+                to: ctx.accounts.buyer_token_account.to_account_info(),
+                authority: listing.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, amount)?;
+            
+            if seller_amount > 0 {
+                if is_sol {
+                    require!(
+                        ctx.accounts.buyer.lamports() >= total_price,
+                        MarketplaceError::InsufficientFunds
+                    );
+                    
+                    system_program::transfer(
+                        CpiContext::new(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::Transfer {
+                                from: ctx.accounts.buyer.to_account_info(),
+                                to: accounts[2].clone(),
+                            },
+                        ),
+                        seller_amount,
+                    )?;
+                } else {
+                    let buyer_quote_account = ctx.accounts.buyer_quote_account.as_ref()
+                        .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+                    let seller_quote_account = Account::<TokenAccount>::try_from(&accounts[4])?;
+                    require!(
+                        seller_quote_account.owner == seller_key
+                            && seller_quote_account.mint == quote_mint,
+                        MarketplaceError::InvalidQuoteAccount
+                    );
+                    
+                    let token_accounts = Transfer {
+                        from: buyer_quote_account.to_account_info(),
+                        to: seller_quote_account.to_account_info(),
+                        authority: ctx.accounts.buyer.to_account_info(),
+                    };
+                    
+                    let token_ctx = CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        token_accounts,
+                    );
+                    
+                    token::transfer(token_ctx, seller_amount)?;
+                }
+            }
+            
+            listing.token_amount = listing.token_amount
+                .checked_sub(amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            listing.updated_at = now;
+            
+            if listing.token_amount == 0 {
+                listing.status = ListingStatus::Completed;
+                
+                // The escrow is empty, so close it and refund its rent to the seller
+                let close_accounts = CloseAccount {
+                    account: accounts[1].clone(),
+                    destination: accounts[2].clone(),
+                    authority: listing.to_account_info(),
+                };
+                
+                let close_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    close_accounts,
+                    signer,
+                );
+                
+                token::close_account(close_ctx)?;
+                
+                let marketplace = &mut ctx.accounts.marketplace;
+                marketplace.active_listings = marketplace.active_listings
+                    .checked_sub(1)
+                    .ok_or(MarketplaceError::ArithmeticError)?;
+            }
+            
+            record_trader_volume(
+                &mut ctx.accounts.buyer_volume,
+                buyer_key,
+                quote_mint,
+                buyer_volume_bump,
+                total_price,
+                amount,
+                true,
+            )?;
+            let seller_volume = &mut seller_volumes[*volume_index];
+            let seller_volume_bump = seller_volume.bump;
+            record_trader_volume(
+                seller_volume,
+                seller_key,
+                quote_mint,
+                seller_volume_bump,
+                total_price,
+                amount,
+                false,
+            )?;
+            let fill = Fill {
+                mint,
+                quote_mint,
+                mint_decimals: listing.mint_decimals,
+                token_amount: amount,
+                total_price,
+                price_per_token: listing.price_per_token,
+            };
+            record_mint_trade(&mut ctx.accounts.mint_stats, &fill, mint_stats_bump, now)?;
+            record_price_observation(&mut ctx.accounts.price_oracle, &fill, price_oracle_bump, now)?;
+            
+            filled += amount;
+            spent = spent.checked_add(total_price)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            total_treasury = total_treasury.checked_add(treasury_amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            total_royalty = total_royalty.checked_add(royalty_amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            listings_filled += 1;
+            
+            emit!(TradeExecuted {
+                listing: listing.key(),
+                seller: seller_key,
+                buyer: buyer_key,
+                mint,
+                quote_mint,
+                token_amount: amount,
+                price_per_token: listing.price_per_token,
+                total_price,
+                fee_amount,
+                seller_amount,
+                remaining_amount: listing.token_amount,
+                timestamp: now,
+            });
+            
+            if fee_amount > 0 {
+                emit!(TradeFeeDistributed {
+                    listing: listing.key(),
+                    quote_mint,
+                    fee,
+                    fee_amount,
+                    treasury_amount,
+                    royalty_recipient,
+                    royalty_amount,
+                    referrer: Pubkey::default(),
+                    referrer_amount: 0,
+                    timestamp: now,
+                });
+            }
+        }
+        
+        require!(
+            filled > 0 && filled >= min_token_amount,
+            MarketplaceError::SlippageExceeded
+        );
+        
+        // Fees are collected once for the whole sweep
+        if is_sol {
+            if total_royalty > 0 {
+                require_keys_eq!(
+                    ctx.accounts.royalty_recipient.as_ref().map_or(Pubkey::default(), |account| account.key()),
+                    royalty_recipient,
+                    MarketplaceError::InvalidFeeAccount
+                );
+            }
+            
+            for (amount, destination) in [
+                (total_treasury, Some(ctx.accounts.marketplace_treasury.to_account_info())),
+                (total_royalty, ctx.accounts.royalty_recipient.as_ref().map(|account| account.to_account_info())),
+            ] {
+                if amount == 0 {
+                    continue;
+                }
+                let destination = destination.ok_or(MarketplaceError::MissingFeeAccounts)?;
+                
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: destination,
+                        },
+                    ),
+                    amount,
+                )?;
+            }
+        } else {
+            let buyer_quote_account = ctx.accounts.buyer_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            if total_royalty > 0 {
+                require_keys_eq!(
+                    ctx.accounts.royalty_quote_account.as_ref().map_or(Pubkey::default(), |account| account.owner),
+                    royalty_recipient,
+                    MarketplaceError::InvalidFeeAccount
+                );
+            }
+            
+            for (amount, destination) in [
+                (total_treasury, ctx.accounts.treasury_quote_account.as_ref()),
+                (total_royalty, ctx.accounts.royalty_quote_account.as_ref()),
+            ] {
+                if amount == 0 {
+                    continue;
+                }
+                let destination = destination.ok_or(MarketplaceError::MissingFeeAccounts)?;
+                require!(
+                    destination.mint == quote_mint,
+                    MarketplaceError::InvalidQuoteAccount
+                );
+                
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, amount)?;
+            }
+        }
+        
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.total_volume = marketplace.total_volume
+            .checked_add(spent)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        marketplace.updated_at = now;
+        
+        // Listings and seller volumes were loaded from remaining accounts,
+        // so Anchor will not write them back on its own
+        for (listing, _, _) in fills.iter() {
+            listing.exit(ctx.program_id)?;
+        }
+        for seller_volume in seller_volumes.iter() {
+            seller_volume.exit(ctx.program_id)?;
+        }
+        
+        emit!(ListingsSwept {
+            buyer: buyer_key,
+            mint,
+            quote_mint,
+            listings_filled,
+            token_amount: filled,
+            total_price: spent,
+            timestamp: now,
+        });
+        
         Ok(())
     }
 
+    /// Time-weighted average price of the mint's listing fills over the last
+    /// `window` seconds, for programs that read the oracle through CPI
+    pub fn get_twap(
+        ctx: Context<ReadPriceOracle>,
+        window: i64,
+    ) -> Result<u64> {
+        require!(window > 0, MarketplaceError::InvalidTwapWindow);
+        
+        let twap = ctx.accounts.price_oracle
+            .get_twap(Clock::get()?.unix_timestamp, window)
+            .ok_or(MarketplaceError::OracleHistoryTooShort)?;
+        
+        Ok(twap)
+    }
+
     pub fn cancel_listing(
         ctx: Context<CancelListing>,
     ) -> Result<()> {
@@ -165,6 +975,47 @@ pub mod marketplace {
             MarketplaceError::ListingNotActive
         );
         
+        // Return the unsold tokens and close the escrow, refunding its rent
+        let seller_key = listing.seller;
+        let index = listing.index.to_le_bytes();
+        let seeds = &[
+            b"listing".as_ref(),
+            seller_key.as_ref(),
+            index.as_ref(),
+            &[listing.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        if listing.token_amount > 0 {
+            let token_accounts = Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.seller_token_account.to_account_info(),
+                authority: listing.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, listing.token_amount)?;
+        }
+        
+        let close_accounts = CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        
+        let close_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            close_accounts,
+            signer,
+        );
+        
+        token::close_account(close_ctx)?;
+        
         // Update listing state
         listing.status = ListingStatus::Cancelled;
         listing.updated_at = Clock::get()?.unix_timestamp;
@@ -175,42 +1026,3083 @@ pub mod marketplace {
             .ok_or(MarketplaceError::ArithmeticError)?;
         marketplace.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(ListingCancelled {
+            listing: listing.key(),
+            seller: listing.seller,
+            mint: listing.mint,
+            remaining_amount: listing.token_amount,
+            timestamp: listing.updated_at,
+        });
+        
         Ok(())
     }
 
-    pub fn update_marketplace_fee(
+    /// Changes an active listing in place, keeping its address. A new token
+    /// amount moves the difference into or out of escrow.
+    pub fn update_listing(
+        ctx: Context<UpdateListing>,
+        price_per_token: Option<u64>,
+        token_amount: Option<u64>,
+        expires_at: Option<i64>, // Some(0) removes the expiry
+    ) -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        
+        require!(
+            listing.status == ListingStatus::Active,
+            MarketplaceError::ListingNotActive
+        );
+        
+        let previous_price_per_token = listing.price_per_token;
+        
+        if let Some(price_per_token) = price_per_token {
+            listing.price_per_token = price_per_token;
+        }
+        
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at == 0 || expires_at > Clock::get()?.unix_timestamp,
+                MarketplaceError::InvalidListingExpiry
+            );
+            listing.expires_at = expires_at;
+        }
+        
+        if let Some(token_amount) = token_amount {
+            // Withdrawing everything is a cancellation
+            require!(
+                token_amount > 0,
+                MarketplaceError::InsufficientTokenAmount
+            );
+            
+            if token_amount > listing.token_amount {
+                let token_accounts = Transfer {
+                    from: ctx.accounts.seller_token_account.to_account_info(),
+                    to: ctx.accounts.escrow_token_account.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, token_amount - listing.token_amount)?;
+            } else if token_amount < listing.token_amount {
+                let seller_key = listing.seller;
+                let index = listing.index.to_le_bytes();
+                let seeds = &[
+                    b"listing".as_ref(),
+                    seller_key.as_ref(),
+                    index.as_ref(),
+                    &[listing.bump],
+                ];
+                let signer = &[&seeds[..]];
+                
+                let token_accounts = Transfer {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    to: ctx.accounts.seller_token_account.to_account_info(),
+                    authority: listing.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    signer,
+                );
+                
+                token::transfer(token_ctx, listing.token_amount - token_amount)?;
+            }
+            
+            listing.token_amount = token_amount;
+        }
+        
+        listing.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(ListingUpdated {
+            listing: listing.key(),
+            seller: listing.seller,
+            mint: listing.mint,
+            previous_price_per_token,
+            price_per_token: listing.price_per_token,
+            token_amount: listing.token_amount,
+            expires_at: listing.expires_at,
+            timestamp: listing.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Permissionless cleanup: closes a completed, cancelled or expired
+    /// listing. Unsold tokens of an expired listing and all rent go back to
+    /// the seller.
+    pub fn close_listing(
+        ctx: Context<CloseListing>,
+    ) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        let marketplace = &mut ctx.accounts.marketplace;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let returned_amount = match listing.status {
+            ListingStatus::Completed | ListingStatus::Cancelled => 0,
+            ListingStatus::Active => {
+                require!(listing.is_expired(now), MarketplaceError::ListingStillActive);
+                
+                let escrow_token_account = ctx.accounts.escrow_token_account.as_ref()
+                    .ok_or(MarketplaceError::MissingEscrowAccounts)?;
+                let seller_token_account = ctx.accounts.seller_token_account.as_ref()
+                    .ok_or(MarketplaceError::MissingEscrowAccounts)?;
+                
+                let seller_key = listing.seller;
+                let index = listing.index.to_le_bytes();
+                let seeds = &[
+                    b"listing".as_ref(),
+                    seller_key.as_ref(),
+                    index.as_ref(),
+                    &[listing.bump],
+                ];
+                let signer = &[&seeds[..]];
+                
+                if listing.token_amount > 0 {
+                    let token_accounts = Transfer {
+                        from: escrow_token_account.to_account_info(),
+                        to: seller_token_account.to_account_info(),
+                        authority: listing.to_account_info(),
+                    };
+                    
+                    let token_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        token_accounts,
+                        signer,
+                    );
+                    
+                    token::transfer(token_ctx, listing.token_amount)?;
+                }
+                
+                let close_accounts = CloseAccount {
+                    account: escrow_token_account.to_account_info(),
+                    destination: ctx.accounts.seller.to_account_info(),
+                    authority: listing.to_account_info(),
+                };
+                
+                let close_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    close_accounts,
+                    signer,
+                );
+                
+                token::close_account(close_ctx)?;
+                
+                marketplace.active_listings = marketplace.active_listings
+                    .checked_sub(1)
+                    .ok_or(MarketplaceError::ArithmeticError)?;
+                marketplace.updated_at = now;
+                
+                listing.token_amount
+            }
+        };
+        
+        emit!(ListingClosed {
+            listing: listing.key(),
+            seller: listing.seller,
+            mint: listing.mint,
+            returned_amount,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    /// When enabled, listings, auctions and order books can only be created
+    /// for mints the registry has verified, and every order, bid, offer and
+    /// fill re-checks the verification
+    pub fn set_require_verified(
         ctx: Context<UpdateMarketplace>,
-        fee: u16,
+        require_verified: bool,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        marketplace.require_verified = require_verified;
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(VerificationRequirementUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            require_verified,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Stops new listings, auctions, orders and offers and every fill.
+    /// Cancels, refunds and withdrawals keep working while paused, and ended
+    /// auctions settle as unsold.
+    pub fn set_paused(
+        ctx: Context<UpdateMarketplace>,
+        paused: bool,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        marketplace.paused = paused;
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(MarketplacePauseUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            paused,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// First step of an authority handover; `accept_authority` completes it.
+    /// Proposing `Pubkey::default()` withdraws a pending proposal.
+    pub fn propose_authority(
+        ctx: Context<UpdateMarketplace>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        marketplace.pending_authority = new_authority;
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(AuthorityTransferProposed {
+            marketplace: marketplace.key(),
+            authority: marketplace.authority,
+            pending_authority: new_authority,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn accept_authority(
+        ctx: Context<AcceptAuthority>,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        let previous_authority = marketplace.authority;
+        marketplace.authority = ctx.accounts.new_authority.key();
+        marketplace.pending_authority = Pubkey::default();
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(AuthorityTransferred {
+            marketplace: marketplace.key(),
+            previous_authority,
+            authority: marketplace.authority,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Fees from later trades go to the new treasury, which must also own the
+    /// treasury quote accounts passed to SPL trades
+    pub fn update_treasury(
+        ctx: Context<UpdateMarketplace>,
+        treasury: Pubkey,
     ) -> Result<()> {
         let marketplace = &mut ctx.accounts.marketplace;
         
-        // Check that fee is reasonable (max 10%)
         require!(
-            fee <= 1000,
-            MarketplaceError::FeeTooHigh
+            treasury != Pubkey::default(),
+            MarketplaceError::InvalidTreasury
         );
         
-        marketplace.fee = fee;
+        let previous_treasury = marketplace.treasury;
+        marketplace.treasury = treasury;
         marketplace.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(TreasuryUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            previous_treasury,
+            treasury,
+            timestamp: marketplace.updated_at,
+        });
+        
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct InitializeMarketplace<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = Marketplace::LEN,
-        seeds = [b"marketplace"],
-        bump
-    )]
-    pub marketplace: Account<'info, Marketplace>,
-    
+    /// Freezes trading of one property mint, e.g. while it is under
+    /// investigation. Existing listings and orders can still be cancelled.
+    pub fn delist_mint(
+        ctx: Context<UpdateMarketplace>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        require!(
+            !marketplace.delisted_mints.contains(&mint),
+            MarketplaceError::MintAlreadyDelisted
+        );
+        
+        require!(
+            marketplace.delisted_mints.len() < Marketplace::MAX_DELISTED_MINTS,
+            MarketplaceError::TooManyDelistedMints
+        );
+        
+        marketplace.delisted_mints.push(mint);
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(MintDelistingUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            mint,
+            delisted: true,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn relist_mint(
+        ctx: Context<UpdateMarketplace>,
+        mint: Pubkey,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        let index = marketplace.delisted_mints.iter()
+            .position(|delisted| *delisted == mint)
+            .ok_or(MarketplaceError::MintNotDelisted)?;
+        
+        marketplace.delisted_mints.remove(index);
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(MintDelistingUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            mint,
+            delisted: false,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn update_marketplace_fee(
+        ctx: Context<UpdateMarketplace>,
+        fee: u16,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        // Check that fee is reasonable (max 10%)
+        require!(
+            fee <= 1000,
+            MarketplaceError::FeeTooHigh
+        );
+        
+        let previous_fee = marketplace.fee;
+        marketplace.fee = fee;
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(MarketplaceFeeUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            previous_fee,
+            fee: marketplace.fee,
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Replaces the volume-based fee tiers. A trade uses the lowest tier fee
+    /// whose `min_volume` the seller has reached in the trade's quote mint.
+    pub fn set_fee_tiers(
+        ctx: Context<UpdateMarketplace>,
+        fee_tiers: Vec<FeeTier>,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        require!(
+            fee_tiers.len() <= Marketplace::MAX_FEE_TIERS,
+            MarketplaceError::TooManyFeeTiers
+        );
+        
+        require!(
+            fee_tiers.iter().all(|tier| tier.fee <= 1000),
+            MarketplaceError::FeeTooHigh
+        );
+        
+        marketplace.fee_tiers = fee_tiers;
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(FeeScheduleUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            referrer_fee_share: marketplace.referrer_fee_share,
+            fee_tiers: marketplace.fee_tiers.clone(),
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn set_referrer_fee_share(
+        ctx: Context<UpdateMarketplace>,
+        referrer_fee_share: u16, // in basis points of the fee
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        require!(
+            referrer_fee_share <= MAX_FEE_SHARE,
+            MarketplaceError::FeeShareTooHigh
+        );
+        
+        marketplace.referrer_fee_share = referrer_fee_share;
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(FeeScheduleUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            referrer_fee_share: marketplace.referrer_fee_share,
+            fee_tiers: marketplace.fee_tiers.clone(),
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Overrides the marketplace fee for one property mint and sends
+    /// `royalty_share` of each fee to `royalty_recipient`, usually the
+    /// property owner.
+    pub fn set_mint_fee_config(
+        ctx: Context<SetMintFeeConfig>,
+        fee: u16,
+        royalty_recipient: Pubkey,
+        royalty_share: u16, // in basis points of the fee
+    ) -> Result<()> {
+        require!(
+            fee <= 1000,
+            MarketplaceError::FeeTooHigh
+        );
+        
+        require!(
+            royalty_share <= MAX_FEE_SHARE,
+            MarketplaceError::FeeShareTooHigh
+        );
+        
+        let mint_fee_config = &mut ctx.accounts.mint_fee_config;
+        mint_fee_config.mint = ctx.accounts.property_token_mint.key();
+        mint_fee_config.fee = fee;
+        mint_fee_config.royalty_recipient = royalty_recipient;
+        mint_fee_config.royalty_share = royalty_share;
+        mint_fee_config.bump = *ctx.bumps.get("mint_fee_config")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        mint_fee_config.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(MintFeeConfigUpdated {
+            mint: mint_fee_config.mint,
+            fee,
+            royalty_recipient,
+            royalty_share,
+            timestamp: mint_fee_config.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn remove_mint_fee_config(
+        ctx: Context<RemoveMintFeeConfig>,
+    ) -> Result<()> {
+        emit!(MintFeeConfigRemoved {
+            mint: ctx.accounts.mint_fee_config.mint,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn add_quote_mint(
+        ctx: Context<UpdateMarketplace>,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        require!(
+            quote_mint != native_mint::ID
+                && !marketplace.allowed_quote_mints.contains(&quote_mint),
+            MarketplaceError::QuoteMintAlreadyAllowed
+        );
+        
+        require!(
+            marketplace.allowed_quote_mints.len() < Marketplace::MAX_QUOTE_MINTS,
+            MarketplaceError::TooManyQuoteMints
+        );
+        
+        marketplace.allowed_quote_mints.push(quote_mint);
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(QuoteMintsUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            allowed_quote_mints: marketplace.allowed_quote_mints.clone(),
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn remove_quote_mint(
+        ctx: Context<UpdateMarketplace>,
+        quote_mint: Pubkey,
+    ) -> Result<()> {
+        let marketplace = &mut ctx.accounts.marketplace;
+        
+        // Existing listings keep settling in their quote mint; only new
+        // listings are affected
+        let index = marketplace.allowed_quote_mints.iter()
+            .position(|mint| *mint == quote_mint)
+            .ok_or(MarketplaceError::QuoteMintNotAllowed)?;
+        
+        marketplace.allowed_quote_mints.remove(index);
+        marketplace.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(QuoteMintsUpdated {
+            marketplace: marketplace.key(),
+            authority: ctx.accounts.authority.key(),
+            allowed_quote_mints: marketplace.allowed_quote_mints.clone(),
+            timestamp: marketplace.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn create_order_book(
+        ctx: Context<CreateOrderBook>,
+    ) -> Result<()> {
+        require_trading(&ctx.accounts.marketplace, &ctx.accounts.property_token_mint.key())?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &ctx.accounts.property_token_mint.key(),
+        )?;
+        
+        let quote_mint = ctx.accounts.quote_mint.key();
+        require!(
+            quote_mint == native_mint::ID
+                || ctx.accounts.marketplace.allowed_quote_mints.contains(&quote_mint),
+            MarketplaceError::QuoteMintNotAllowed
+        );
+        
+        let order_book = &mut ctx.accounts.order_book;
+        order_book.mint = ctx.accounts.property_token_mint.key();
+        order_book.mint_decimals = ctx.accounts.property_token_mint.decimals;
+        order_book.quote_mint = quote_mint;
+        order_book.base_vault = ctx.accounts.base_vault.key();
+        order_book.quote_vault = ctx.accounts.quote_vault.key();
+        order_book.next_order_id = 0;
+        order_book.bids = Vec::new();
+        order_book.asks = Vec::new();
+        order_book.bump = *ctx.bumps.get("order_book")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        order_book.created_at = Clock::get()?.unix_timestamp;
+        order_book.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OrderBookCreated {
+            order_book: order_book.key(),
+            mint: order_book.mint,
+            quote_mint: order_book.quote_mint,
+            timestamp: order_book.created_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn place_order(
+        ctx: Context<PlaceOrder>,
+        side: OrderSide,
+        price: u64, // quote base units per whole property token
+        quantity: u64,
+    ) -> Result<()> {
+        require_trading(&ctx.accounts.marketplace, &ctx.accounts.order_book.mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &ctx.accounts.order_book.mint,
+        )?;
+        
+        require!(price > 0, MarketplaceError::InvalidOrder);
+        
+        // Each side has a fixed number of slots, so an order has to be worth
+        // at least one whole token to take one
+        let mint_decimals = ctx.accounts.order_book.mint_decimals;
+        let min_quantity = 10u64
+            .checked_pow(mint_decimals as u32)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        require!(quantity >= min_quantity, MarketplaceError::OrderTooSmall);
+        
+        // The evicted order's owner is credited through this account, which
+        // would overwrite the placer's own open orders if it were the same
+        if let Some(evicted_open_orders) = ctx.accounts.evicted_open_orders.as_ref() {
+            require!(
+                evicted_open_orders.order_book == ctx.accounts.order_book.key()
+                    && evicted_open_orders.key() != ctx.accounts.open_orders.key(),
+                MarketplaceError::InvalidOpenOrders
+            );
+        }
+        
+        // Asks escrow the tokens for sale, bids escrow the full quote amount
+        let (vault, amount) = match side {
+            OrderSide::Ask => (ctx.accounts.base_vault.to_account_info(), quantity),
+            OrderSide::Bid => (
+                ctx.accounts.quote_vault.to_account_info(),
+                quote_total(price, quantity, mint_decimals)?,
+            ),
+        };
+        let expected_mint = match side {
+            OrderSide::Ask => ctx.accounts.order_book.mint,
+            OrderSide::Bid => ctx.accounts.order_book.quote_mint,
+        };
+        require!(
+            ctx.accounts.owner_token_account.mint == expected_mint,
+            MarketplaceError::InvalidOrder
+        );
+        
+        let token_accounts = Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: vault,
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        
+        let token_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_accounts,
+        );
+        
+        token::transfer(token_ctx, amount)?;
+        
+        let open_orders = &mut ctx.accounts.open_orders;
+        if open_orders.owner == Pubkey::default() {
+            open_orders.order_book = ctx.accounts.order_book.key();
+            open_orders.owner = ctx.accounts.owner.key();
+            open_orders.bump = *ctx.bumps.get("open_orders")
+                .ok_or(ErrorCode::ConstraintSeeds)?;
+            open_orders.created_at = Clock::get()?.unix_timestamp;
+        }
+        open_orders.updated_at = Clock::get()?.unix_timestamp;
+        
+        // Matches are cranked without the owner's signature, so the volume
+        // account they are credited to has to exist before the order fills
+        let trader_volume = &mut ctx.accounts.trader_volume;
+        if trader_volume.trader == Pubkey::default() {
+            trader_volume.trader = ctx.accounts.owner.key();
+            trader_volume.quote_mint = ctx.accounts.order_book.quote_mint;
+            trader_volume.bump = *ctx.bumps.get("trader_volume")
+                .ok_or(ErrorCode::ConstraintSeeds)?;
+            trader_volume.updated_at = Clock::get()?.unix_timestamp;
+        }
+        
+        let order_book = &mut ctx.accounts.order_book;
+        let order = Order {
+            order_id: order_book.next_order_id,
+            owner: ctx.accounts.owner.key(),
+            price,
+            quantity,
+            created_at: Clock::get()?.unix_timestamp,
+        };
+        order_book.next_order_id = order_book.next_order_id.checked_add(1)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        let evicted = insert_order(order_book.side_mut(side), order, side)?;
+        order_book.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OrderPlaced {
+            order_book: order_book.key(),
+            order_id: order.order_id,
+            owner: order.owner,
+            side,
+            price,
+            quantity,
+            timestamp: order.created_at,
+        });
+        
+        // A full side drops its worst order, which is refunded to its owner's
+        // open orders as if it had been cancelled
+        if let Some(evicted) = evicted {
+            let evicted_open_orders = if evicted.owner == ctx.accounts.owner.key() {
+                &mut ctx.accounts.open_orders
+            } else {
+                let evicted_open_orders = ctx.accounts.evicted_open_orders.as_mut()
+                    .ok_or(MarketplaceError::MissingEvictedOpenOrders)?;
+                require_keys_eq!(
+                    evicted_open_orders.owner,
+                    evicted.owner,
+                    MarketplaceError::MissingEvictedOpenOrders
+                );
+                evicted_open_orders
+            };
+            credit_order_escrow(evicted_open_orders, &evicted, side, mint_decimals)?;
+            
+            emit!(OrderCancelled {
+                order_book: ctx.accounts.order_book.key(),
+                order_id: evicted.order_id,
+                owner: evicted.owner,
+                side,
+                remaining_quantity: evicted.quantity,
+                timestamp: order.created_at,
+            });
+        }
+        
+        Ok(())
+    }
+
+    pub fn cancel_order(
+        ctx: Context<CancelOrder>,
+        side: OrderSide,
+        order_id: u64,
+    ) -> Result<()> {
+        let order_book = &mut ctx.accounts.order_book;
+        let mint_decimals = order_book.mint_decimals;
+        let orders = order_book.side_mut(side);
+        
+        let index = orders.iter()
+            .position(|order| order.order_id == order_id)
+            .ok_or(MarketplaceError::OrderNotFound)?;
+        require!(
+            orders[index].owner == ctx.accounts.owner.key(),
+            MarketplaceError::Unauthorized
+        );
+        let order = orders.remove(index);
+        
+        // The escrowed remainder is credited to the owner's open orders and
+        // withdrawn with `settle_funds`
+        credit_order_escrow(&mut ctx.accounts.open_orders, &order, side, mint_decimals)?;
+        order_book.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OrderCancelled {
+            order_book: order_book.key(),
+            order_id,
+            owner: order.owner,
+            side,
+            remaining_quantity: order.quantity,
+            timestamp: order_book.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    /// Crank: crosses the best bid and ask while they overlap, up to `limit`
+    /// fills. Every owner involved is passed once in remaining accounts as
+    /// `[open_orders, trader_volume]`; matching stops at the first owner
+    /// whose accounts are missing.
+    pub fn match_orders<'info>(
+        ctx: Context<'_, '_, '_, 'info, MatchOrders<'info>>,
+        limit: u8,
+    ) -> Result<()> {
+        require_trading(&ctx.accounts.marketplace, &ctx.accounts.order_book.mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &ctx.accounts.order_book.mint,
+        )?;
+        
+        let order_book_key = ctx.accounts.order_book.key();
+        let quote_mint = ctx.accounts.order_book.quote_mint;
+        
+        let traders = ctx.remaining_accounts.chunks_exact(2);
+        require!(
+            traders.remainder().is_empty(),
+            MarketplaceError::InvalidOpenOrders
+        );
+        
+        let mut open_orders = Vec::with_capacity(traders.len());
+        let mut trader_volumes = Vec::with_capacity(traders.len());
+        for accounts in traders {
+            let account = Account::<OpenOrders>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                account.order_book,
+                order_book_key,
+                MarketplaceError::InvalidOpenOrders
+            );
+            // Each copy is written back on exit, so a duplicate would
+            // overwrite the credits made through the first one
+            require!(
+                !open_orders.iter().any(|loaded: &Account<OpenOrders>| loaded.key() == account.key()),
+                MarketplaceError::InvalidOpenOrders
+            );
+            
+            // Only this program creates volume accounts, at the trader's own
+            // address, so matching fields identify the right one
+            let trader_volume = Account::<TraderVolume>::try_from(&accounts[1])?;
+            require!(
+                trader_volume.trader == account.owner && trader_volume.quote_mint == quote_mint,
+                MarketplaceError::InvalidOpenOrders
+            );
+            
+            open_orders.push(account);
+            trader_volumes.push(trader_volume);
+        }
+        
+        let mint_stats_bump = *ctx.bumps.get("mint_stats").ok_or(ErrorCode::ConstraintSeeds)?;
+        let price_oracle_bump = *ctx.bumps.get("price_oracle").ok_or(ErrorCode::ConstraintSeeds)?;
+        
+        let mint_fee_config = load_mint_fee_config(&ctx.accounts.mint_fee_config)?;
+        let royalty_recipient = match mint_fee_config.as_ref() {
+            Some(config) => config.royalty_recipient,
+            None => Pubkey::default(),
+        };
+        
+        let marketplace = &ctx.accounts.marketplace;
+        let order_book = &mut ctx.accounts.order_book;
+        let now = Clock::get()?.unix_timestamp;
+        let mut fills: u8 = 0;
+        let mut total_treasury: u64 = 0;
+        let mut total_royalty: u64 = 0;
+        let mut total_volume: u64 = 0;
+        
+        while fills < limit {
+            let (Some(bid), Some(ask)) = (order_book.bids.first().copied(), order_book.asks.first().copied()) else {
+                break;
+            };
+            if bid.price < ask.price {
+                break;
+            }
+            
+            let buyer_index = open_orders.iter().position(|account| account.owner == bid.owner);
+            let seller_index = open_orders.iter().position(|account| account.owner == ask.owner);
+            let (Some(buyer_index), Some(seller_index)) = (buyer_index, seller_index) else {
+                break;
+            };
+            
+            // Price-time priority: the order that rested first sets the price
+            let price = if bid.order_id < ask.order_id { bid.price } else { ask.price };
+            let quantity = bid.quantity.min(ask.quantity);
+            
+            // The bid escrowed its total at its own price, rounded up once, so
+            // a fill releases the difference between that total before and
+            // after it. The seller is paid at most what was released, which
+            // rounding can leave one base unit under the fill's own total.
+            let released = quote_total(bid.price, bid.quantity, order_book.mint_decimals)?
+                .checked_sub(quote_total(bid.price, bid.quantity - quantity, order_book.mint_decimals)?)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            let quote_amount = quote_total(price, quantity, order_book.mint_decimals)?
+                .min(released);
+            let FeeSplit {
+                fee,
+                fee_amount,
+                seller_amount,
+                treasury_amount,
+                royalty_amount,
+                ..
+            } = fee_split(
+                marketplace,
+                mint_fee_config.as_ref(),
+                quote_mint,
+                trader_volumes[seller_index].volume,
+                quote_amount,
+            )?;
+            // The bid escrowed its own price, so it gets the improvement back
+            let refund = released - quote_amount;
+            
+            let buyer = &mut open_orders[buyer_index];
+            buyer.base_free = buyer.base_free.checked_add(quantity)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            buyer.quote_free = buyer.quote_free.checked_add(refund)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            buyer.updated_at = now;
+            
+            let seller = &mut open_orders[seller_index];
+            seller.quote_free = seller.quote_free
+                .checked_add(seller_amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            seller.updated_at = now;
+            
+            order_book.bids[0].quantity -= quantity;
+            if order_book.bids[0].quantity == 0 {
+                order_book.bids.remove(0);
+            }
+            order_book.asks[0].quantity -= quantity;
+            if order_book.asks[0].quantity == 0 {
+                order_book.asks.remove(0);
+            }
+            
+            // Crossing your own orders moves nothing, so it does not count
+            // towards a volume tier
+            if buyer_index != seller_index {
+                let buyer_bump = trader_volumes[buyer_index].bump;
+                let seller_bump = trader_volumes[seller_index].bump;
+                record_trader_volume(
+                    &mut trader_volumes[buyer_index],
+                    bid.owner,
+                    quote_mint,
+                    buyer_bump,
+                    quote_amount,
+                    quantity,
+                    true,
+                )?;
+                record_trader_volume(
+                    &mut trader_volumes[seller_index],
+                    ask.owner,
+                    quote_mint,
+                    seller_bump,
+                    quote_amount,
+                    quantity,
+                    false,
+                )?;
+            }
+            let fill = Fill {
+                mint: order_book.mint,
+                quote_mint,
+                mint_decimals: order_book.mint_decimals,
+                token_amount: quantity,
+                total_price: quote_amount,
+                price_per_token: price,
+            };
+            record_mint_trade(&mut ctx.accounts.mint_stats, &fill, mint_stats_bump, now)?;
+            record_price_observation(&mut ctx.accounts.price_oracle, &fill, price_oracle_bump, now)?;
+            
+            total_treasury = total_treasury.checked_add(treasury_amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            total_royalty = total_royalty.checked_add(royalty_amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            total_volume = total_volume.checked_add(quote_amount)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+            fills += 1;
+            
+            emit!(OrdersMatched {
+                order_book: order_book_key,
+                bid_id: bid.order_id,
+                ask_id: ask.order_id,
+                buyer: bid.owner,
+                seller: ask.owner,
+                price,
+                quantity,
+                fee_amount,
+                timestamp: now,
+            });
+            
+            if fee_amount > 0 {
+                emit!(TradeFeeDistributed {
+                    listing: order_book_key,
+                    quote_mint: order_book.quote_mint,
+                    fee,
+                    fee_amount,
+                    treasury_amount,
+                    royalty_recipient,
+                    royalty_amount,
+                    referrer: Pubkey::default(),
+                    referrer_amount: 0,
+                    timestamp: now,
+                });
+            }
+        }
+        
+        // Fees leave the quote vault once for the whole batch
+        let mint_key = order_book.mint;
+        let quote_mint_key = order_book.quote_mint;
+        let seeds = &[
+            b"order_book".as_ref(),
+            mint_key.as_ref(),
+            quote_mint_key.as_ref(),
+            &[order_book.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        if total_treasury > 0 {
+            let token_accounts = Transfer {
+                from: ctx.accounts.quote_vault.to_account_info(),
+                to: ctx.accounts.treasury_quote_account.to_account_info(),
+                authority: order_book.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, total_treasury)?;
+        }
+        
+        if total_royalty > 0 {
+            let royalty_quote_account = royalty_quote_account(
+                ctx.accounts.royalty_quote_account.as_ref(),
+                royalty_recipient,
+                quote_mint_key,
+            )?;
+            
+            let token_accounts = Transfer {
+                from: ctx.accounts.quote_vault.to_account_info(),
+                to: royalty_quote_account.to_account_info(),
+                authority: order_book.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, total_royalty)?;
+        }
+        
+        order_book.updated_at = now;
+        
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.total_volume = marketplace.total_volume.checked_add(total_volume)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        marketplace.updated_at = now;
+        
+        // Open orders and volumes were loaded from remaining accounts, so
+        // Anchor will not write them back on its own
+        for account in open_orders.iter() {
+            account.exit(ctx.program_id)?;
+        }
+        for account in trader_volumes.iter() {
+            account.exit(ctx.program_id)?;
+        }
+        
+        Ok(())
+    }
+
+    pub fn settle_funds(
+        ctx: Context<SettleFunds>,
+    ) -> Result<()> {
+        let order_book = &ctx.accounts.order_book;
+        let open_orders = &mut ctx.accounts.open_orders;
+        
+        let mint_key = order_book.mint;
+        let quote_mint_key = order_book.quote_mint;
+        let seeds = &[
+            b"order_book".as_ref(),
+            mint_key.as_ref(),
+            quote_mint_key.as_ref(),
+            &[order_book.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let base_amount = open_orders.base_free;
+        if base_amount > 0 {
+            let token_accounts = Transfer {
+                from: ctx.accounts.base_vault.to_account_info(),
+                to: ctx.accounts.owner_base_account.to_account_info(),
+                authority: order_book.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, base_amount)?;
+        }
+        
+        let quote_amount = open_orders.quote_free;
+        if quote_amount > 0 {
+            let token_accounts = Transfer {
+                from: ctx.accounts.quote_vault.to_account_info(),
+                to: ctx.accounts.owner_quote_account.to_account_info(),
+                authority: order_book.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, quote_amount)?;
+        }
+        
+        open_orders.base_free = 0;
+        open_orders.quote_free = 0;
+        open_orders.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(FundsSettled {
+            order_book: order_book.key(),
+            owner: open_orders.owner,
+            base_amount,
+            quote_amount,
+            timestamp: open_orders.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_open_orders(
+        ctx: Context<CloseOpenOrders>,
+    ) -> Result<()> {
+        let order_book = &ctx.accounts.order_book;
+        let open_orders = &ctx.accounts.open_orders;
+        
+        require!(
+            open_orders.base_free == 0 && open_orders.quote_free == 0,
+            MarketplaceError::UnsettledFunds
+        );
+        
+        // Fills are credited to the owner's open orders account, so it has
+        // to outlive their resting orders
+        let owner = open_orders.owner;
+        require!(
+            !order_book.bids.iter().chain(order_book.asks.iter()).any(|order| order.owner == owner),
+            MarketplaceError::OrdersStillResting
+        );
+        
+        emit!(OpenOrdersClosed {
+            order_book: order_book.key(),
+            owner,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        kind: AuctionKind,
+        token_amount: u64,
+        start_price: u64, // for the whole lot, in quote base units
+        start_time: i64,
+        end_time: i64,
+    ) -> Result<()> {
+        require_trading(&ctx.accounts.marketplace, &ctx.accounts.property_token_mint.key())?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &ctx.accounts.property_token_mint.key(),
+        )?;
+        
+        require!(
+            token_amount > 0,
+            MarketplaceError::InsufficientTokenAmount
+        );
+        
+        require!(
+            start_price > 0
+                && end_time > start_time
+                && end_time > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidAuctionConfig
+        );
+        
+        match kind {
+            AuctionKind::English { min_increment, extension, .. } => {
+                require!(
+                    min_increment > 0 && extension >= 0,
+                    MarketplaceError::InvalidAuctionConfig
+                );
+            }
+            AuctionKind::Dutch { end_price } => {
+                require!(
+                    end_price <= start_price,
+                    MarketplaceError::InvalidAuctionConfig
+                );
+            }
+        }
+        
+        let quote_mint = ctx.accounts.quote_mint.key();
+        require!(
+            quote_mint == native_mint::ID
+                || ctx.accounts.marketplace.allowed_quote_mints.contains(&quote_mint),
+            MarketplaceError::QuoteMintNotAllowed
+        );
+        
+        // English bids in an SPL mint are escrowed in the auction's quote vault
+        let quote_vault = match (&kind, quote_mint == native_mint::ID) {
+            (AuctionKind::English { .. }, false) => ctx.accounts.quote_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?
+                .key(),
+            _ => {
+                require!(
+                    ctx.accounts.quote_vault.is_none(),
+                    MarketplaceError::InvalidQuoteAccount
+                );
+                Pubkey::default()
+            }
+        };
+        
+        // Move the lot into the auction's escrow
+        let token_accounts = Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        };
+        
+        let token_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            token_accounts,
+        );
+        
+        token::transfer(token_ctx, token_amount)?;
+        
+        // Auctions share the seller's listing counter
+        let seller_listings = &mut ctx.accounts.seller_listings;
+        if seller_listings.seller == Pubkey::default() {
+            seller_listings.seller = ctx.accounts.seller.key();
+            seller_listings.bump = *ctx.bumps.get("seller_listings")
+                .ok_or(ErrorCode::ConstraintSeeds)?;
+        }
+        let index = seller_listings.listing_count;
+        seller_listings.listing_count = index.checked_add(1)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
+        let auction = &mut ctx.accounts.auction;
+        auction.seller = ctx.accounts.seller.key();
+        auction.index = index;
+        auction.mint = ctx.accounts.property_token_mint.key();
+        auction.mint_decimals = ctx.accounts.property_token_mint.decimals;
+        auction.quote_mint = quote_mint;
+        auction.escrow = ctx.accounts.escrow_token_account.key();
+        auction.quote_vault = quote_vault;
+        auction.token_amount = token_amount;
+        auction.kind = kind;
+        auction.start_price = start_price;
+        auction.start_time = start_time;
+        auction.end_time = end_time;
+        auction.highest_bidder = Pubkey::default();
+        auction.highest_bid = 0;
+        auction.open_bids = 0;
+        auction.status = AuctionStatus::Active;
+        auction.bump = *ctx.bumps.get("auction")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        auction.created_at = Clock::get()?.unix_timestamp;
+        auction.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(AuctionCreated {
+            auction: auction.key(),
+            seller: auction.seller,
+            index: auction.index,
+            mint: auction.mint,
+            quote_mint: auction.quote_mint,
+            kind: auction.kind,
+            token_amount: auction.token_amount,
+            start_price: auction.start_price,
+            start_time: auction.start_time,
+            end_time: auction.end_time,
+            timestamp: auction.created_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn place_bid(
+        ctx: Context<PlaceBid>,
+        amount: u64, // total bid for the lot, in quote base units
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require_trading(&ctx.accounts.marketplace, &auction.mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &auction.mint,
+        )?;
+        
+        let AuctionKind::English { min_increment, extension, .. } = auction.kind else {
+            return err!(MarketplaceError::WrongAuctionKind);
+        };
+        
+        require!(
+            auction.status == AuctionStatus::Active,
+            MarketplaceError::AuctionNotActive
+        );
+        require!(now >= auction.start_time, MarketplaceError::AuctionNotStarted);
+        require!(now < auction.end_time, MarketplaceError::AuctionEnded);
+        
+        let minimum_bid = if auction.highest_bidder == Pubkey::default() {
+            auction.start_price
+        } else {
+            auction.highest_bid.checked_add(min_increment)
+                .ok_or(MarketplaceError::ArithmeticError)?
+        };
+        require!(amount >= minimum_bid, MarketplaceError::BidTooLow);
+        
+        // A seller bidding on their own lot would only be shilling the price
+        require!(
+            ctx.accounts.bidder.key() != auction.seller,
+            MarketplaceError::SelfTrade
+        );
+        
+        let auction_bid = &mut ctx.accounts.auction_bid;
+        if auction_bid.bidder == Pubkey::default() {
+            auction_bid.auction = auction.key();
+            auction_bid.bidder = ctx.accounts.bidder.key();
+            auction_bid.bump = *ctx.bumps.get("auction_bid")
+                .ok_or(ErrorCode::ConstraintSeeds)?;
+            auction_bid.created_at = now;
+            
+            auction.open_bids = auction.open_bids.checked_add(1)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+        }
+        
+        // Raising an earlier bid only escrows the difference
+        let top_up = amount.checked_sub(auction_bid.amount)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
+        if auction.quote_mint == native_mint::ID {
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bidder.to_account_info(),
+                        to: auction_bid.to_account_info(),
+                    },
+                ),
+                top_up,
+            )?;
+        } else {
+            let bidder_quote_account = ctx.accounts.bidder_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let quote_vault = ctx.accounts.quote_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            let token_accounts = Transfer {
+                from: bidder_quote_account.to_account_info(),
+                to: quote_vault.to_account_info(),
+                authority: ctx.accounts.bidder.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+            );
+            
+            token::transfer(token_ctx, top_up)?;
+        }
+        
+        auction_bid.amount = amount;
+        auction_bid.updated_at = now;
+        
+        auction.highest_bidder = ctx.accounts.bidder.key();
+        auction.highest_bid = amount;
+        
+        // Anti-sniping: a late bid pushes the end back
+        if auction.end_time - now < extension {
+            auction.end_time = now.checked_add(extension)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+        }
+        auction.updated_at = now;
+        
+        emit!(AuctionBidPlaced {
+            auction: auction.key(),
+            bidder: auction.highest_bidder,
+            amount,
+            end_time: auction.end_time,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn refund_bid(
+        ctx: Context<RefundBid>,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let auction_bid = &ctx.accounts.auction_bid;
+        
+        // The leading bid stays escrowed unless the auction ended unsold
+        require!(
+            auction.highest_bidder != auction_bid.bidder
+                || auction.status == AuctionStatus::Unsold,
+            MarketplaceError::BidNotRefundable
+        );
+        
+        let amount = auction_bid.amount;
+        
+        // SOL bids are held on the bid account itself and are returned when
+        // it closes; SPL bids come back out of the quote vault
+        if auction.quote_mint != native_mint::ID {
+            let bidder_quote_account = ctx.accounts.bidder_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let quote_vault = ctx.accounts.quote_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            let seller_key = auction.seller;
+            let index = auction.index.to_le_bytes();
+            let seeds = &[
+                b"auction".as_ref(),
+                seller_key.as_ref(),
+                index.as_ref(),
+                &[auction.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let token_accounts = Transfer {
+                from: quote_vault.to_account_info(),
+                to: bidder_quote_account.to_account_info(),
+                authority: auction.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, amount)?;
+        }
+        
+        auction.open_bids = auction.open_bids.checked_sub(1)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
+        emit!(AuctionBidRefunded {
+            auction: auction.key(),
+            bidder: auction_bid.bidder,
+            amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn buy_dutch_auction(
+        ctx: Context<BuyDutchAuction>,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let marketplace = &mut ctx.accounts.marketplace;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require_trading(marketplace, &auction.mint)?;
+        require_verified_property(
+            marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &auction.mint,
+        )?;
+        
+        let AuctionKind::Dutch { end_price } = auction.kind else {
+            return err!(MarketplaceError::WrongAuctionKind);
+        };
+        
+        require!(
+            auction.status == AuctionStatus::Active,
+            MarketplaceError::AuctionNotActive
+        );
+        require!(now >= auction.start_time, MarketplaceError::AuctionNotStarted);
+        require!(now < auction.end_time, MarketplaceError::AuctionEnded);
+        
+        let total_price = dutch_price(
+            auction.start_price,
+            end_price,
+            auction.start_time,
+            auction.end_time,
+            now,
+        )?;
+        let mint_fee_config = load_mint_fee_config(&ctx.accounts.mint_fee_config)?;
+        let FeeSplit {
+            fee,
+            fee_amount,
+            seller_amount,
+            treasury_amount,
+            royalty_recipient,
+            royalty_amount,
+        } = fee_split(
+            marketplace,
+            mint_fee_config.as_ref(),
+            auction.quote_mint,
+            ctx.accounts.seller_volume.volume,
+            total_price,
+        )?;
+        
+        if auction.quote_mint == native_mint::ID {
+            require!(
+                ctx.accounts.buyer.lamports() >= total_price,
+                MarketplaceError::InsufficientFunds
+            );
+            
+            if seller_amount > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: ctx.accounts.seller.to_account_info(),
+                        },
+                    ),
+                    seller_amount,
+                )?;
+            }
+            
+            if treasury_amount > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: ctx.accounts.marketplace_treasury.to_account_info(),
+                        },
+                    ),
+                    treasury_amount,
+                )?;
+            }
+            
+            if royalty_amount > 0 {
+                let royalty_account = royalty_account(ctx.accounts.royalty_recipient.as_ref(), royalty_recipient)?;
+                
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.buyer.to_account_info(),
+                            to: royalty_account.to_account_info(),
+                        },
+                    ),
+                    royalty_amount,
+                )?;
+            }
+        } else {
+            let buyer_quote_account = ctx.accounts.buyer_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let seller_quote_account = ctx.accounts.seller_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let treasury_quote_account = ctx.accounts.treasury_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            require!(
+                buyer_quote_account.mint == auction.quote_mint
+                    && seller_quote_account.mint == auction.quote_mint
+                    && treasury_quote_account.mint == auction.quote_mint,
+                MarketplaceError::InvalidQuoteAccount
+            );
+            
+            require!(
+                buyer_quote_account.amount >= total_price,
+                MarketplaceError::InsufficientFunds
+            );
+            
+            if seller_amount > 0 {
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: seller_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, seller_amount)?;
+            }
+            
+            if treasury_amount > 0 {
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: treasury_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, treasury_amount)?;
+            }
+            
+            if royalty_amount > 0 {
+                let royalty_quote_account = royalty_quote_account(
+                    ctx.accounts.royalty_quote_account.as_ref(),
+                    royalty_recipient,
+                    auction.quote_mint,
+                )?;
+                
+                let token_accounts = Transfer {
+                    from: buyer_quote_account.to_account_info(),
+                    to: royalty_quote_account.to_account_info(),
+                    authority: ctx.accounts.buyer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                );
+                
+                token::transfer(token_ctx, royalty_amount)?;
+            }
+        }
+        
+        let seller_key = auction.seller;
+        let index = auction.index.to_le_bytes();
+        let seeds = &[
+            b"auction".as_ref(),
+            seller_key.as_ref(),
+            index.as_ref(),
+            &[auction.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        release_auction_lot(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.buyer_token_account,
+            &ctx.accounts.seller,
+            auction,
+            signer,
+        )?;
+        
+        auction.highest_bidder = ctx.accounts.buyer.key();
+        auction.highest_bid = total_price;
+        auction.status = AuctionStatus::Settled;
+        auction.updated_at = now;
+        
+        record_trader_volume(
+            &mut ctx.accounts.buyer_volume,
+            ctx.accounts.buyer.key(),
+            auction.quote_mint,
+            *ctx.bumps.get("buyer_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            auction.token_amount,
+            true,
+        )?;
+        record_trader_volume(
+            &mut ctx.accounts.seller_volume,
+            auction.seller,
+            auction.quote_mint,
+            *ctx.bumps.get("seller_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            auction.token_amount,
+            false,
+        )?;
+        let fill = Fill::at_total(
+            auction.mint,
+            auction.quote_mint,
+            auction.mint_decimals,
+            auction.token_amount,
+            total_price,
+        )?;
+        record_mint_trade(
+            &mut ctx.accounts.mint_stats,
+            &fill,
+            *ctx.bumps.get("mint_stats").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        record_price_observation(
+            &mut ctx.accounts.price_oracle,
+            &fill,
+            *ctx.bumps.get("price_oracle").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        
+        marketplace.total_volume = marketplace.total_volume
+            .checked_add(total_price)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        marketplace.updated_at = now;
+        
+        emit!(AuctionSettled {
+            auction: auction.key(),
+            seller: auction.seller,
+            buyer: auction.highest_bidder,
+            mint: auction.mint,
+            quote_mint: auction.quote_mint,
+            token_amount: auction.token_amount,
+            price: total_price,
+            fee_amount,
+            seller_amount,
+            timestamp: now,
+        });
+        
+        if fee_amount > 0 {
+            emit!(TradeFeeDistributed {
+                listing: auction.key(),
+                quote_mint: auction.quote_mint,
+                fee,
+                fee_amount,
+                treasury_amount,
+                royalty_recipient,
+                royalty_amount,
+                referrer: Pubkey::default(),
+                referrer_amount: 0,
+                timestamp: now,
+            });
+        }
+        
+        Ok(())
+    }
+
+    /// Ends an auction once its time is up. An English auction whose highest
+    /// bid meets the reserve is paid out from the winner's escrowed bid;
+    /// otherwise the lot goes back to the seller and bidders can refund.
+    /// While trading is paused, the mint is delisted or it has lost its
+    /// required verification, every auction ends unsold.
+    pub fn settle_auction(
+        ctx: Context<SettleAuction>,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        let marketplace = &mut ctx.accounts.marketplace;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            auction.status == AuctionStatus::Active,
+            MarketplaceError::AuctionNotActive
+        );
+        require!(now >= auction.end_time, MarketplaceError::AuctionStillRunning);
+        
+        let reserve_met = match auction.kind {
+            AuctionKind::English { reserve_price, .. } => {
+                auction.highest_bidder != Pubkey::default()
+                    && auction.highest_bid >= reserve_price
+            }
+            AuctionKind::Dutch { .. } => false,
+        };
+        
+        // A sale is a fill and needs trading to be open. Returning the lot
+        // is not, so a pause, delisting or lapsed verification cannot lock up
+        // the lot or the leading bid, which becomes refundable once the
+        // auction is unsold.
+        let trading = !marketplace.paused && !marketplace.delisted_mints.contains(&auction.mint);
+        let sold = reserve_met
+            && trading
+            && property_verified(
+                marketplace,
+                ctx.accounts.property_record.as_deref(),
+                &auction.mint,
+            )?;
+        
+        let seller_key = auction.seller;
+        let index = auction.index.to_le_bytes();
+        let seeds = &[
+            b"auction".as_ref(),
+            seller_key.as_ref(),
+            index.as_ref(),
+            &[auction.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        if !sold {
+            let seller_token_account = ctx.accounts.seller_token_account.as_ref()
+                .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+            
+            release_auction_lot(
+                &ctx.accounts.token_program,
+                &ctx.accounts.escrow_token_account,
+                seller_token_account,
+                &ctx.accounts.seller,
+                auction,
+                signer,
+            )?;
+            
+            auction.status = AuctionStatus::Unsold;
+            auction.updated_at = now;
+            
+            emit!(AuctionCancelled {
+                auction: auction.key(),
+                seller: auction.seller,
+                mint: auction.mint,
+                token_amount: auction.token_amount,
+                status: auction.status,
+                timestamp: now,
+            });
+            
+            return Ok(());
+        }
+        
+        let winner = ctx.accounts.winner.as_ref()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        let winning_bid = ctx.accounts.winning_bid.as_ref()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        let winner_token_account = ctx.accounts.winner_token_account.as_ref()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        let winner_volume = ctx.accounts.winner_volume.as_mut()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        let seller_volume = ctx.accounts.seller_volume.as_mut()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        let mint_stats = ctx.accounts.mint_stats.as_mut()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        let price_oracle = ctx.accounts.price_oracle.as_mut()
+            .ok_or(MarketplaceError::MissingAuctionAccounts)?;
+        
+        let total_price = auction.highest_bid;
+        let mint_fee_config = load_mint_fee_config(&ctx.accounts.mint_fee_config)?;
+        let FeeSplit {
+            fee,
+            fee_amount,
+            seller_amount,
+            treasury_amount,
+            royalty_recipient,
+            royalty_amount,
+        } = fee_split(
+            marketplace,
+            mint_fee_config.as_ref(),
+            auction.quote_mint,
+            seller_volume.volume,
+            total_price,
+        )?;
+        
+        if auction.quote_mint == native_mint::ID {
+            // The winning bid's lamports sit on its bid account, which this
+            // program owns
+            let bid_info = winning_bid.to_account_info();
+            move_lamports(&bid_info, &ctx.accounts.seller, seller_amount)?;
+            move_lamports(&bid_info, &ctx.accounts.marketplace_treasury, treasury_amount)?;
+            
+            if royalty_amount > 0 {
+                let royalty_account = royalty_account(ctx.accounts.royalty_recipient.as_ref(), royalty_recipient)?;
+                move_lamports(&bid_info, royalty_account, royalty_amount)?;
+            }
+        } else {
+            let quote_vault = ctx.accounts.quote_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let seller_quote_account = ctx.accounts.seller_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let treasury_quote_account = ctx.accounts.treasury_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            require!(
+                seller_quote_account.mint == auction.quote_mint
+                    && treasury_quote_account.mint == auction.quote_mint,
+                MarketplaceError::InvalidQuoteAccount
+            );
+            
+            if seller_amount > 0 {
+                let token_accounts = Transfer {
+                    from: quote_vault.to_account_info(),
+                    to: seller_quote_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    signer,
+                );
+                
+                token::transfer(token_ctx, seller_amount)?;
+            }
+            
+            if treasury_amount > 0 {
+                let token_accounts = Transfer {
+                    from: quote_vault.to_account_info(),
+                    to: treasury_quote_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    signer,
+                );
+                
+                token::transfer(token_ctx, treasury_amount)?;
+            }
+            
+            if royalty_amount > 0 {
+                let royalty_quote_account = royalty_quote_account(
+                    ctx.accounts.royalty_quote_account.as_ref(),
+                    royalty_recipient,
+                    auction.quote_mint,
+                )?;
+                
+                let token_accounts = Transfer {
+                    from: quote_vault.to_account_info(),
+                    to: royalty_quote_account.to_account_info(),
+                    authority: auction.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    signer,
+                );
+                
+                token::transfer(token_ctx, royalty_amount)?;
+            }
+        }
+        
+        // The winner's bid is spent, so its account closes back to them
+        winning_bid.close(winner.to_account_info())?;
+        auction.open_bids = auction.open_bids.checked_sub(1)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
+        release_auction_lot(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_token_account,
+            winner_token_account,
+            &ctx.accounts.seller,
+            auction,
+            signer,
+        )?;
+        
+        auction.status = AuctionStatus::Settled;
+        auction.updated_at = now;
+        
+        record_trader_volume(
+            winner_volume,
+            auction.highest_bidder,
+            auction.quote_mint,
+            *ctx.bumps.get("winner_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            auction.token_amount,
+            true,
+        )?;
+        record_trader_volume(
+            seller_volume,
+            auction.seller,
+            auction.quote_mint,
+            *ctx.bumps.get("seller_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            auction.token_amount,
+            false,
+        )?;
+        let fill = Fill::at_total(
+            auction.mint,
+            auction.quote_mint,
+            auction.mint_decimals,
+            auction.token_amount,
+            total_price,
+        )?;
+        record_mint_trade(
+            mint_stats,
+            &fill,
+            *ctx.bumps.get("mint_stats").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        record_price_observation(
+            price_oracle,
+            &fill,
+            *ctx.bumps.get("price_oracle").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        
+        marketplace.total_volume = marketplace.total_volume
+            .checked_add(total_price)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        marketplace.updated_at = now;
+        
+        emit!(AuctionSettled {
+            auction: auction.key(),
+            seller: auction.seller,
+            buyer: auction.highest_bidder,
+            mint: auction.mint,
+            quote_mint: auction.quote_mint,
+            token_amount: auction.token_amount,
+            price: total_price,
+            fee_amount,
+            seller_amount,
+            timestamp: now,
+        });
+        
+        if fee_amount > 0 {
+            emit!(TradeFeeDistributed {
+                listing: auction.key(),
+                quote_mint: auction.quote_mint,
+                fee,
+                fee_amount,
+                treasury_amount,
+                royalty_recipient,
+                royalty_amount,
+                referrer: Pubkey::default(),
+                referrer_amount: 0,
+                timestamp: now,
+            });
+        }
+        
+        Ok(())
+    }
+
+    pub fn cancel_auction(
+        ctx: Context<CancelAuction>,
+    ) -> Result<()> {
+        let auction = &mut ctx.accounts.auction;
+        
+        require!(
+            auction.status == AuctionStatus::Active,
+            MarketplaceError::AuctionNotActive
+        );
+        
+        // Bidders are owed the lot once they have bid
+        require!(
+            auction.highest_bidder == Pubkey::default(),
+            MarketplaceError::AuctionHasBids
+        );
+        
+        let seller_key = auction.seller;
+        let index = auction.index.to_le_bytes();
+        let seeds = &[
+            b"auction".as_ref(),
+            seller_key.as_ref(),
+            index.as_ref(),
+            &[auction.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        release_auction_lot(
+            &ctx.accounts.token_program,
+            &ctx.accounts.escrow_token_account,
+            &ctx.accounts.seller_token_account,
+            &ctx.accounts.seller.to_account_info(),
+            auction,
+            signer,
+        )?;
+        
+        // No bids means the quote vault is empty and can be closed as well
+        if let Some(quote_vault) = ctx.accounts.quote_vault.as_ref() {
+            let close_accounts = CloseAccount {
+                account: quote_vault.to_account_info(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: auction.to_account_info(),
+            };
+            
+            let close_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                signer,
+            );
+            
+            token::close_account(close_ctx)?;
+        }
+        
+        auction.status = AuctionStatus::Cancelled;
+        auction.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(AuctionCancelled {
+            auction: auction.key(),
+            seller: auction.seller,
+            mint: auction.mint,
+            token_amount: auction.token_amount,
+            status: auction.status,
+            timestamp: auction.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_auction(
+        ctx: Context<CloseAuction>,
+    ) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        
+        require!(
+            auction.status != AuctionStatus::Active,
+            MarketplaceError::AuctionStillRunning
+        );
+        
+        // Losing bidders refund through the auction account, so it stays
+        // open until every bid has been returned
+        require!(auction.open_bids == 0, MarketplaceError::BidsOutstanding);
+        
+        // Every bid is out of the quote vault by now. A cancelled auction
+        // may already have closed its vault.
+        match ctx.accounts.quote_vault.as_ref() {
+            Some(quote_vault) => {
+                let seller_key = auction.seller;
+                let index = auction.index.to_le_bytes();
+                let seeds = &[
+                    b"auction".as_ref(),
+                    seller_key.as_ref(),
+                    index.as_ref(),
+                    &[auction.bump],
+                ];
+                let signer = &[&seeds[..]];
+                
+                let close_accounts = CloseAccount {
+                    account: quote_vault.to_account_info(),
+                    destination: ctx.accounts.seller.to_account_info(),
+                    authority: auction.to_account_info(),
+                };
+                
+                let close_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    close_accounts,
+                    signer,
+                );
+                
+                token::close_account(close_ctx)?;
+            }
+            None => require!(
+                auction.quote_vault == Pubkey::default()
+                    || auction.status == AuctionStatus::Cancelled,
+                MarketplaceError::MissingQuoteAccounts
+            ),
+        }
+        
+        // The auction's rent goes back to the seller
+        emit!(AuctionClosed {
+            auction: auction.key(),
+            seller: auction.seller,
+            mint: auction.mint,
+            status: auction.status,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn make_offer(
+        ctx: Context<MakeOffer>,
+        price_per_token: u64,
+        token_amount: u64,
+        expires_at: i64,
+    ) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        
+        require_trading(&ctx.accounts.marketplace, &listing.mint)?;
+        require_verified_property(
+            &ctx.accounts.marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &listing.mint,
+        )?;
+        
+        require!(
+            listing.status == ListingStatus::Active,
+            MarketplaceError::ListingNotActive
+        );
+        
+        require!(
+            !listing.is_expired(Clock::get()?.unix_timestamp),
+            MarketplaceError::ListingExpired
+        );
+        
+        require!(
+            token_amount > 0 && token_amount <= listing.token_amount,
+            MarketplaceError::InsufficientTokenAmount
+        );
+        
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            MarketplaceError::InvalidOfferExpiry
+        );
+        
+        require!(
+            ctx.accounts.buyer.key() != listing.seller,
+            MarketplaceError::SelfTrade
+        );
+        
+        // Offers are priced like the listing, in its quote mint
+        let total_price = quote_total(price_per_token, token_amount, listing.mint_decimals)?;
+        
+        let vault = if listing.quote_mint == native_mint::ID {
+            require!(
+                ctx.accounts.offer_vault.is_none(),
+                MarketplaceError::InvalidQuoteAccount
+            );
+            
+            // SOL offers are held as lamports on the offer account itself
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: ctx.accounts.offer.to_account_info(),
+                    },
+                ),
+                total_price,
+            )?;
+            
+            Pubkey::default()
+        } else {
+            let buyer_quote_account = ctx.accounts.buyer_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let offer_vault = ctx.accounts.offer_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            let token_accounts = Transfer {
+                from: buyer_quote_account.to_account_info(),
+                to: offer_vault.to_account_info(),
+                authority: ctx.accounts.buyer.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+            );
+            
+            token::transfer(token_ctx, total_price)?;
+            
+            offer_vault.key()
+        };
+        
+        let offer = &mut ctx.accounts.offer;
+        offer.listing = listing.key();
+        offer.buyer = ctx.accounts.buyer.key();
+        offer.vault = vault;
+        offer.price_per_token = price_per_token;
+        offer.token_amount = token_amount;
+        offer.total_price = total_price;
+        offer.expires_at = expires_at;
+        offer.bump = *ctx.bumps.get("offer")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        offer.created_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OfferMade {
+            offer: offer.key(),
+            listing: offer.listing,
+            buyer: offer.buyer,
+            price_per_token,
+            token_amount,
+            total_price,
+            expires_at,
+            timestamp: offer.created_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn accept_offer(
+        ctx: Context<AcceptOffer>,
+    ) -> Result<()> {
+        let listing = &mut ctx.accounts.listing;
+        let marketplace = &mut ctx.accounts.marketplace;
+        let offer = &ctx.accounts.offer;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require_trading(marketplace, &listing.mint)?;
+        require_verified_property(
+            marketplace,
+            ctx.accounts.property_record.as_deref(),
+            &listing.mint,
+        )?;
+        
+        require!(
+            listing.status == ListingStatus::Active,
+            MarketplaceError::ListingNotActive
+        );
+        
+        require!(!listing.is_expired(now), MarketplaceError::ListingExpired);
+        require!(now < offer.expires_at, MarketplaceError::OfferExpired);
+        
+        require!(
+            offer.token_amount <= listing.token_amount,
+            MarketplaceError::InsufficientTokenAmount
+        );
+        
+        let total_price = offer.total_price;
+        let mint_fee_config = load_mint_fee_config(&ctx.accounts.mint_fee_config)?;
+        let FeeSplit {
+            fee,
+            fee_amount,
+            seller_amount,
+            treasury_amount,
+            royalty_recipient,
+            royalty_amount,
+        } = fee_split(
+            marketplace,
+            mint_fee_config.as_ref(),
+            listing.quote_mint,
+            ctx.accounts.seller_volume.volume,
+            total_price,
+        )?;
+        
+        // Release tokens from escrow to the offer's buyer
+        let seller_key = listing.seller;
+        let index = listing.index.to_le_bytes();
+        let seeds = &[
+            b"listing".as_ref(),
+            seller_key.as_ref(),
+            index.as_ref(),
+            &[listing.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let token_accounts = Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: listing.to_account_info(),
+        };
+        
+        let token_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token_accounts,
+            signer,
+        );
+        
+        token::transfer(token_ctx, offer.token_amount)?;
+        
+        if listing.quote_mint == native_mint::ID {
+            // The offer account is owned by this program, so its lamports
+            // can be paid out directly
+            let offer_info = offer.to_account_info();
+            move_lamports(&offer_info, &ctx.accounts.seller.to_account_info(), seller_amount)?;
+            move_lamports(&offer_info, &ctx.accounts.marketplace_treasury, treasury_amount)?;
+            
+            if royalty_amount > 0 {
+                let royalty_account = royalty_account(ctx.accounts.royalty_recipient.as_ref(), royalty_recipient)?;
+                move_lamports(&offer_info, royalty_account, royalty_amount)?;
+            }
+        } else {
+            let offer_vault = ctx.accounts.offer_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let seller_quote_account = ctx.accounts.seller_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let treasury_quote_account = ctx.accounts.treasury_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            require!(
+                seller_quote_account.mint == listing.quote_mint
+                    && treasury_quote_account.mint == listing.quote_mint,
+                MarketplaceError::InvalidQuoteAccount
+            );
+            
+            let listing_key = offer.listing;
+            let buyer_key = offer.buyer;
+            let offer_seeds = &[
+                b"offer".as_ref(),
+                listing_key.as_ref(),
+                buyer_key.as_ref(),
+                &[offer.bump],
+            ];
+            let offer_signer = &[&offer_seeds[..]];
+            
+            if seller_amount > 0 {
+                let token_accounts = Transfer {
+                    from: offer_vault.to_account_info(),
+                    to: seller_quote_account.to_account_info(),
+                    authority: offer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    offer_signer,
+                );
+                
+                token::transfer(token_ctx, seller_amount)?;
+            }
+            
+            if treasury_amount > 0 {
+                let token_accounts = Transfer {
+                    from: offer_vault.to_account_info(),
+                    to: treasury_quote_account.to_account_info(),
+                    authority: offer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    offer_signer,
+                );
+                
+                token::transfer(token_ctx, treasury_amount)?;
+            }
+            
+            if royalty_amount > 0 {
+                let royalty_quote_account = royalty_quote_account(
+                    ctx.accounts.royalty_quote_account.as_ref(),
+                    royalty_recipient,
+                    listing.quote_mint,
+                )?;
+                
+                let token_accounts = Transfer {
+                    from: offer_vault.to_account_info(),
+                    to: royalty_quote_account.to_account_info(),
+                    authority: offer.to_account_info(),
+                };
+                
+                let token_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    token_accounts,
+                    offer_signer,
+                );
+                
+                token::transfer(token_ctx, royalty_amount)?;
+            }
+            
+            let close_accounts = CloseAccount {
+                account: offer_vault.to_account_info(),
+                destination: ctx.accounts.buyer.to_account_info(),
+                authority: offer.to_account_info(),
+            };
+            
+            let close_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                offer_signer,
+            );
+            
+            token::close_account(close_ctx)?;
+        }
+        
+        record_trader_volume(
+            &mut ctx.accounts.buyer_volume,
+            offer.buyer,
+            listing.quote_mint,
+            *ctx.bumps.get("buyer_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            offer.token_amount,
+            true,
+        )?;
+        record_trader_volume(
+            &mut ctx.accounts.seller_volume,
+            listing.seller,
+            listing.quote_mint,
+            *ctx.bumps.get("seller_volume").ok_or(ErrorCode::ConstraintSeeds)?,
+            total_price,
+            offer.token_amount,
+            false,
+        )?;
+        let fill = Fill {
+            mint: listing.mint,
+            quote_mint: listing.quote_mint,
+            mint_decimals: listing.mint_decimals,
+            token_amount: offer.token_amount,
+            total_price,
+            price_per_token: offer.price_per_token,
+        };
+        record_mint_trade(
+            &mut ctx.accounts.mint_stats,
+            &fill,
+            *ctx.bumps.get("mint_stats").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        record_price_observation(
+            &mut ctx.accounts.price_oracle,
+            &fill,
+            *ctx.bumps.get("price_oracle").ok_or(ErrorCode::ConstraintSeeds)?,
+            now,
+        )?;
+        
+        listing.token_amount = listing.token_amount
+            .checked_sub(offer.token_amount)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
+        if listing.token_amount == 0 {
+            listing.status = ListingStatus::Completed;
+            
+            let close_accounts = CloseAccount {
+                account: ctx.accounts.escrow_token_account.to_account_info(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: listing.to_account_info(),
+            };
+            
+            let close_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                signer,
+            );
+            
+            token::close_account(close_ctx)?;
+            
+            marketplace.active_listings = marketplace.active_listings
+                .checked_sub(1)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+        }
+        
+        marketplace.total_volume = marketplace.total_volume
+            .checked_add(total_price)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        
+        listing.updated_at = now;
+        marketplace.updated_at = now;
+        
+        emit!(OfferAccepted {
+            offer: offer.key(),
+            listing: listing.key(),
+            buyer: offer.buyer,
+            timestamp: now,
+        });
+        
+        // Accepted offers are trades, so they are reported like any other fill
+        emit!(TradeExecuted {
+            listing: listing.key(),
+            seller: listing.seller,
+            buyer: offer.buyer,
+            mint: listing.mint,
+            quote_mint: listing.quote_mint,
+            token_amount: offer.token_amount,
+            price_per_token: offer.price_per_token,
+            total_price,
+            fee_amount,
+            seller_amount,
+            remaining_amount: listing.token_amount,
+            timestamp: now,
+        });
+        
+        if fee_amount > 0 {
+            emit!(TradeFeeDistributed {
+                listing: listing.key(),
+                quote_mint: listing.quote_mint,
+                fee,
+                fee_amount,
+                treasury_amount,
+                royalty_recipient,
+                royalty_amount,
+                referrer: Pubkey::default(),
+                referrer_amount: 0,
+                timestamp: now,
+            });
+        }
+        
+        Ok(())
+    }
+
+    pub fn withdraw_offer(
+        ctx: Context<WithdrawOffer>,
+    ) -> Result<()> {
+        let offer = &ctx.accounts.offer;
+        
+        // SOL offers are refunded when the offer account closes; SPL offers
+        // are returned from the offer's vault first
+        if offer.vault != Pubkey::default() {
+            let offer_vault = ctx.accounts.offer_vault.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            let buyer_quote_account = ctx.accounts.buyer_quote_account.as_ref()
+                .ok_or(MarketplaceError::MissingQuoteAccounts)?;
+            
+            let listing_key = offer.listing;
+            let buyer_key = offer.buyer;
+            let seeds = &[
+                b"offer".as_ref(),
+                listing_key.as_ref(),
+                buyer_key.as_ref(),
+                &[offer.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let token_accounts = Transfer {
+                from: offer_vault.to_account_info(),
+                to: buyer_quote_account.to_account_info(),
+                authority: offer.to_account_info(),
+            };
+            
+            let token_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_accounts,
+                signer,
+            );
+            
+            token::transfer(token_ctx, offer.total_price)?;
+            
+            let close_accounts = CloseAccount {
+                account: offer_vault.to_account_info(),
+                destination: ctx.accounts.buyer.to_account_info(),
+                authority: offer.to_account_info(),
+            };
+            
+            let close_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                close_accounts,
+                signer,
+            );
+            
+            token::close_account(close_ctx)?;
+        }
+        
+        emit!(OfferWithdrawn {
+            offer: offer.key(),
+            listing: offer.listing,
+            buyer: offer.buyer,
+            refund: offer.total_price,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+}
+
+/// Price of `token_amount` base units of a property mint with `mint_decimals`
+/// decimals, where `price_per_token` is in quote base units per whole token.
+/// Rounds up so fills of a fraction of a base unit are never free.
+fn quote_total(price_per_token: u64, token_amount: u64, mint_decimals: u8) -> Result<u64> {
+    let scale = 10u128
+        .checked_pow(mint_decimals as u32)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    let total = (price_per_token as u128)
+        .checked_mul(token_amount as u128)
+        .ok_or(MarketplaceError::ArithmeticError)?
+        .checked_add(scale - 1)
+        .ok_or(MarketplaceError::ArithmeticError)?
+        / scale;
+    
+    u64::try_from(total).map_err(|_| error!(MarketplaceError::ArithmeticError))
+}
+
+/// Inserts `order` keeping the side sorted best first: highest price for
+/// bids, lowest for asks, and by arrival (order id) within a price. On a
+/// full side the worst order is evicted and returned, provided `order` is
+/// priced better than it.
+fn insert_order(orders: &mut Vec<Order>, order: Order, side: OrderSide) -> Result<Option<Order>> {
+    let index = orders.iter()
+        .position(|resting| match side {
+            OrderSide::Bid => order.price > resting.price,
+            OrderSide::Ask => order.price < resting.price,
+        })
+        .unwrap_or(orders.len());
+    
+    let evicted = if orders.len() < OrderBook::MAX_ORDERS_PER_SIDE {
+        None
+    } else {
+        require!(index < orders.len(), MarketplaceError::OrderBookFull);
+        orders.pop()
+    };
+    orders.insert(index, order);
+    
+    Ok(evicted)
+}
+
+/// Credits what `order` still has in escrow to its owner's open orders, to
+/// be withdrawn with `settle_funds`
+fn credit_order_escrow(
+    open_orders: &mut OpenOrders,
+    order: &Order,
+    side: OrderSide,
+    mint_decimals: u8,
+) -> Result<()> {
+    match side {
+        OrderSide::Ask => {
+            open_orders.base_free = open_orders.base_free.checked_add(order.quantity)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+        }
+        OrderSide::Bid => {
+            let refund = quote_total(order.price, order.quantity, mint_decimals)?;
+            open_orders.quote_free = open_orders.quote_free.checked_add(refund)
+                .ok_or(MarketplaceError::ArithmeticError)?;
+        }
+    }
+    open_orders.updated_at = Clock::get()?.unix_timestamp;
+    
+    Ok(())
+}
+
+/// Fails if the marketplace is paused or `mint` has been delisted
+fn require_trading(marketplace: &Marketplace, mint: &Pubkey) -> Result<()> {
+    require!(!marketplace.paused, MarketplaceError::MarketplacePaused);
+    require!(
+        !marketplace.delisted_mints.contains(mint),
+        MarketplaceError::MintDelisted
+    );
+    
+    Ok(())
+}
+
+/// With `require_verified` set, `mint` needs a registry `PropertyRecord` that
+/// is verified and whose verification has not expired
+fn require_verified_property(
+    marketplace: &Marketplace,
+    property_record: Option<&PropertyRecord>,
+    mint: &Pubkey,
+) -> Result<()> {
+    require!(
+        property_verified(marketplace, property_record, mint)?,
+        MarketplaceError::PropertyNotVerified
+    );
+    
+    Ok(())
+}
+
+/// Whether `mint` may trade under the verification requirement. Fails if the
+/// requirement is on and no record, or the record of another mint, is passed,
+/// so a caller cannot make a verified property look unverified.
+fn property_verified(
+    marketplace: &Marketplace,
+    property_record: Option<&PropertyRecord>,
+    mint: &Pubkey,
+) -> Result<bool> {
+    if !marketplace.require_verified {
+        return Ok(true);
+    }
+    
+    let property_record = property_record.ok_or(MarketplaceError::PropertyNotVerified)?;
+    require_keys_eq!(
+        property_record.token_mint,
+        *mint,
+        MarketplaceError::InvalidPropertyRecord
+    );
+    
+    let now = Clock::get()?.unix_timestamp;
+    let unexpired = property_record.verification_details.as_ref()
+        .map_or(false, |details| details.verification_expiry > now);
+    
+    Ok(property_record.verification_status == VerificationStatus::Verified && unexpired)
+}
+
+/// Upper bound for the royalty and referrer shares, so together they never
+/// exceed the fee
+const MAX_FEE_SHARE: u16 = 5000;
+
+/// Fee in basis points for a trade: the mint's override if it has one,
+/// lowered to the best volume tier the trader qualifies for
+fn effective_fee(
+    marketplace: &Marketplace,
+    mint_fee_config: Option<&MintFeeConfig>,
+    quote_mint: Pubkey,
+    volume: u64,
+) -> u16 {
+    let base_fee = mint_fee_config.map_or(marketplace.fee, |config| config.fee);
+    
+    marketplace.fee_tiers.iter()
+        .filter(|tier| tier.quote_mint == quote_mint && volume >= tier.min_volume)
+        .map(|tier| tier.fee)
+        .fold(base_fee, u16::min)
+}
+
+fn load_mint_fee_config(info: &AccountInfo) -> Result<Option<MintFeeConfig>> {
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    
+    Ok(Some(Account::<MintFeeConfig>::try_from(info)?.into_inner()))
+}
+
+/// `share` basis points of `amount`, rounded down
+fn share_of(amount: u64, share: u16) -> Result<u64> {
+    Ok((amount as u128)
+        .checked_mul(share as u128)
+        .ok_or(MarketplaceError::ArithmeticError)?
+        .checked_div(10000)
+        .ok_or(MarketplaceError::ArithmeticError)? as u64)
+}
+
+fn record_trader_volume(
+    trader_volume: &mut Account<TraderVolume>,
+    trader: Pubkey,
+    quote_mint: Pubkey,
+    bump: u8,
+    amount: u64,
+    token_amount: u64,
+    is_buyer: bool,
+) -> Result<()> {
+    if trader_volume.trader == Pubkey::default() {
+        trader_volume.trader = trader;
+        trader_volume.quote_mint = quote_mint;
+        trader_volume.bump = bump;
+    }
+    
+    trader_volume.volume = trader_volume.volume.checked_add(amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    trader_volume.trade_count = trader_volume.trade_count.checked_add(1)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    if is_buyer {
+        trader_volume.bought_volume = trader_volume.bought_volume.checked_add(amount)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        trader_volume.tokens_bought = trader_volume.tokens_bought.checked_add(token_amount)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+    } else {
+        trader_volume.sold_volume = trader_volume.sold_volume.checked_add(amount)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+        trader_volume.tokens_sold = trader_volume.tokens_sold.checked_add(token_amount)
+            .ok_or(MarketplaceError::ArithmeticError)?;
+    }
+    
+    trader_volume.updated_at = Clock::get()?.unix_timestamp;
+    
+    Ok(())
+}
+
+/// One fill as the market data accounts record it
+struct Fill {
+    mint: Pubkey,
+    quote_mint: Pubkey,
+    mint_decimals: u8,
+    token_amount: u64, // in property base units
+    total_price: u64, // in quote base units
+    price_per_token: u64, // quote base units per whole property token
+}
+
+impl Fill {
+    /// A fill whose price is only known as a total, as for auction lots
+    fn at_total(
+        mint: Pubkey,
+        quote_mint: Pubkey,
+        mint_decimals: u8,
+        token_amount: u64,
+        total_price: u64,
+    ) -> Result<Self> {
+        let price_per_token = (total_price as u128)
+            .checked_mul(10u128.pow(mint_decimals as u32))
+            .ok_or(MarketplaceError::ArithmeticError)?
+            .checked_div(token_amount as u128)
+            .ok_or(MarketplaceError::ArithmeticError)? as u64;
+        
+        Ok(Self {
+            mint,
+            quote_mint,
+            mint_decimals,
+            token_amount,
+            total_price,
+            price_per_token,
+        })
+    }
+}
+
+/// Adds a fill to its mint's market data
+fn record_mint_trade(
+    mint_stats: &mut Account<MintStats>,
+    fill: &Fill,
+    bump: u8,
+    now: i64,
+) -> Result<()> {
+    if mint_stats.mint == Pubkey::default() {
+        mint_stats.mint = fill.mint;
+        mint_stats.quote_mint = fill.quote_mint;
+        mint_stats.low_price = u64::MAX;
+        mint_stats.bump = bump;
+        mint_stats.first_trade_at = now;
+    }
+    
+    mint_stats.volume = mint_stats.volume.checked_add(fill.total_price)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    mint_stats.token_volume = mint_stats.token_volume.checked_add(fill.token_amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    mint_stats.trade_count = mint_stats.trade_count.checked_add(1)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    mint_stats.last_price = fill.price_per_token;
+    mint_stats.high_price = mint_stats.high_price.max(fill.price_per_token);
+    mint_stats.low_price = mint_stats.low_price.min(fill.price_per_token);
+    
+    // Same units as `price_per_token`: quote base units per whole token
+    mint_stats.vwap = (mint_stats.volume as u128)
+        .checked_mul(10u128.pow(fill.mint_decimals as u32))
+        .ok_or(MarketplaceError::ArithmeticError)?
+        .checked_div(mint_stats.token_volume as u128)
+        .ok_or(MarketplaceError::ArithmeticError)? as u64;
+    
+    mint_stats.updated_at = now;
+    
+    Ok(())
+}
+
+/// Rolls the oracle's accumulator forward to `now` at the previous price, then
+/// folds the fill into the volume-weighted price. A new observation is stored
+/// at most once per `OBSERVATION_INTERVAL`, and halves the weight of earlier
+/// fills, so a fill moves the price only in proportion to its size.
+fn record_price_observation(
+    price_oracle: &mut Account<PriceOracle>,
+    fill: &Fill,
+    bump: u8,
+    now: i64,
+) -> Result<()> {
+    if price_oracle.mint == Pubkey::default() {
+        price_oracle.mint = fill.mint;
+        price_oracle.quote_mint = fill.quote_mint;
+        price_oracle.bump = bump;
+        price_oracle.last_update = now;
+    }
+    
+    let elapsed = now.checked_sub(price_oracle.last_update)
+        .ok_or(MarketplaceError::ArithmeticError)?
+        .max(0) as u128;
+    price_oracle.cumulative_price = (price_oracle.last_price as u128)
+        .checked_mul(elapsed)
+        .and_then(|accrued| price_oracle.cumulative_price.checked_add(accrued))
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    price_oracle.last_update = now;
+    
+    let observation = PriceObservation {
+        timestamp: now,
+        cumulative_price: price_oracle.cumulative_price,
+    };
+    let head = price_oracle.head as usize;
+    let due = match price_oracle.observations.get(head) {
+        Some(newest) => now - newest.timestamp >= PriceOracle::OBSERVATION_INTERVAL,
+        None => true,
+    };
+    
+    if due {
+        if price_oracle.observations.len() < PriceOracle::MAX_OBSERVATIONS {
+            price_oracle.observations.push(observation);
+            price_oracle.head = (price_oracle.observations.len() - 1) as u16;
+        } else {
+            let next = (head + 1) % PriceOracle::MAX_OBSERVATIONS;
+            price_oracle.observations[next] = observation;
+            price_oracle.head = next as u16;
+        }
+        
+        price_oracle.weighted_price_volume /= 2;
+        price_oracle.weighted_token_volume /= 2;
+    }
+    
+    price_oracle.weighted_price_volume = (fill.price_per_token as u128)
+        .checked_mul(fill.token_amount as u128)
+        .and_then(|weighted| price_oracle.weighted_price_volume.checked_add(weighted))
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    price_oracle.weighted_token_volume = price_oracle.weighted_token_volume
+        .checked_add(fill.token_amount as u128)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    let price = price_oracle.weighted_price_volume
+        .checked_div(price_oracle.weighted_token_volume)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    price_oracle.last_price = u64::try_from(price)
+        .map_err(|_| error!(MarketplaceError::ArithmeticError))?;
+    
+    Ok(())
+}
+
+/// Largest number of base units whose `quote_total` fits in `budget`
+fn affordable_amount(price_per_token: u64, budget: u64, mint_decimals: u8) -> Result<u64> {
+    if price_per_token == 0 {
+        return Ok(u64::MAX);
+    }
+    
+    let amount = (budget as u128)
+        .checked_mul(10u128.pow(mint_decimals as u32))
+        .ok_or(MarketplaceError::ArithmeticError)?
+        .checked_div(price_per_token as u128)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    Ok(amount.min(u64::MAX as u128) as u64)
+}
+
+/// Splits `total_price` into the marketplace fee and the seller's proceeds
+fn split_fee(total_price: u64, fee: u16) -> Result<(u64, u64)> {
+    let fee_amount = share_of(total_price, fee)?;
+    
+    let seller_amount = total_price
+        .checked_sub(fee_amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    Ok((fee_amount, seller_amount))
+}
+
+/// How one fill's price is divided between the seller, the treasury and the
+/// mint's royalty recipient
+struct FeeSplit {
+    fee: u16, // effective rate in basis points
+    fee_amount: u64,
+    seller_amount: u64,
+    treasury_amount: u64,
+    royalty_recipient: Pubkey,
+    royalty_amount: u64,
+}
+
+/// Splits a fill's `total_price` using the mint's fee config and the seller's
+/// volume tier. Every settlement path goes through here, so none of them can
+/// skip the mint's fee override or its royalty.
+fn fee_split(
+    marketplace: &Marketplace,
+    mint_fee_config: Option<&MintFeeConfig>,
+    quote_mint: Pubkey,
+    seller_volume: u64,
+    total_price: u64,
+) -> Result<FeeSplit> {
+    let fee = effective_fee(marketplace, mint_fee_config, quote_mint, seller_volume);
+    let (fee_amount, seller_amount) = split_fee(total_price, fee)?;
+    
+    let (royalty_recipient, royalty_share) = match mint_fee_config {
+        Some(config) => (config.royalty_recipient, config.royalty_share),
+        None => (Pubkey::default(), 0),
+    };
+    let royalty_amount = share_of(fee_amount, royalty_share)?;
+    let treasury_amount = fee_amount
+        .checked_sub(royalty_amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    Ok(FeeSplit {
+        fee,
+        fee_amount,
+        seller_amount,
+        treasury_amount,
+        royalty_recipient,
+        royalty_amount,
+    })
+}
+
+/// The account named to receive a SOL royalty, checked against the mint's
+/// fee config
+fn royalty_account<'a, 'info>(
+    account: Option<&'a AccountInfo<'info>>,
+    royalty_recipient: Pubkey,
+) -> Result<&'a AccountInfo<'info>> {
+    let account = account.ok_or(MarketplaceError::MissingFeeAccounts)?;
+    require_keys_eq!(
+        account.key(),
+        royalty_recipient,
+        MarketplaceError::InvalidFeeAccount
+    );
+    
+    Ok(account)
+}
+
+/// The token account named to receive an SPL royalty, checked against the
+/// mint's fee config
+fn royalty_quote_account<'a, 'info>(
+    account: Option<&'a Account<'info, TokenAccount>>,
+    royalty_recipient: Pubkey,
+    quote_mint: Pubkey,
+) -> Result<&'a Account<'info, TokenAccount>> {
+    let account = account.ok_or(MarketplaceError::MissingFeeAccounts)?;
+    require!(
+        account.owner == royalty_recipient && account.mint == quote_mint,
+        MarketplaceError::InvalidFeeAccount
+    );
+    
+    Ok(account)
+}
+
+/// Moves lamports out of an account this program owns
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_lamports = from.lamports().checked_sub(amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    let to_lamports = to.lamports().checked_add(amount)
+        .ok_or(MarketplaceError::ArithmeticError)?;
+    
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    
+    Ok(())
+}
+
+/// Current Dutch auction price: falls linearly from `start_price` at
+/// `start_time` to `end_price` at `end_time`, rounding in the seller's favour.
+fn dutch_price(
+    start_price: u64,
+    end_price: u64,
+    start_time: i64,
+    end_time: i64,
+    now: i64,
+) -> Result<u64> {
+    if now <= start_time {
+        return Ok(start_price);
+    }
+    if now >= end_time {
+        return Ok(end_price);
+    }
+    
+    let decay = ((start_price - end_price) as u128)
+        .checked_mul((now - start_time) as u128)
+        .ok_or(MarketplaceError::ArithmeticError)?
+        .checked_div((end_time - start_time) as u128)
+        .ok_or(MarketplaceError::ArithmeticError)? as u64;
+    
+    Ok(start_price - decay)
+}
+
+/// Moves an auction's lot out of escrow and closes the escrow, refunding its
+/// rent to the seller
+fn release_auction_lot<'info>(
+    token_program: &Program<'info, Token>,
+    escrow_token_account: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    seller: &AccountInfo<'info>,
+    auction: &Account<'info, Auction>,
+    signer: &[&[&[u8]]],
+) -> Result<()> {
+    let token_accounts = Transfer {
+        from: escrow_token_account.to_account_info(),
+        to: destination.to_account_info(),
+        authority: auction.to_account_info(),
+    };
+    
+    let token_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token_accounts,
+        signer,
+    );
+    
+    token::transfer(token_ctx, auction.token_amount)?;
+    
+    let close_accounts = CloseAccount {
+        account: escrow_token_account.to_account_info(),
+        destination: seller.clone(),
+        authority: auction.to_account_info(),
+    };
+    
+    let close_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        close_accounts,
+        signer,
+    );
+    
+    token::close_account(close_ctx)
+}
+
+#[derive(Accounts)]
+pub struct InitializeMarketplace<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = Marketplace::LEN,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
     /// The marketplace treasury that will receive fees
     pub treasury: AccountInfo<'info>,
     
@@ -219,54 +4111,1211 @@ pub struct InitializeMarketplace<'info> {
 }
 
 #[derive(Accounts)]
-pub struct CreateListing<'info> {
+pub struct CreateListing<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = SellerListings::LEN,
+        seeds = [b"seller_listings", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_listings: Account<'info, SellerListings>,
+    
+    /// Derived from the seller's next listing index; see `Listing::address`
+    #[account(
+        init,
+        payer = seller,
+        space = Listing::LEN,
+        seeds = [
+            b"listing", 
+            seller.key().as_ref(),
+            &seller_listings.listing_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    pub property_token_mint: Account<'info, Mint>,
+    
+    /// Mint the listing is priced in; the native mint for SOL
+    pub quote_mint: Account<'info, Mint>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == property_token_mint.key(),
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = seller,
+        token::mint = property_token_mint,
+        token::authority = listing,
+        seeds = [b"escrow", listing.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", seller.key().as_ref(), quote_mint.key().as_ref()],
+        bump
+    )]
+    pub seller_volume: Account<'info, TraderVolume>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteTrade<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = listing.status == ListingStatus::Active,
+        constraint = listing.seller == seller.key()
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        address = marketplace.treasury @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace_treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = listing.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == listing.mint,
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    /// Quote accounts are only needed for listings priced in an SPL mint
+    #[account(
+        mut,
+        constraint = buyer_quote_account.owner == buyer.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub buyer_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = seller_quote_account.owner == seller.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub seller_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = treasury_quote_account.owner == marketplace.treasury @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: fee override and royalty for the listed mint. Always passed so a
+    /// buyer cannot skip the royalty; empty if the mint has no config
+    #[account(
+        seeds = [b"mint_fee", listing.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    /// CHECK: receives the royalty on SOL listings; checked against
+    /// `mint_fee_config.royalty_recipient`
+    #[account(mut)]
+    pub royalty_recipient: Option<AccountInfo<'info>>,
+    
+    #[account(mut)]
+    pub royalty_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: any account the buyer names other than the buyer and seller;
+    /// receives the referrer share on SOL listings
+    #[account(mut)]
+    pub referrer: Option<AccountInfo<'info>>,
+    
+    /// The referrer's quote account, which receives its share on SPL listings
+    #[account(mut)]
+    pub referrer_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", buyer.key().as_ref(), listing.quote_mint.as_ref()],
+        bump
+    )]
+    pub buyer_volume: Account<'info, TraderVolume>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", seller.key().as_ref(), listing.quote_mint.as_ref()],
+        bump,
+        // Trading with yourself would count the volume twice
+        constraint = seller.key() != buyer.key() @ MarketplaceError::SelfTrade
+    )]
+    pub seller_volume: Account<'info, TraderVolume>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = MintStats::LEN,
+        seeds = [b"mint_stats", listing.mint.as_ref(), listing.quote_mint.as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PriceOracle::LEN,
+        seeds = [b"price_oracle", listing.mint.as_ref(), listing.quote_mint.as_ref()],
+        bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SweepListings<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// CHECK: receives SOL fees; checked against `marketplace.treasury`
+    #[account(
+        mut,
+        constraint = marketplace_treasury.key() == marketplace.treasury @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace_treasury: AccountInfo<'info>,
+    
+    pub property_token_mint: Account<'info, Mint>,
+    
+    /// Mint every swept listing is priced in; the native mint for SOL
+    pub quote_mint: Account<'info, Mint>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    /// CHECK: fee override and royalty for the mint; empty if it has none
+    #[account(
+        seeds = [b"mint_fee", property_token_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    
+    /// CHECK: receives the royalty on SOL sweeps; checked against
+    /// `mint_fee_config.royalty_recipient`
+    #[account(mut)]
+    pub royalty_recipient: Option<AccountInfo<'info>>,
+    
+    #[account(mut)]
+    pub royalty_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == property_token_mint.key(),
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    /// Quote accounts are only needed for sweeps in an SPL mint
+    #[account(
+        mut,
+        constraint = buyer_quote_account.owner == buyer.key() @ MarketplaceError::InvalidQuoteAccount,
+        constraint = buyer_quote_account.mint == quote_mint.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub buyer_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = treasury_quote_account.owner == marketplace.treasury @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", buyer.key().as_ref(), quote_mint.key().as_ref()],
+        bump
+    )]
+    pub buyer_volume: Account<'info, TraderVolume>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = MintStats::LEN,
+        seeds = [b"mint_stats", property_token_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PriceOracle::LEN,
+        seeds = [b"price_oracle", property_token_mint.key().as_ref(), quote_mint.key().as_ref()],
+        bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReadPriceOracle<'info> {
+    pub price_oracle: Account<'info, PriceOracle>,
+}
+
+#[derive(Accounts)]
+pub struct CancelListing<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = listing.seller == seller.key() @ MarketplaceError::Unauthorized
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        address = listing.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == listing.mint,
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListing<'info> {
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = listing.seller == seller.key() @ MarketplaceError::Unauthorized
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    #[account(
+        mut,
+        address = listing.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == listing.mint,
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseListing<'info> {
+    /// CHECK: receives the listing's tokens and rent; checked against `listing.seller`
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        has_one = seller,
+        close = seller
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    /// Escrow accounts are only needed while the listing is still active
+    #[account(
+        mut,
+        address = listing.escrow
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == listing.mint,
+        constraint = seller_token_account.owner == listing.seller
+    )]
+    pub seller_token_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketplace<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.pending_authority == new_authority.key() @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintFeeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub property_token_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = MintFeeConfig::LEN,
+        seeds = [b"mint_fee", property_token_mint.key().as_ref()],
+        bump
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveMintFeeConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        close = authority
+    )]
+    pub mint_fee_config: Account<'info, MintFeeConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOrderBook<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump,
+        constraint = marketplace.authority == authority.key() @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub property_token_mint: Account<'info, Mint>,
+    
+    pub quote_mint: Account<'info, Mint>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = OrderBook::LEN,
+        seeds = [
+            b"order_book",
+            property_token_mint.key().as_ref(),
+            quote_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub order_book: Account<'info, OrderBook>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = property_token_mint,
+        token::authority = order_book,
+        seeds = [b"order_book_base", order_book.key().as_ref()],
+        bump
+    )]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = authority,
+        token::mint = quote_mint,
+        token::authority = order_book,
+        seeds = [b"order_book_quote", order_book.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OpenOrders::LEN,
+        seeds = [b"open_orders", order_book.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+    
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", owner.key().as_ref(), order_book.quote_mint.as_ref()],
+        bump
+    )]
+    pub trader_volume: Account<'info, TraderVolume>,
+    
+    /// Property tokens for an ask, quote tokens for a bid
+    #[account(
+        mut,
+        constraint = owner_token_account.owner == owner.key()
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = order_book.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = order_book.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    /// Open orders of the owner of the side's worst order; required when the
+    /// side is full and that order belongs to someone else
+    #[account(mut)]
+    pub evicted_open_orders: Option<Account<'info, OpenOrders>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOrder<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [b"open_orders", order_book.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+}
+
+#[derive(Accounts)]
+pub struct MatchOrders<'info> {
+    /// Anyone can crank; pays for the mint's market data account on its
+    /// first fill
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(mut)]
+    pub order_book: Account<'info, OrderBook>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(mut, address = order_book.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = treasury_quote_account.owner == marketplace.treasury @ MarketplaceError::InvalidQuoteAccount,
+        constraint = treasury_quote_account.mint == order_book.quote_mint @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: fee override and royalty for the book's mint; empty if it has
+    /// none
+    #[account(
+        seeds = [b"mint_fee", order_book.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    
+    /// Receives the royalty; checked against
+    /// `mint_fee_config.royalty_recipient`
+    #[account(mut)]
+    pub royalty_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MintStats::LEN,
+        seeds = [b"mint_stats", order_book.mint.as_ref(), order_book.quote_mint.as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PriceOracle::LEN,
+        seeds = [b"price_oracle", order_book.mint.as_ref(), order_book.quote_mint.as_ref()],
+        bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleFunds<'info> {
+    pub owner: Signer<'info>,
+    
+    pub order_book: Account<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [b"open_orders", order_book.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+    
+    #[account(mut, address = order_book.base_vault)]
+    pub base_vault: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = order_book.quote_vault)]
+    pub quote_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_base_account.mint == order_book.mint,
+        constraint = owner_base_account.owner == owner.key()
+    )]
+    pub owner_base_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = owner_quote_account.mint == order_book.quote_mint,
+        constraint = owner_quote_account.owner == owner.key()
+    )]
+    pub owner_quote_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseOpenOrders<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub order_book: Account<'info, OrderBook>,
+    
+    #[account(
+        mut,
+        seeds = [b"open_orders", order_book.key().as_ref(), owner.key().as_ref()],
+        bump = open_orders.bump,
+        close = owner
+    )]
+    pub open_orders: Account<'info, OpenOrders>,
+}
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub listing: Account<'info, Listing>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        init,
+        payer = buyer,
+        space = Offer::LEN,
+        seeds = [b"offer", listing.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    
+    #[account(address = listing.quote_mint)]
+    pub quote_mint: Account<'info, Mint>,
+    
+    /// Quote accounts are only needed for listings priced in an SPL mint
+    #[account(
+        mut,
+        constraint = buyer_quote_account.owner == buyer.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub buyer_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        init,
+        payer = buyer,
+        token::mint = quote_mint,
+        token::authority = offer,
+        seeds = [b"offer_escrow", offer.key().as_ref()],
+        bump
+    )]
+    pub offer_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = listing.seller == seller.key() @ MarketplaceError::Unauthorized
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    #[account(
+        mut,
+        has_one = listing,
+        has_one = buyer,
+        close = buyer
+    )]
+    pub offer: Account<'info, Offer>,
+    
+    /// CHECK: receives the offer's rent; checked against `offer.buyer`
+    #[account(mut)]
+    pub buyer: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        address = marketplace.treasury @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace_treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = listing.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == listing.mint,
+        constraint = buyer_token_account.owner == offer.buyer
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = offer.vault)]
+    pub offer_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = seller_quote_account.owner == seller.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub seller_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = treasury_quote_account.owner == marketplace.treasury @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    /// CHECK: fee override and royalty for the listed mint; empty if it has
+    /// none
+    #[account(
+        seeds = [b"mint_fee", listing.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    
+    /// CHECK: receives the royalty in SOL; checked against
+    /// `mint_fee_config.royalty_recipient`
+    #[account(mut)]
+    pub royalty_recipient: Option<AccountInfo<'info>>,
+    
+    #[account(mut)]
+    pub royalty_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", buyer.key().as_ref(), listing.quote_mint.as_ref()],
+        bump
+    )]
+    pub buyer_volume: Account<'info, TraderVolume>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", seller.key().as_ref(), listing.quote_mint.as_ref()],
+        bump,
+        // Trading with yourself would count the volume twice
+        constraint = seller.key() != buyer.key() @ MarketplaceError::SelfTrade
+    )]
+    pub seller_volume: Account<'info, TraderVolume>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = MintStats::LEN,
+        seeds = [b"mint_stats", listing.mint.as_ref(), listing.quote_mint.as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = PriceOracle::LEN,
+        seeds = [b"price_oracle", listing.mint.as_ref(), listing.quote_mint.as_ref()],
+        bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = buyer @ MarketplaceError::Unauthorized,
+        close = buyer
+    )]
+    pub offer: Account<'info, Offer>,
+    
+    #[account(mut, address = offer.vault)]
+    pub offer_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = buyer_quote_account.owner == buyer.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub buyer_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        space = SellerListings::LEN,
+        seeds = [b"seller_listings", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_listings: Account<'info, SellerListings>,
+    
+    #[account(
+        init,
+        payer = seller,
+        space = Auction::LEN,
+        seeds = [
+            b"auction",
+            seller.key().as_ref(),
+            &seller_listings.listing_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+    
+    pub property_token_mint: Account<'info, Mint>,
+    
+    /// Mint bids are made in; the native mint for SOL
+    pub quote_mint: Account<'info, Mint>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == property_token_mint.key(),
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = seller,
+        token::mint = property_token_mint,
+        token::authority = auction,
+        seeds = [b"escrow", auction.key().as_ref()],
+        bump
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Only needed for English auctions in an SPL quote mint
+    #[account(
+        init,
+        payer = seller,
+        token::mint = quote_mint,
+        token::authority = auction,
+        seeds = [b"auction_quote", auction.key().as_ref()],
+        bump
+    )]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = AuctionBid::LEN,
+        seeds = [b"auction_bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub auction_bid: Account<'info, AuctionBid>,
+    
+    /// Quote accounts are only needed for auctions in an SPL mint
+    #[account(
+        mut,
+        constraint = bidder_quote_account.owner == bidder.key() @ MarketplaceError::InvalidQuoteAccount,
+        constraint = bidder_quote_account.mint == auction.quote_mint @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub bidder_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut, address = auction.quote_vault)]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RefundBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(mut)]
+    pub auction: Account<'info, Auction>,
+    
+    #[account(
+        mut,
+        seeds = [b"auction_bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = auction_bid.bump,
+        close = bidder
+    )]
+    pub auction_bid: Account<'info, AuctionBid>,
+    
+    #[account(
+        mut,
+        constraint = bidder_quote_account.owner == bidder.key() @ MarketplaceError::InvalidQuoteAccount,
+        constraint = bidder_quote_account.mint == auction.quote_mint @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub bidder_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut, address = auction.quote_vault)]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct BuyDutchAuction<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.seller == seller.key()
+    )]
+    pub auction: Account<'info, Auction>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        address = marketplace.treasury @ MarketplaceError::Unauthorized
+    )]
+    pub marketplace_treasury: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        address = auction.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token_account.mint == auction.mint,
+        constraint = buyer_token_account.owner == buyer.key()
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = buyer_quote_account.owner == buyer.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub buyer_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = seller_quote_account.owner == seller.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub seller_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = treasury_quote_account.owner == marketplace.treasury @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Registry record for the property mint; required when the marketplace
+    /// only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    /// CHECK: fee override and royalty for the auctioned mint; empty if it has
+    /// none
+    #[account(
+        seeds = [b"mint_fee", auction.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    
+    /// CHECK: receives the royalty in SOL; checked against
+    /// `mint_fee_config.royalty_recipient`
     #[account(mut)]
-    pub seller: Signer<'info>,
+    pub royalty_recipient: Option<AccountInfo<'info>>,
+    
+    #[account(mut)]
+    pub royalty_quote_account: Option<Account<'info, TokenAccount>>,
     
     #[account(
-        init,
-        payer = seller,
-        space = Listing::LEN,
-        seeds = [
-            b"listing", 
-            seller.key().as_ref(),
-            property_token_mint.key().as_ref(),
-            &Clock::get()?.unix_timestamp.to_be_bytes()
-        ],
+        init_if_needed,
+        payer = buyer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", buyer.key().as_ref(), auction.quote_mint.as_ref()],
         bump
     )]
-    pub listing: Account<'info, Listing>,
+    pub buyer_volume: Account<'info, TraderVolume>,
     
-    pub property_token_mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", seller.key().as_ref(), auction.quote_mint.as_ref()],
+        bump,
+        // Trading with yourself would count the volume twice
+        constraint = seller.key() != buyer.key() @ MarketplaceError::SelfTrade
+    )]
+    pub seller_volume: Account<'info, TraderVolume>,
     
     #[account(
-        mut,
-        seeds = [b"marketplace"],
+        init_if_needed,
+        payer = buyer,
+        space = MintStats::LEN,
+        seeds = [b"mint_stats", auction.mint.as_ref(), auction.quote_mint.as_ref()],
         bump
     )]
-    pub marketplace: Account<'info, Marketplace>,
+    pub mint_stats: Account<'info, MintStats>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        space = PriceOracle::LEN,
+        seeds = [b"price_oracle", auction.mint.as_ref(), auction.quote_mint.as_ref()],
+        bump
+    )]
+    pub price_oracle: Account<'info, PriceOracle>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
 }
 
 #[derive(Accounts)]
-pub struct ExecuteTrade<'info> {
+pub struct SettleAuction<'info> {
+    /// Anyone can settle; pays for the market data accounts on a mint's
+    /// first sale
     #[account(mut)]
-    pub buyer: Signer<'info>,
+    pub payer: Signer<'info>,
     
     #[account(mut)]
     pub seller: AccountInfo<'info>,
     
-    #[account(mut)]
-    pub seller_authority: AccountInfo<'info>,
-    
     #[account(
         mut,
-        constraint = listing.status == ListingStatus::Active,
-        constraint = listing.seller == seller.key()
+        constraint = auction.seller == seller.key()
     )]
-    pub listing: Account<'info, Listing>,
+    pub auction: Account<'info, Auction>,
     
     #[account(
         mut,
@@ -275,104 +5324,690 @@ pub struct ExecuteTrade<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
-    #[account(mut)]
+    #[account(
+        mut,
+        address = marketplace.treasury @ MarketplaceError::Unauthorized
+    )]
     pub marketplace_treasury: AccountInfo<'info>,
     
     #[account(
         mut,
-        constraint = seller_token_account.mint == listing.mint,
+        address = auction.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// Receives the lot back when the auction ends unsold
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == auction.mint,
         constraint = seller_token_account.owner == seller.key()
     )]
-    pub seller_token_account: Account<'info, TokenAccount>,
+    pub seller_token_account: Option<Account<'info, TokenAccount>>,
     
+    /// Winner accounts are only needed when the auction sold
     #[account(
         mut,
-        constraint = buyer_token_account.mint == listing.mint,
-        constraint = buyer_token_account.owner == buyer.key()
+        address = auction.highest_bidder
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub winner: Option<AccountInfo<'info>>,
+    
+    #[account(
+        mut,
+        seeds = [b"auction_bid", auction.key().as_ref(), auction.highest_bidder.as_ref()],
+        bump = winning_bid.bump
+    )]
+    pub winning_bid: Option<Account<'info, AuctionBid>>,
+    
+    #[account(
+        mut,
+        constraint = winner_token_account.mint == auction.mint,
+        constraint = winner_token_account.owner == auction.highest_bidder
+    )]
+    pub winner_token_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut, address = auction.quote_vault)]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = seller_quote_account.owner == seller.key() @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub seller_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        constraint = treasury_quote_account.owner == marketplace.treasury @ MarketplaceError::InvalidQuoteAccount
+    )]
+    pub treasury_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Registry record for the property mint; a sale needs it when the
+    /// marketplace only trades verified properties
+    pub property_record: Option<Account<'info, PropertyRecord>>,
+    
+    /// CHECK: fee override and royalty for the auctioned mint; empty if it has
+    /// none
+    #[account(
+        seeds = [b"mint_fee", auction.mint.as_ref()],
+        bump
+    )]
+    pub mint_fee_config: UncheckedAccount<'info>,
+    
+    /// CHECK: receives the royalty in SOL; checked against
+    /// `mint_fee_config.royalty_recipient`
+    #[account(mut)]
+    pub royalty_recipient: Option<AccountInfo<'info>>,
+    
+    #[account(mut)]
+    pub royalty_quote_account: Option<Account<'info, TokenAccount>>,
+    
+    /// Market data accounts are only needed when the auction sold
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", auction.highest_bidder.as_ref(), auction.quote_mint.as_ref()],
+        bump
+    )]
+    pub winner_volume: Option<Account<'info, TraderVolume>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = TraderVolume::LEN,
+        seeds = [b"trader_volume", seller.key().as_ref(), auction.quote_mint.as_ref()],
+        bump
+    )]
+    pub seller_volume: Option<Account<'info, TraderVolume>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = MintStats::LEN,
+        seeds = [b"mint_stats", auction.mint.as_ref(), auction.quote_mint.as_ref()],
+        bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = PriceOracle::LEN,
+        seeds = [b"price_oracle", auction.mint.as_ref(), auction.quote_mint.as_ref()],
+        bump
+    )]
+    pub price_oracle: Option<Account<'info, PriceOracle>>,
     
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct CancelListing<'info> {
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    
-    #[account(
-        mut,
-        constraint = listing.seller == seller.key() @ MarketplaceError::Unauthorized
-    )]
-    pub listing: Account<'info, Listing>,
-    
-    #[account(
-        mut,
-        seeds = [b"marketplace"],
-        bump
-    )]
-    pub marketplace: Account<'info, Marketplace>,
-}
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        constraint = auction.seller == seller.key() @ MarketplaceError::Unauthorized
+    )]
+    pub auction: Account<'info, Auction>,
+    
+    #[account(
+        mut,
+        address = auction.escrow
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = seller_token_account.mint == auction.mint,
+        constraint = seller_token_account.owner == seller.key()
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = auction.quote_vault)]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAuction<'info> {
+    /// CHECK: receives the auction's rent; checked against `auction.seller`
+    #[account(mut)]
+    pub seller: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        has_one = seller,
+        close = seller
+    )]
+    pub auction: Account<'info, Auction>,
+    
+    /// Only needed while an SPL auction's quote vault is still open
+    #[account(mut, address = auction.quote_vault)]
+    pub quote_vault: Option<Account<'info, TokenAccount>>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[account]
+pub struct Marketplace {
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub fee: u16, // in basis points (e.g., 100 = 1.00%)
+    pub referrer_fee_share: u16, // basis points of the fee paid to a trade's referrer
+    pub fee_tiers: Vec<FeeTier>,
+    pub allowed_quote_mints: Vec<Pubkey>, // SPL mints listings may be priced in, besides SOL
+    pub pending_authority: Pubkey, // default when no handover is in progress
+    pub paused: bool,
+    pub delisted_mints: Vec<Pubkey>, // property mints that may not be traded
+    pub require_verified: bool, // only trade mints the registry has verified
+    pub active_listings: u64,
+    pub total_volume: u64, // summed in quote base units across quote mints
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Marketplace {
+    pub const MAX_QUOTE_MINTS: usize = 8;
+    pub const MAX_FEE_TIERS: usize = 8;
+    pub const MAX_DELISTED_MINTS: usize = 16;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // authority pubkey
+        32 + // treasury pubkey
+        2 + // fee
+        2 + // referrer_fee_share
+        (4 + FeeTier::LEN * Self::MAX_FEE_TIERS) + // fee_tiers
+        (4 + 32 * Self::MAX_QUOTE_MINTS) + // allowed_quote_mints
+        32 + // pending_authority pubkey
+        1 + // paused
+        (4 + 32 * Self::MAX_DELISTED_MINTS) + // delisted_mints
+        1 + // require_verified
+        8 + // active_listings
+        8 + // total_volume
+        8 + // created_at
+        8; // updated_at
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeTier {
+    pub quote_mint: Pubkey,
+    pub min_volume: u64, // seller's volume in `quote_mint` base units
+    pub fee: u16, // in basis points
+}
+
+impl FeeTier {
+    pub const LEN: usize = 
+        32 + // quote_mint pubkey
+        8 + // min_volume
+        2; // fee
+}
+
+/// Per-mint fee override and secondary-sale royalty
+#[account]
+pub struct MintFeeConfig {
+    pub mint: Pubkey,
+    pub fee: u16, // in basis points, replaces `Marketplace::fee`
+    pub royalty_recipient: Pubkey,
+    pub royalty_share: u16, // basis points of the fee paid to `royalty_recipient`
+    pub bump: u8,
+    pub updated_at: i64,
+}
+
+impl MintFeeConfig {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint pubkey
+        2 + // fee
+        32 + // royalty_recipient pubkey
+        2 + // royalty_share
+        1 + // bump
+        8; // updated_at
+}
+
+/// A trader's fill volume in one quote mint, used for fee tiers and
+/// per-trader stats
+#[account]
+pub struct TraderVolume {
+    pub trader: Pubkey,
+    pub quote_mint: Pubkey,
+    pub volume: u64, // in quote base units, as buyer and seller
+    pub trade_count: u64,
+    pub bought_volume: u64, // quote base units paid as buyer
+    pub sold_volume: u64, // quote base units received as seller, before fees
+    pub tokens_bought: u64,
+    pub tokens_sold: u64,
+    pub bump: u8,
+    pub updated_at: i64,
+}
+
+impl TraderVolume {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // trader pubkey
+        32 + // quote_mint pubkey
+        8 + // volume
+        8 + // trade_count
+        8 + // bought_volume
+        8 + // sold_volume
+        8 + // tokens_bought
+        8 + // tokens_sold
+        1 + // bump
+        8; // updated_at
+}
+
+/// Market data for one property mint's fills in one quote mint.
+/// Prices are in quote base units per whole token, like `price_per_token`.
+#[account]
+pub struct MintStats {
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub volume: u64, // in quote base units
+    pub token_volume: u64, // in property base units
+    pub trade_count: u64,
+    pub last_price: u64,
+    pub vwap: u64, // volume-weighted average price over all fills
+    pub high_price: u64,
+    pub low_price: u64,
+    pub bump: u8,
+    pub first_trade_at: i64,
+    pub updated_at: i64,
+}
+
+impl MintStats {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint pubkey
+        32 + // quote_mint pubkey
+        8 + // volume
+        8 + // token_volume
+        8 + // trade_count
+        8 + // last_price
+        8 + // vwap
+        8 + // high_price
+        8 + // low_price
+        1 + // bump
+        8 + // first_trade_at
+        8; // updated_at
+}
+
+/// TWAP source for one property mint in one quote mint, updated by every
+/// fill: trades, sweeps, accepted offers, auction sales and order book matches
+#[account]
+pub struct PriceOracle {
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub last_price: u64, // volume-weighted price of recent fills, as price_per_token
+    pub cumulative_price: u128, // sum of price * seconds up to last_update
+    pub last_update: i64,
+    pub head: u16, // index of the newest observation
+    pub observations: Vec<PriceObservation>, // ring buffer
+    pub weighted_price_volume: u128, // sum of price_per_token * token_amount, halved at each observation
+    pub weighted_token_volume: u128, // sum of token_amount, halved alike
+    pub bump: u8,
+}
+
+impl PriceOracle {
+    pub const MAX_OBSERVATIONS: usize = 96;
+
+    /// Minimum spacing between stored observations, so a full buffer always
+    /// covers at least 24 hours
+    pub const OBSERVATION_INTERVAL: i64 = 900;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // mint pubkey
+        32 + // quote_mint pubkey
+        8 + // last_price
+        16 + // cumulative_price
+        8 + // last_update
+        2 + // head
+        (4 + PriceObservation::LEN * Self::MAX_OBSERVATIONS) + // observations
+        16 + // weighted_price_volume
+        16 + // weighted_token_volume
+        1; // bump
+
+    /// Cumulative price at `timestamp`. Between two observations the value is
+    /// interpolated linearly. Returns `None` before the oldest observation.
+    pub fn cumulative_price_at(&self, timestamp: i64) -> Option<u128> {
+        if self.observations.is_empty() {
+            return None;
+        }
+        
+        if timestamp >= self.last_update {
+            let elapsed = (timestamp - self.last_update) as u128;
+            return self.cumulative_price.checked_add((self.last_price as u128).checked_mul(elapsed)?);
+        }
+        
+        // Walk the observations oldest first, ending with the live accumulator
+        let len = self.observations.len();
+        let oldest = (self.head as usize + 1) % len;
+        let points = (0..len)
+            .map(|i| self.observations[(oldest + i) % len])
+            .chain(std::iter::once(PriceObservation {
+                timestamp: self.last_update,
+                cumulative_price: self.cumulative_price,
+            }));
+        
+        let mut previous: Option<PriceObservation> = None;
+        for point in points {
+            if let Some(start) = previous {
+                if start.timestamp <= timestamp && timestamp < point.timestamp {
+                    let span = (point.timestamp - start.timestamp) as u128;
+                    let offset = (timestamp - start.timestamp) as u128;
+                    let accrued = point.cumulative_price
+                        .checked_sub(start.cumulative_price)?
+                        .checked_mul(offset)?
+                        / span;
+                    return start.cumulative_price.checked_add(accrued);
+                }
+            }
+            previous = Some(point);
+        }
+        
+        None
+    }
+
+    /// Time-weighted average price over the `window` seconds before `now`, or
+    /// `None` if the oracle's history does not reach back that far
+    pub fn get_twap(&self, now: i64, window: i64) -> Option<u64> {
+        if window <= 0 {
+            return None;
+        }
+        
+        let end = self.cumulative_price_at(now)?;
+        let start = self.cumulative_price_at(now.checked_sub(window)?)?;
+        
+        u64::try_from(end.checked_sub(start)? / window as u128).ok()
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    pub cumulative_price: u128,
+}
+
+impl PriceObservation {
+    pub const LEN: usize = 
+        8 + // timestamp
+        16; // cumulative_price
+}
+
+#[account]
+pub struct Listing {
+    pub seller: Pubkey,
+    pub index: u64, // position in the seller's listings, part of the seeds
+    pub mint: Pubkey,
+    pub mint_decimals: u8,
+    pub quote_mint: Pubkey, // native mint for SOL
+    pub escrow: Pubkey, // token account holding the listed tokens
+    pub price_per_token: u64, // quote base units per whole property token
+    pub token_amount: u64,
+    pub expires_at: i64, // 0 if the listing never expires
+    pub status: ListingStatus,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Listing {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // seller pubkey
+        8 + // index
+        32 + // mint pubkey
+        1 + // mint_decimals
+        32 + // quote_mint pubkey
+        32 + // escrow pubkey
+        8 + // price_per_token
+        8 + // token_amount
+        8 + // expires_at
+        1 + // status
+        1 + // bump
+        8 + // created_at
+        8; // updated_at
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+
+    /// Address of the seller's `index`-th listing. Indexes start at 0 and
+    /// follow `SellerListings::listing_count`.
+    pub fn address(seller: &Pubkey, index: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"listing", seller.as_ref(), &index.to_le_bytes()],
+            &crate::ID,
+        )
+    }
+}
+
+#[account]
+pub struct SellerListings {
+    pub seller: Pubkey,
+    pub listing_count: u64, // index of the seller's next listing
+    pub bump: u8,
+}
+
+impl SellerListings {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // seller pubkey
+        8 + // listing_count
+        1; // bump
+}
+
+/// A buyer's standing offer on a listing, with the full payment escrowed.
+/// SOL offers are held as lamports on this account.
+#[account]
+pub struct Offer {
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub vault: Pubkey, // token account holding SPL offers; default for SOL
+    pub price_per_token: u64, // quote base units per whole property token
+    pub token_amount: u64,
+    pub total_price: u64, // amount escrowed
+    pub expires_at: i64,
+    pub bump: u8,
+    pub created_at: i64,
+}
+
+impl Offer {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // listing pubkey
+        32 + // buyer pubkey
+        32 + // vault pubkey
+        8 + // price_per_token
+        8 + // token_amount
+        8 + // total_price
+        8 + // expires_at
+        1 + // bump
+        8; // created_at
+}
+
+/// A lot of property tokens sold as a whole by auction. Prices are totals for
+/// the lot in quote base units.
+#[account]
+pub struct Auction {
+    pub seller: Pubkey,
+    pub index: u64, // shares the seller's listing counter, part of the seeds
+    pub mint: Pubkey,
+    pub mint_decimals: u8,
+    pub quote_mint: Pubkey, // native mint for SOL
+    pub escrow: Pubkey, // token account holding the lot
+    pub quote_vault: Pubkey, // holds SPL bids; default for SOL and Dutch auctions
+    pub token_amount: u64,
+    pub kind: AuctionKind,
+    pub start_price: u64, // opening bid (English) or starting price (Dutch)
+    pub start_time: i64,
+    pub end_time: i64, // extended by late English bids
+    pub highest_bidder: Pubkey, // default until the first bid; the buyer once sold
+    pub highest_bid: u64,
+    pub open_bids: u32, // bid accounts not yet refunded or settled
+    pub status: AuctionStatus,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Auction {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // seller pubkey
+        8 + // index
+        32 + // mint pubkey
+        1 + // mint_decimals
+        32 + // quote_mint pubkey
+        32 + // escrow pubkey
+        32 + // quote_vault pubkey
+        8 + // token_amount
+        AuctionKind::LEN + // kind
+        8 + // start_price
+        8 + // start_time
+        8 + // end_time
+        32 + // highest_bidder pubkey
+        8 + // highest_bid
+        4 + // open_bids
+        1 + // status
+        1 + // bump
+        8 + // created_at
+        8; // updated_at
 
-#[derive(Accounts)]
-pub struct UpdateMarketplace<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"marketplace"],
-        bump,
-        constraint = marketplace.authority == authority.key() @ MarketplaceError::Unauthorized
-    )]
-    pub marketplace: Account<'info, Marketplace>,
+    /// Address of the auction at the seller's `index`
+    pub fn address(seller: &Pubkey, index: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[b"auction", seller.as_ref(), &index.to_le_bytes()],
+            &crate::ID,
+        )
+    }
 }
 
+/// A bidder's escrowed bid on an English auction. SOL bids are held as
+/// lamports on this account.
 #[account]
-pub struct Marketplace {
-    pub authority: Pubkey,
-    pub treasury: Pubkey,
-    pub fee: u16, // in basis points (e.g., 100 = 1.00%)
-    pub active_listings: u64,
-    pub total_volume: u64,
+pub struct AuctionBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-impl Marketplace {
+impl AuctionBid {
     pub const LEN: usize = 8 + // discriminator
-        32 + // authority pubkey
-        32 + // treasury pubkey
-        2 + // fee
-        8 + // active_listings
-        8 + // total_volume
+        32 + // auction pubkey
+        32 + // bidder pubkey
+        8 + // amount
+        1 + // bump
         8 + // created_at
         8; // updated_at
 }
 
+/// Resting limit orders for one property mint against one quote mint. Each
+/// side is a slab kept sorted best first, so the top of book is index 0.
 #[account]
-pub struct Listing {
-    pub seller: Pubkey,
+pub struct OrderBook {
     pub mint: Pubkey,
-    pub price_per_token: u64,
-    pub token_amount: u64,
-    pub status: ListingStatus,
+    pub mint_decimals: u8,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey, // escrow for asks
+    pub quote_vault: Pubkey, // escrow for bids
+    pub next_order_id: u64,
+    pub bids: Vec<Order>,
+    pub asks: Vec<Order>,
+    pub bump: u8,
     pub created_at: i64,
     pub updated_at: i64,
 }
 
-impl Listing {
+impl OrderBook {
+    pub const MAX_ORDERS_PER_SIDE: usize = 32;
+
     pub const LEN: usize = 8 + // discriminator
-        32 + // seller pubkey
         32 + // mint pubkey
-        8 + // price_per_token
-        8 + // token_amount
-        1 + // status
+        1 + // mint_decimals
+        32 + // quote_mint pubkey
+        32 + // base_vault pubkey
+        32 + // quote_vault pubkey
+        8 + // next_order_id
+        (4 + Order::LEN * Self::MAX_ORDERS_PER_SIDE) + // bids
+        (4 + Order::LEN * Self::MAX_ORDERS_PER_SIDE) + // asks
+        1 + // bump
+        8 + // created_at
+        8; // updated_at
+
+    pub fn side_mut(&mut self, side: OrderSide) -> &mut Vec<Order> {
+        match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct Order {
+    pub order_id: u64, // also the order's time priority
+    pub owner: Pubkey,
+    pub price: u64, // quote base units per whole property token
+    pub quantity: u64, // remaining, in property base units
+    pub created_at: i64,
+}
+
+impl Order {
+    pub const LEN: usize = 
+        8 + // order_id
+        32 + // owner pubkey
+        8 + // price
+        8 + // quantity
+        8; // created_at
+}
+
+/// A trader's settled balances on one order book, withdrawn with `settle_funds`
+#[account]
+pub struct OpenOrders {
+    pub order_book: Pubkey,
+    pub owner: Pubkey,
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl OpenOrders {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // order_book pubkey
+        32 + // owner pubkey
+        8 + // base_free
+        8 + // quote_free
+        1 + // bump
         8 + // created_at
         8; // updated_at
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum AuctionKind {
+    /// Ascending bids; a bid within `extension` seconds of the end pushes
+    /// the end to `extension` seconds after it
+    English { reserve_price: u64, min_increment: u64, extension: i64 },
+    /// Price falls linearly to `end_price`; the first buyer takes the lot
+    Dutch { end_price: u64 },
+}
+
+impl AuctionKind {
+    pub const LEN: usize = 1 + 8 + 8 + 8; // tag + largest variant
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum AuctionStatus {
+    Active,
+    Settled,
+    Unsold,
+    Cancelled,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
 pub enum ListingStatus {
     Active,
@@ -380,6 +6015,342 @@ pub enum ListingStatus {
     Cancelled,
 }
 
+#[event]
+pub struct MarketplaceInitialized {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingCreated {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub index: u64,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub price_per_token: u64,
+    pub token_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeExecuted {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_price: u64,
+    pub fee_amount: u64,
+    pub seller_amount: u64,
+    pub remaining_amount: u64, // tokens left on the listing after this fill
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TradeFeeDistributed {
+    pub listing: Pubkey, // listing, auction or order book that was filled
+    pub quote_mint: Pubkey,
+    pub fee: u16, // effective rate in basis points
+    pub fee_amount: u64,
+    pub treasury_amount: u64,
+    pub royalty_recipient: Pubkey, // default if the mint has no royalty
+    pub royalty_amount: u64,
+    pub referrer: Pubkey, // default if the trade had no referrer
+    pub referrer_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingsSwept {
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub listings_filled: u32,
+    pub token_amount: u64,
+    pub total_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingUpdated {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub previous_price_per_token: u64,
+    pub price_per_token: u64,
+    pub token_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ListingClosed {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub returned_amount: u64, // unsold tokens returned from an expired listing
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketplaceFeeUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub previous_fee: u16,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct QuoteMintsUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub allowed_quote_mints: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarketplacePauseUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuthorityTransferred {
+    pub marketplace: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TreasuryUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub previous_treasury: Pubkey,
+    pub treasury: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintDelistingUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub delisted: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerificationRequirementUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub require_verified: bool,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeScheduleUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub referrer_fee_share: u16,
+    pub fee_tiers: Vec<FeeTier>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintFeeConfigUpdated {
+    pub mint: Pubkey,
+    pub fee: u16,
+    pub royalty_recipient: Pubkey,
+    pub royalty_share: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MintFeeConfigRemoved {
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderBookCreated {
+    pub order_book: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderPlaced {
+    pub order_book: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub price: u64,
+    pub quantity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrderCancelled {
+    pub order_book: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub remaining_quantity: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OrdersMatched {
+    pub order_book: Pubkey,
+    pub bid_id: u64,
+    pub ask_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub quantity: u64,
+    pub fee_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FundsSettled {
+    pub order_book: Pubkey,
+    pub owner: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OpenOrdersClosed {
+    pub order_book: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub price_per_token: u64,
+    pub token_amount: u64,
+    pub total_price: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfferAccepted {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OfferWithdrawn {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub refund: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub index: u64,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub kind: AuctionKind,
+    pub token_amount: u64,
+    pub start_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionBidPlaced {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub end_time: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionBidRefunded {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_amount: u64,
+    pub price: u64,
+    pub fee_amount: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub token_amount: u64,
+    pub status: AuctionStatus, // Cancelled by the seller or ended Unsold
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AuctionClosed {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub status: AuctionStatus,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum MarketplaceError {
     #[msg("Listing is not active")]
@@ -399,4 +6370,151 @@ pub enum MarketplaceError {
     
     #[msg("Fee too high")]
     FeeTooHigh,
+    
+    #[msg("Quote mint is not allowed by the marketplace")]
+    QuoteMintNotAllowed,
+    
+    #[msg("Quote mint is already allowed")]
+    QuoteMintAlreadyAllowed,
+    
+    #[msg("Too many quote mints")]
+    TooManyQuoteMints,
+    
+    #[msg("Quote token accounts are required for this listing")]
+    MissingQuoteAccounts,
+    
+    #[msg("Quote token account does not match the listing")]
+    InvalidQuoteAccount,
+    
+    #[msg("Order price and quantity must be positive and match the order book")]
+    InvalidOrder,
+    
+    #[msg("Order book side is full")]
+    OrderBookFull,
+    
+    #[msg("Order not found")]
+    OrderNotFound,
+    
+    #[msg("Open orders account belongs to another order book")]
+    InvalidOpenOrders,
+    
+    #[msg("Invalid auction parameters")]
+    InvalidAuctionConfig,
+    
+    #[msg("Auction is not active")]
+    AuctionNotActive,
+    
+    #[msg("Auction has not started")]
+    AuctionNotStarted,
+    
+    #[msg("Auction has ended")]
+    AuctionEnded,
+    
+    #[msg("Auction is still running")]
+    AuctionStillRunning,
+    
+    #[msg("Instruction does not apply to this kind of auction")]
+    WrongAuctionKind,
+    
+    #[msg("Bid is below the minimum")]
+    BidTooLow,
+    
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+    
+    #[msg("The leading bid cannot be refunded")]
+    BidNotRefundable,
+    
+    #[msg("Missing accounts for settling the auction")]
+    MissingAuctionAccounts,
+    
+    #[msg("Offer expiry must be in the future")]
+    InvalidOfferExpiry,
+    
+    #[msg("Offer has expired")]
+    OfferExpired,
+    
+    #[msg("Listing expiry must be in the future")]
+    InvalidListingExpiry,
+    
+    #[msg("Listing has expired")]
+    ListingExpired,
+    
+    #[msg("Listing is active and has not expired")]
+    ListingStillActive,
+    
+    #[msg("Missing escrow accounts for closing the listing")]
+    MissingEscrowAccounts,
+    
+    #[msg("Listing terms are worse than the buyer's limits")]
+    SlippageExceeded,
+    
+    #[msg("Minimum token amount exceeds the requested amount")]
+    InvalidTradeLimits,
+    
+    #[msg("Too many fee tiers")]
+    TooManyFeeTiers,
+    
+    #[msg("Fee share is too high")]
+    FeeShareTooHigh,
+    
+    #[msg("Missing royalty or referrer accounts")]
+    MissingFeeAccounts,
+    
+    #[msg("Royalty or referrer account does not match")]
+    InvalidFeeAccount,
+    
+    #[msg("Buyer and seller must differ")]
+    SelfTrade,
+    
+    #[msg("Marketplace is paused")]
+    MarketplacePaused,
+    
+    #[msg("Trading of this property mint is suspended")]
+    MintDelisted,
+    
+    #[msg("Mint is already delisted")]
+    MintAlreadyDelisted,
+    
+    #[msg("Mint is not delisted")]
+    MintNotDelisted,
+    
+    #[msg("Too many delisted mints")]
+    TooManyDelistedMints,
+    
+    #[msg("Invalid treasury")]
+    InvalidTreasury,
+    
+    #[msg("Property is not verified by the registry")]
+    PropertyNotVerified,
+    
+    #[msg("Property record does not match the mint")]
+    InvalidPropertyRecord,
+    
+    #[msg("Sweep accounts do not match the listings")]
+    InvalidSweepAccounts,
+    
+    #[msg("TWAP window must be positive")]
+    InvalidTwapWindow,
+    
+    #[msg("Price oracle history does not cover the window")]
+    OracleHistoryTooShort,
+    
+    #[msg("Auction still has bids to refund")]
+    BidsOutstanding,
+    
+    #[msg("Settle funds before closing the open orders account")]
+    UnsettledFunds,
+    
+    #[msg("Open orders account still has resting orders")]
+    OrdersStillResting,
+    
+    #[msg("Referrer cannot be the buyer or the seller")]
+    InvalidReferrer,
+    
+    #[msg("Orders must be for at least one whole token")]
+    OrderTooSmall,
+    
+    #[msg("Open orders of the evicted order's owner are required")]
+    MissingEvictedOpenOrders,
 } 
\ No newline at end of file
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::program_option::COption;
+use anchor_lang::system_program;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
 
 declare_id!("PTok1111111111111111111111111111111111111");
 
@@ -25,9 +27,21 @@ pub mod property_tokenization {
         property.token_price = token_price;
         property.status = PropertyStatus::Available;
         property.details = property_details;
+        property.pending_owner = None;
         property.created_at = Clock::get()?.unix_timestamp;
         property.updated_at = Clock::get()?.unix_timestamp;
 
+        emit!(PropertyInitialized {
+            property: property.key(),
+            owner: property.owner,
+            mint: property.mint,
+            property_id: property.property_id.clone(),
+            property_uri: property.property_uri.clone(),
+            total_tokens: property.total_tokens,
+            token_price: property.token_price,
+            timestamp: property.created_at,
+        });
+
         Ok(())
     }
 
@@ -69,6 +83,17 @@ pub mod property_tokenization {
         
         property.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(TokensPurchased {
+            property: property.key(),
+            mint: property.mint,
+            buyer: ctx.accounts.buyer.key(),
+            amount,
+            token_price: property.token_price,
+            available_tokens: property.available_tokens,
+            status: property.status,
+            timestamp: property.updated_at,
+        });
+        
         Ok(())
     }
 
@@ -76,93 +101,1545 @@ pub mod property_tokenization {
         ctx: Context<UpdateProperty>,
         property_details: PropertyDetails,
     ) -> Result<()> {
+        // Only the owner (or enough of its multisig owners) can update property details
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        let property = &mut ctx.accounts.property;
+        
+        property.details = property_details;
+        property.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(PropertyDetailsUpdated {
+            property: property.key(),
+            owner: property.owner,
+            details: property.details.clone(),
+            timestamp: property.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn update_property_status(
+        ctx: Context<UpdateProperty>,
+        status: PropertyStatus,
+    ) -> Result<()> {
+        // Only the owner (or enough of its multisig owners) can update property status
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        let property = &mut ctx.accounts.property;
+        
+        require_status_change_allowed(property.status, status)?;
+        
+        let previous_status = property.status;
+        property.status = status;
+        property.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(PropertyStatusUpdated {
+            property: property.key(),
+            owner: property.owner,
+            previous_status,
+            status: property.status,
+            timestamp: property.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn propose_owner_transfer(
+        ctx: Context<UpdateProperty>,
+        new_owner: Pubkey,
+    ) -> Result<()> {
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
         let property = &mut ctx.accounts.property;
         
-        // Only the owner can update property details
         require!(
-            property.owner == ctx.accounts.owner.key(),
-            PropertyError::Unauthorized
+            new_owner != property.owner,
+            PropertyError::InvalidNewOwner
         );
         
-        property.details = property_details;
+        property.pending_owner = Some(new_owner);
+        property.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OwnerTransferProposed {
+            property: property.key(),
+            owner: property.owner,
+            pending_owner: new_owner,
+            timestamp: property.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn cancel_owner_transfer(
+        ctx: Context<UpdateProperty>,
+    ) -> Result<()> {
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        let property = &mut ctx.accounts.property;
+        let pending_owner = property.pending_owner
+            .ok_or(PropertyError::NoPendingOwner)?;
+        
+        property.pending_owner = None;
+        property.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OwnerTransferCancelled {
+            property: property.key(),
+            owner: property.owner,
+            pending_owner,
+            timestamp: property.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn accept_owner_transfer(
+        ctx: Context<AcceptOwnerTransfer>,
+    ) -> Result<()> {
+        let pending_owner = ctx.accounts.property.pending_owner
+            .ok_or(PropertyError::NoPendingOwner)?;
+        
+        // The pending owner must sign for itself; a multisig accepts once
+        // enough of its owners have signed
+        require_owner_authority(
+            pending_owner,
+            ctx.accounts.new_owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        let property = &mut ctx.accounts.property;
+        let previous_owner = property.owner;
+        
+        property.owner = pending_owner;
+        property.pending_owner = None;
         property.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(OwnerTransferred {
+            property: property.key(),
+            previous_owner,
+            owner: property.owner,
+            timestamp: property.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn create_owner_multisig(
+        ctx: Context<CreateOwnerMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_multisig_owners(&owners, threshold)?;
+        
+        let owner_multisig = &mut ctx.accounts.owner_multisig;
+        owner_multisig.property = ctx.accounts.property.key();
+        owner_multisig.owners = owners;
+        owner_multisig.threshold = threshold;
+        owner_multisig.bump = *ctx.bumps.get("owner_multisig")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        owner_multisig.created_at = Clock::get()?.unix_timestamp;
+        owner_multisig.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OwnerMultisigUpdated {
+            owner_multisig: owner_multisig.key(),
+            property: owner_multisig.property,
+            owners: owner_multisig.owners.clone(),
+            threshold: owner_multisig.threshold,
+            timestamp: owner_multisig.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn update_owner_multisig(
+        ctx: Context<UpdateOwnerMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        // Whoever controls the property controls its owner set, so a multisig
+        // that already owns the property must approve its own changes
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            Some(&ctx.accounts.owner_multisig),
+            ctx.remaining_accounts,
+        )?;
+        
+        validate_multisig_owners(&owners, threshold)?;
+        
+        let owner_multisig = &mut ctx.accounts.owner_multisig;
+        owner_multisig.owners = owners;
+        owner_multisig.threshold = threshold;
+        owner_multisig.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(OwnerMultisigUpdated {
+            owner_multisig: owner_multisig.key(),
+            property: owner_multisig.property,
+            owners: owner_multisig.owners.clone(),
+            threshold: owner_multisig.threshold,
+            timestamp: owner_multisig.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn initialize_governance(
+        ctx: Context<InitializeGovernance>,
+        quorum_bps: u16,
+        voting_period: i64,
+        proposal_threshold: u64,
+    ) -> Result<()> {
+        // Only the owner (or enough of its multisig owners) can open governance
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        require!(
+            quorum_bps > 0 && quorum_bps <= 10000 && voting_period > 0,
+            PropertyError::InvalidGovernanceConfig
+        );
+        
+        let governance = &mut ctx.accounts.governance;
+        governance.property = ctx.accounts.property.key();
+        governance.mint = ctx.accounts.mint.key();
+        governance.vault = ctx.accounts.governance_vault.key();
+        governance.quorum_bps = quorum_bps;
+        governance.voting_period = voting_period;
+        governance.proposal_threshold = proposal_threshold;
+        governance.proposal_count = 0;
+        governance.total_deposited = 0;
+        governance.bump = *ctx.bumps.get("governance")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        governance.created_at = Clock::get()?.unix_timestamp;
+        governance.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(GovernanceInitialized {
+            governance: governance.key(),
+            property: governance.property,
+            mint: governance.mint,
+            quorum_bps,
+            voting_period,
+            proposal_threshold,
+            timestamp: governance.created_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn deposit_governance_tokens(
+        ctx: Context<DepositGovernanceTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, PropertyError::InsufficientTokens);
+        
+        // Voting power is whatever sits in escrow, so tokens used to vote
+        // cannot be moved to another wallet and voted again
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.voter_token_account.to_account_info(),
+            to: ctx.accounts.governance_vault.to_account_info(),
+            authority: ctx.accounts.voter.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        token::transfer(cpi_ctx, amount)?;
+        
+        let voter_record = &mut ctx.accounts.voter_record;
+        if voter_record.voter == Pubkey::default() {
+            voter_record.governance = ctx.accounts.governance.key();
+            voter_record.voter = ctx.accounts.voter.key();
+            voter_record.created_at = Clock::get()?.unix_timestamp;
+        }
+        voter_record.deposited_amount = voter_record.deposited_amount.checked_add(amount)
+            .ok_or(PropertyError::ArithmeticError)?;
+        voter_record.updated_at = Clock::get()?.unix_timestamp;
+        
+        let governance = &mut ctx.accounts.governance;
+        governance.total_deposited = governance.total_deposited.checked_add(amount)
+            .ok_or(PropertyError::ArithmeticError)?;
+        governance.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(GovernanceTokensDeposited {
+            governance: governance.key(),
+            voter: voter_record.voter,
+            amount,
+            deposited_amount: voter_record.deposited_amount,
+            timestamp: governance.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn withdraw_governance_tokens(
+        ctx: Context<WithdrawGovernanceTokens>,
+        amount: u64,
+    ) -> Result<()> {
+        let voter_record = &mut ctx.accounts.voter_record;
+        
+        // Tokens stay locked until every proposal they voted on has closed
+        require!(
+            Clock::get()?.unix_timestamp >= voter_record.locked_until,
+            PropertyError::TokensLocked
+        );
+        
+        require!(
+            amount > 0 && amount <= voter_record.deposited_amount,
+            PropertyError::InsufficientTokens
+        );
+        
+        let property_key = ctx.accounts.governance.property;
+        let seeds = &[
+            b"governance".as_ref(),
+            property_key.as_ref(),
+            &[ctx.accounts.governance.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.governance_vault.to_account_info(),
+            to: ctx.accounts.voter_token_account.to_account_info(),
+            authority: ctx.accounts.governance.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        
+        token::transfer(cpi_ctx, amount)?;
+        
+        voter_record.deposited_amount = voter_record.deposited_amount.checked_sub(amount)
+            .ok_or(PropertyError::ArithmeticError)?;
+        voter_record.updated_at = Clock::get()?.unix_timestamp;
+        
+        let governance = &mut ctx.accounts.governance;
+        governance.total_deposited = governance.total_deposited.checked_sub(amount)
+            .ok_or(PropertyError::ArithmeticError)?;
+        governance.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(GovernanceTokensWithdrawn {
+            governance: governance.key(),
+            voter: voter_record.voter,
+            amount,
+            deposited_amount: voter_record.deposited_amount,
+            timestamp: governance.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_voter_record(
+        ctx: Context<CloseVoterRecord>,
+    ) -> Result<()> {
+        let voter_record = &ctx.accounts.voter_record;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            voter_record.deposited_amount == 0,
+            PropertyError::VoterRecordNotEmpty
+        );
+        
+        require!(
+            now >= voter_record.locked_until,
+            PropertyError::TokensLocked
+        );
+        
+        // The record's rent goes back to the voter, and depositing again
+        // recreates it
+        emit!(VoterRecordClosed {
+            governance: voter_record.governance,
+            voter: voter_record.voter,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        action: ProposalAction,
+        description_uri: String,
+    ) -> Result<()> {
+        require!(
+            description_uri.len() <= Proposal::MAX_DESCRIPTION_URI_LEN,
+            PropertyError::DescriptionTooLong
+        );
+        
+        require!(
+            ctx.accounts.voter_record.deposited_amount >= ctx.accounts.governance.proposal_threshold,
+            PropertyError::InsufficientVotingPower
+        );
+        
+        let governance = &mut ctx.accounts.governance;
+        let proposal = &mut ctx.accounts.proposal;
+        let now = Clock::get()?.unix_timestamp;
+        
+        proposal.governance = governance.key();
+        proposal.property = governance.property;
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.index = governance.proposal_count;
+        proposal.action = action;
+        proposal.description_uri = description_uri;
+        proposal.votes_for = 0;
+        proposal.votes_against = 0;
+        proposal.voting_starts_at = now;
+        proposal.voting_ends_at = now.checked_add(governance.voting_period)
+            .ok_or(PropertyError::ArithmeticError)?;
+        proposal.status = ProposalStatus::Active;
+        proposal.created_at = now;
+        proposal.updated_at = now;
+        
+        governance.proposal_count = governance.proposal_count.checked_add(1)
+            .ok_or(PropertyError::ArithmeticError)?;
+        governance.updated_at = now;
+        
+        emit!(ProposalCreated {
+            proposal: proposal.key(),
+            governance: proposal.governance,
+            property: proposal.property,
+            proposer: proposal.proposer,
+            index: proposal.index,
+            action: proposal.action,
+            description_uri: proposal.description_uri.clone(),
+            voting_ends_at: proposal.voting_ends_at,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn cast_vote(
+        ctx: Context<CastVote>,
+        support: bool,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voter_record = &mut ctx.accounts.voter_record;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            proposal.status == ProposalStatus::Active,
+            PropertyError::ProposalNotActive
+        );
+        
+        require!(
+            now < proposal.voting_ends_at,
+            PropertyError::VotingClosed
+        );
+        
+        let weight = voter_record.deposited_amount;
+        require!(weight > 0, PropertyError::InsufficientVotingPower);
+        
+        if support {
+            proposal.votes_for = proposal.votes_for.checked_add(weight)
+                .ok_or(PropertyError::ArithmeticError)?;
+        } else {
+            proposal.votes_against = proposal.votes_against.checked_add(weight)
+                .ok_or(PropertyError::ArithmeticError)?;
+        }
+        proposal.updated_at = now;
+        
+        // The vote record is created with `init`, so a second vote from the
+        // same voter fails; the lock keeps the weight from being withdrawn
+        // and re-deposited elsewhere before the vote closes
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal.key();
+        vote_record.voter = ctx.accounts.voter.key();
+        vote_record.support = support;
+        vote_record.weight = weight;
+        vote_record.created_at = now;
+        
+        voter_record.locked_until = voter_record.locked_until.max(proposal.voting_ends_at);
+        voter_record.updated_at = now;
+        
+        emit!(VoteCast {
+            proposal: proposal.key(),
+            voter: vote_record.voter,
+            support,
+            weight,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn execute_proposal(
+        ctx: Context<ExecuteProposal>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let property = &mut ctx.accounts.property;
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            proposal.status == ProposalStatus::Active,
+            PropertyError::ProposalNotActive
+        );
+        
+        require!(
+            now >= proposal.voting_ends_at,
+            PropertyError::VotingStillOpen
+        );
+        
+        // Quorum is measured against the full supply, not just what is escrowed
+        let quorum = (property.total_tokens as u128)
+            .checked_mul(ctx.accounts.governance.quorum_bps as u128)
+            .ok_or(PropertyError::ArithmeticError)?
+            / 10000;
+        let total_votes = (proposal.votes_for as u128)
+            .checked_add(proposal.votes_against as u128)
+            .ok_or(PropertyError::ArithmeticError)?;
+        let passed = total_votes >= quorum && proposal.votes_for > proposal.votes_against;
+        
+        if passed {
+            match proposal.action {
+                ProposalAction::Signal => {}
+                ProposalAction::UpdatePropertyStatus { status } => {
+                    require_status_change_allowed(property.status, status)?;
+                    
+                    let previous_status = property.status;
+                    property.status = status;
+                    property.updated_at = now;
+                    
+                    emit!(PropertyStatusUpdated {
+                        property: property.key(),
+                        owner: property.owner,
+                        previous_status,
+                        status: property.status,
+                        timestamp: now,
+                    });
+                }
+                ProposalAction::StartLiquidation { min_proceeds } => {
+                    let liquidation = ctx.accounts.liquidation.as_mut()
+                        .ok_or(PropertyError::MissingLiquidationAccounts)?;
+                    let mint = ctx.accounts.mint.as_ref()
+                        .ok_or(PropertyError::MissingLiquidationAccounts)?;
+                    
+                    // Guards against the funding being swapped for a smaller
+                    // one after holders voted on it
+                    require!(
+                        liquidation.proceeds >= min_proceeds,
+                        PropertyError::ProceedsTooLow
+                    );
+                    
+                    activate_liquidation(property, liquidation, mint, now)?;
+                }
+            }
+            proposal.status = ProposalStatus::Executed;
+        } else {
+            proposal.status = ProposalStatus::Defeated;
+        }
+        proposal.updated_at = now;
+        
+        emit!(ProposalFinalized {
+            proposal: proposal.key(),
+            governance: proposal.governance,
+            property: proposal.property,
+            status: proposal.status,
+            votes_for: proposal.votes_for,
+            votes_against: proposal.votes_against,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn cancel_proposal(
+        ctx: Context<CancelProposal>,
+    ) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        
+        require!(
+            proposal.status == ProposalStatus::Active,
+            PropertyError::ProposalNotActive
+        );
+        
+        // Once holders have voted the outcome belongs to them, so the proposer
+        // cannot withdraw a proposal that is about to pass
+        require!(
+            proposal.votes_for == 0 && proposal.votes_against == 0,
+            PropertyError::ProposalHasVotes
+        );
+        
+        proposal.status = ProposalStatus::Cancelled;
+        proposal.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(ProposalCancelled {
+            proposal: proposal.key(),
+            governance: proposal.governance,
+            proposer: proposal.proposer,
+            timestamp: proposal.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_proposal(
+        ctx: Context<CloseProposal>,
+    ) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        
+        // Executed, defeated and cancelled proposals are final
+        require!(
+            proposal.status != ProposalStatus::Active,
+            PropertyError::ProposalStillActive
+        );
+        
+        // The proposal's rent goes back to the proposer
+        emit!(ProposalClosed {
+            proposal: proposal.key(),
+            governance: proposal.governance,
+            proposer: proposal.proposer,
+            status: proposal.status,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_vote_record(
+        ctx: Context<CloseVoteRecord>,
+    ) -> Result<()> {
+        let vote_record = &ctx.accounts.vote_record;
+        let now = Clock::get()?.unix_timestamp;
+        
+        // The vote record is what stops a second vote, so it has to outlive
+        // voting. A closed proposal can never take votes again, since
+        // proposal indexes are not reused.
+        if !ctx.accounts.proposal.data_is_empty() {
+            let proposal = Account::<Proposal>::try_from(&ctx.accounts.proposal)?;
+            require!(
+                proposal.status != ProposalStatus::Active || now >= proposal.voting_ends_at,
+                PropertyError::VotingStillOpen
+            );
+        }
+        
+        // The record's rent goes back to the voter
+        emit!(VoteRecordClosed {
+            proposal: vote_record.proposal,
+            voter: vote_record.voter,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn fund_liquidation(
+        ctx: Context<FundLiquidation>,
+        proceeds: u64,
+    ) -> Result<()> {
+        require!(proceeds > 0, PropertyError::ProceedsTooLow);
+        
+        let property = &ctx.accounts.property;
+        require!(
+            property.status != PropertyStatus::Liquidating
+                && property.status != PropertyStatus::Liquidated,
+            PropertyError::InvalidStatusChange
+        );
+        
+        // Sale proceeds are held as lamports on the liquidation account itself
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.liquidation.to_account_info(),
+                },
+            ),
+            proceeds,
+        )?;
+        
+        let liquidation = &mut ctx.accounts.liquidation;
+        liquidation.property = property.key();
+        liquidation.mint = property.mint;
+        liquidation.funder = ctx.accounts.funder.key();
+        liquidation.proceeds = proceeds;
+        liquidation.supply = 0;
+        liquidation.redeem_until = 0;
+        liquidation.redeemed_tokens = 0;
+        liquidation.paid_out = 0;
+        liquidation.status = LiquidationStatus::Pending;
+        liquidation.bump = *ctx.bumps.get("liquidation")
+            .ok_or(ErrorCode::ConstraintSeeds)?;
+        liquidation.created_at = Clock::get()?.unix_timestamp;
+        liquidation.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(LiquidationFunded {
+            liquidation: liquidation.key(),
+            property: liquidation.property,
+            funder: liquidation.funder,
+            proceeds,
+            timestamp: liquidation.created_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn cancel_liquidation(
+        ctx: Context<CancelLiquidation>,
+    ) -> Result<()> {
+        // The owner can also cancel, so a stray funding cannot block the
+        // property's real liquidation
+        if ctx.accounts.authority.key() != ctx.accounts.liquidation.funder {
+            require_owner_authority(
+                ctx.accounts.property.owner,
+                ctx.accounts.authority.key(),
+                ctx.accounts.owner_multisig.as_ref(),
+                ctx.remaining_accounts,
+            )?;
+        }
+        
+        // Closing the account returns the proceeds and rent to the funder
+        let liquidation = &ctx.accounts.liquidation;
+        
+        emit!(LiquidationCancelled {
+            liquidation: liquidation.key(),
+            property: liquidation.property,
+            funder: liquidation.funder,
+            proceeds: liquidation.proceeds,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
         Ok(())
     }
 
-    pub fn update_property_status(
-        ctx: Context<UpdateProperty>,
-        status: PropertyStatus,
-    ) -> Result<()> {
-        let property = &mut ctx.accounts.property;
-        
-        // Only the owner can update property status
-        require!(
-            property.owner == ctx.accounts.owner.key(),
-            PropertyError::Unauthorized
-        );
-        
-        property.status = status;
-        property.updated_at = Clock::get()?.unix_timestamp;
-        
-        Ok(())
-    }
+    pub fn start_liquidation(
+        ctx: Context<StartLiquidation>,
+    ) -> Result<()> {
+        // Only the owner (or enough of its multisig owners) can start a
+        // liquidation directly; holders can also approve one through governance
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        activate_liquidation(
+            &mut ctx.accounts.property,
+            &mut ctx.accounts.liquidation,
+            &ctx.accounts.mint,
+            Clock::get()?.unix_timestamp,
+        )
+    }
+
+    pub fn redeem_liquidation(
+        ctx: Context<RedeemLiquidation>,
+        amount: u64,
+    ) -> Result<()> {
+        let liquidation = &mut ctx.accounts.liquidation;
+        
+        require!(
+            liquidation.status == LiquidationStatus::Active,
+            PropertyError::LiquidationNotActive
+        );
+        
+        let redeemed_tokens = liquidation.redeemed_tokens.checked_add(amount)
+            .ok_or(PropertyError::ArithmeticError)?;
+        require!(
+            amount > 0 && redeemed_tokens <= liquidation.supply,
+            PropertyError::InsufficientTokens
+        );
+        
+        // The last redemption takes whatever rounding left behind
+        let payout = if redeemed_tokens == liquidation.supply {
+            liquidation.proceeds.checked_sub(liquidation.paid_out)
+                .ok_or(PropertyError::ArithmeticError)?
+        } else {
+            ((liquidation.proceeds as u128)
+                .checked_mul(amount as u128)
+                .ok_or(PropertyError::ArithmeticError)?
+                / liquidation.supply as u128) as u64
+        };
+        
+        let cpi_accounts = Burn {
+            mint: ctx.accounts.mint.to_account_info(),
+            from: ctx.accounts.holder_token_account.to_account_info(),
+            authority: ctx.accounts.holder.to_account_info(),
+        };
+        
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+        
+        token::burn(cpi_ctx, amount)?;
+        
+        // The liquidation account is owned by this program, so its lamports
+        // can be debited directly
+        move_lamports(
+            &liquidation.to_account_info(),
+            &ctx.accounts.holder.to_account_info(),
+            payout,
+        )?;
+        
+        let now = Clock::get()?.unix_timestamp;
+        liquidation.redeemed_tokens = redeemed_tokens;
+        liquidation.paid_out = liquidation.paid_out.checked_add(payout)
+            .ok_or(PropertyError::ArithmeticError)?;
+        liquidation.updated_at = now;
+        
+        emit!(LiquidationRedeemed {
+            liquidation: liquidation.key(),
+            property: liquidation.property,
+            holder: ctx.accounts.holder.key(),
+            amount,
+            payout,
+            redeemed_tokens: liquidation.redeemed_tokens,
+            timestamp: now,
+        });
+        
+        if liquidation.redeemed_tokens == liquidation.supply {
+            liquidation.status = LiquidationStatus::Completed;
+            
+            let property = &mut ctx.accounts.property;
+            let previous_status = property.status;
+            property.status = PropertyStatus::Liquidated;
+            property.available_tokens = 0;
+            property.updated_at = now;
+            
+            emit!(PropertyStatusUpdated {
+                property: property.key(),
+                owner: property.owner,
+                previous_status,
+                status: property.status,
+                timestamp: now,
+            });
+        }
+        
+        Ok(())
+    }
+
+    pub fn close_liquidation(
+        ctx: Context<CloseLiquidation>,
+    ) -> Result<()> {
+        require_owner_authority(
+            ctx.accounts.property.owner,
+            ctx.accounts.owner.key(),
+            ctx.accounts.owner_multisig.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        
+        let liquidation = &ctx.accounts.liquidation;
+        let property = &mut ctx.accounts.property;
+        let now = Clock::get()?.unix_timestamp;
+        
+        // Tokens held in escrows that can never burn them would otherwise
+        // keep the liquidation open forever, so once the redemption window
+        // has passed the owner can close it with tokens still outstanding
+        let redemption_over = liquidation.status == LiquidationStatus::Active
+            && now >= liquidation.redeem_until;
+        require!(
+            liquidation.status == LiquidationStatus::Completed || redemption_over,
+            PropertyError::LiquidationNotComplete
+        );
+        
+        // Proceeds nobody redeemed in time belong to the holders, not the
+        // owner, so they go back to the funder with the account's rent when
+        // the `close` constraint closes the liquidation
+        let unredeemed_proceeds = liquidation.proceeds.checked_sub(liquidation.paid_out)
+            .ok_or(PropertyError::ArithmeticError)?;
+        
+        // The property stays behind as a tombstone, so its id cannot be
+        // registered again for another mint while holders still hold tokens
+        if property.status != PropertyStatus::Liquidated {
+            let previous_status = property.status;
+            property.status = PropertyStatus::Liquidated;
+            property.available_tokens = 0;
+            property.updated_at = now;
+            
+            emit!(PropertyStatusUpdated {
+                property: property.key(),
+                owner: property.owner,
+                previous_status,
+                status: property.status,
+                timestamp: now,
+            });
+        }
+        
+        emit!(LiquidationClosed {
+            liquidation: liquidation.key(),
+            property: property.key(),
+            owner: property.owner,
+            unredeemed_proceeds,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+}
+
+/// Checks that `signer` may act as `expected`: either it is that key, or
+/// `expected` is the property's `OwnerMultisig` and at least `threshold` of
+/// its owners signed (the signer plus any signers in `remaining_accounts`).
+fn require_owner_authority(
+    expected: Pubkey,
+    signer: Pubkey,
+    owner_multisig: Option<&Account<OwnerMultisig>>,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    if signer == expected {
+        return Ok(());
+    }
+    
+    let owner_multisig = owner_multisig.ok_or(PropertyError::Unauthorized)?;
+    require_keys_eq!(
+        owner_multisig.key(),
+        expected,
+        PropertyError::Unauthorized
+    );
+    
+    let mut approvals: Vec<Pubkey> = Vec::with_capacity(owner_multisig.owners.len());
+    let signers = std::iter::once(signer).chain(
+        remaining_accounts
+            .iter()
+            .filter(|account| account.is_signer)
+            .map(|account| account.key()),
+    );
+    for key in signers {
+        if owner_multisig.owners.contains(&key) && !approvals.contains(&key) {
+            approvals.push(key);
+        }
+    }
+    
+    require!(
+        approvals.len() >= owner_multisig.threshold as usize,
+        PropertyError::NotEnoughSigners
+    );
+    
+    Ok(())
+}
+
+/// Owners and governance can move a property between its trading states, but
+/// only a liquidation can move it into (or out of) the liquidation states.
+fn require_status_change_allowed(current: PropertyStatus, next: PropertyStatus) -> Result<()> {
+    let liquidation_state =
+        |status| status == PropertyStatus::Liquidating || status == PropertyStatus::Liquidated;
+    
+    require!(
+        !liquidation_state(current) && !liquidation_state(next),
+        PropertyError::InvalidStatusChange
+    );
+    
+    Ok(())
+}
+
+/// Snapshots the outstanding supply and opens a funded liquidation for redemption.
+fn activate_liquidation(
+    property: &mut Account<Property>,
+    liquidation: &mut Account<Liquidation>,
+    mint: &Account<Mint>,
+    now: i64,
+) -> Result<()> {
+    require!(
+        liquidation.status == LiquidationStatus::Pending,
+        PropertyError::LiquidationNotPending
+    );
+    
+    require!(
+        property.status != PropertyStatus::Liquidating
+            && property.status != PropertyStatus::Liquidated,
+        PropertyError::InvalidStatusChange
+    );
+    
+    // Payouts are pro rata to the snapshot, so nobody may be able to mint
+    // more tokens while the liquidation runs. The liquidation account never
+    // mints, so handing it the authority freezes the supply as well.
+    let supply_frozen = match mint.mint_authority {
+        COption::None => true,
+        COption::Some(authority) => authority == liquidation.key(),
+    };
+    require!(supply_frozen, PropertyError::MintAuthorityNotRevoked);
+    
+    let supply = mint.supply;
+    require!(supply > 0, PropertyError::InsufficientTokens);
+    
+    liquidation.supply = supply;
+    liquidation.redeem_until = now.checked_add(Liquidation::REDEMPTION_PERIOD)
+        .ok_or(PropertyError::ArithmeticError)?;
+    liquidation.status = LiquidationStatus::Active;
+    liquidation.updated_at = now;
+    
+    let previous_status = property.status;
+    property.status = PropertyStatus::Liquidating;
+    property.updated_at = now;
+    
+    emit!(LiquidationStarted {
+        liquidation: liquidation.key(),
+        property: property.key(),
+        proceeds: liquidation.proceeds,
+        supply,
+        redeem_until: liquidation.redeem_until,
+        timestamp: now,
+    });
+    
+    emit!(PropertyStatusUpdated {
+        property: property.key(),
+        owner: property.owner,
+        previous_status,
+        status: property.status,
+        timestamp: now,
+    });
+    
+    Ok(())
+}
+
+/// Moves lamports out of an account this program owns.
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_lamports = from.lamports().checked_sub(amount)
+        .ok_or(PropertyError::ArithmeticError)?;
+    let to_lamports = to.lamports().checked_add(amount)
+        .ok_or(PropertyError::ArithmeticError)?;
+    
+    **from.try_borrow_mut_lamports()? = from_lamports;
+    **to.try_borrow_mut_lamports()? = to_lamports;
+    
+    Ok(())
+}
+
+fn validate_multisig_owners(owners: &[Pubkey], threshold: u8) -> Result<()> {
+    require!(
+        !owners.is_empty() && owners.len() <= OwnerMultisig::MAX_OWNERS,
+        PropertyError::InvalidOwnerSet
+    );
+    
+    for (index, owner) in owners.iter().enumerate() {
+        require!(
+            !owners[..index].contains(owner),
+            PropertyError::InvalidOwnerSet
+        );
+    }
+    
+    require!(
+        threshold > 0 && threshold as usize <= owners.len(),
+        PropertyError::InvalidThreshold
+    );
+    
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(property_id: String)]
+pub struct InitializeProperty<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = Property::LEN,
+        seeds = [b"property", property_id.as_bytes()],
+        bump
+    )]
+    pub property: Account<'info, Property>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseTokens<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    
+    #[account(mut)]
+    pub treasury_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = buyer_token_account.owner == buyer.key(),
+        constraint = buyer_token_account.mint == property.mint,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    pub treasury_authority: AccountInfo<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateProperty<'info> {
+    /// The property owner, or one of the multisig owners when the property is
+    /// owned by its `OwnerMultisig` (co-signers go in remaining accounts)
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Option<Account<'info, OwnerMultisig>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOwnerTransfer<'info> {
+    /// The pending owner, or one of the multisig owners when the pending owner
+    /// is the property's `OwnerMultisig`
+    pub new_owner: Signer<'info>,
+    
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Option<Account<'info, OwnerMultisig>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateOwnerMultisig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        constraint = property.owner == owner.key() @ PropertyError::Unauthorized
+    )]
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = OwnerMultisig::LEN,
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump
+    )]
+    pub owner_multisig: Account<'info, OwnerMultisig>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateOwnerMultisig<'info> {
+    pub owner: Signer<'info>,
+    
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        mut,
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Account<'info, OwnerMultisig>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeGovernance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Option<Account<'info, OwnerMultisig>>,
+    
+    #[account(address = property.mint)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = Governance::LEN,
+        seeds = [b"governance", property.key().as_ref()],
+        bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init,
+        payer = owner,
+        token::mint = mint,
+        token::authority = governance,
+        seeds = [b"governance_vault", property.key().as_ref()],
+        bump
+    )]
+    pub governance_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DepositGovernanceTokens<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance", governance.property.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoterRecord::LEN,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    #[account(
+        mut,
+        constraint = voter_token_account.owner == voter.key(),
+        constraint = voter_token_account.mint == governance.mint,
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = governance.vault)]
+    pub governance_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawGovernanceTokens<'info> {
+    pub voter: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance", governance.property.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    #[account(
+        mut,
+        constraint = voter_token_account.owner == voter.key(),
+        constraint = voter_token_account.mint == governance.mint,
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut, address = governance.vault)]
+    pub governance_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVoterRecord<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump,
+        close = voter
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"governance", governance.property.as_ref()],
+        bump = governance.bump
+    )]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        seeds = [b"voter", governance.key().as_ref(), proposer.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [
+            b"proposal",
+            governance.key().as_ref(),
+            &governance.proposal_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = governance
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    pub governance: Account<'info, Governance>,
+    
+    #[account(
+        mut,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+    
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// Anyone can execute a proposal once voting has closed
+    pub executor: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = governance,
+        has_one = property
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    #[account(has_one = property)]
+    pub governance: Account<'info, Governance>,
+    
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    
+    /// Required when the proposal's action is `StartLiquidation`
+    #[account(
+        mut,
+        seeds = [b"liquidation", property.key().as_ref()],
+        bump = liquidation.bump
+    )]
+    pub liquidation: Option<Account<'info, Liquidation>>,
+    
+    #[account(address = property.mint)]
+    pub mint: Option<Account<'info, Mint>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    pub proposer: Signer<'info>,
+    
+    #[account(
+        mut,
+        has_one = proposer @ PropertyError::Unauthorized
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct CloseProposal<'info> {
+    #[account(
+        mut,
+        has_one = proposer,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+    
+    /// CHECK: receives the proposal's rent; checked against `proposal.proposer`
+    #[account(mut)]
+    pub proposer: AccountInfo<'info>,
 }
 
 #[derive(Accounts)]
-#[instruction(property_id: String)]
-pub struct InitializeProperty<'info> {
+pub struct CloseVoteRecord<'info> {
+    #[account(
+        mut,
+        has_one = proposal,
+        has_one = voter,
+        close = voter
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+    
+    /// CHECK: the voted-on proposal, which may already be closed; checked
+    /// against `vote_record.proposal`
+    pub proposal: AccountInfo<'info>,
+    
+    /// CHECK: receives the vote record's rent; checked against `vote_record.voter`
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub voter: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct FundLiquidation<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+    
+    pub property: Account<'info, Property>,
     
     #[account(
         init,
-        payer = owner,
-        space = Property::LEN,
-        seeds = [b"property", property_id.as_bytes()],
+        payer = funder,
+        space = Liquidation::LEN,
+        seeds = [b"liquidation", property.key().as_ref()],
         bump
     )]
+    pub liquidation: Account<'info, Liquidation>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelLiquidation<'info> {
+    /// The funder, or the property owner
+    pub authority: Signer<'info>,
+    
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Option<Account<'info, OwnerMultisig>>,
+    
+    #[account(
+        mut,
+        seeds = [b"liquidation", property.key().as_ref()],
+        bump = liquidation.bump,
+        has_one = funder,
+        constraint = liquidation.status == LiquidationStatus::Pending @ PropertyError::LiquidationNotPending,
+        close = funder
+    )]
+    pub liquidation: Account<'info, Liquidation>,
+    
+    /// CHECK: receives the proceeds and rent; checked against `liquidation.funder`
+    #[account(mut)]
+    pub funder: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StartLiquidation<'info> {
+    pub owner: Signer<'info>,
+    
+    #[account(mut)]
     pub property: Account<'info, Property>,
     
+    #[account(
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Option<Account<'info, OwnerMultisig>>,
+    
+    #[account(address = property.mint)]
     pub mint: Account<'info, Mint>,
     
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    #[account(
+        mut,
+        seeds = [b"liquidation", property.key().as_ref()],
+        bump = liquidation.bump
+    )]
+    pub liquidation: Account<'info, Liquidation>,
 }
 
 #[derive(Accounts)]
-pub struct PurchaseTokens<'info> {
+pub struct RedeemLiquidation<'info> {
     #[account(mut)]
-    pub buyer: Signer<'info>,
+    pub holder: Signer<'info>,
     
     #[account(mut)]
     pub property: Account<'info, Property>,
     
-    #[account(mut)]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    #[account(mut, address = property.mint)]
+    pub mint: Account<'info, Mint>,
     
     #[account(
         mut,
-        constraint = buyer_token_account.owner == buyer.key(),
-        constraint = buyer_token_account.mint == property.mint,
+        constraint = holder_token_account.owner == holder.key(),
+        constraint = holder_token_account.mint == property.mint,
     )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
+    pub holder_token_account: Account<'info, TokenAccount>,
     
-    pub treasury_authority: AccountInfo<'info>,
+    #[account(
+        mut,
+        seeds = [b"liquidation", property.key().as_ref()],
+        bump = liquidation.bump
+    )]
+    pub liquidation: Account<'info, Liquidation>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateProperty<'info> {
-    #[account(mut)]
+pub struct CloseLiquidation<'info> {
     pub owner: Signer<'info>,
     
+    #[account(mut)]
+    pub property: Account<'info, Property>,
+    
+    #[account(
+        seeds = [b"owner_multisig", property.key().as_ref()],
+        bump = owner_multisig.bump
+    )]
+    pub owner_multisig: Option<Account<'info, OwnerMultisig>>,
+    
     #[account(
         mut,
-        constraint = property.owner == owner.key() @ PropertyError::Unauthorized
+        seeds = [b"liquidation", property.key().as_ref()],
+        bump = liquidation.bump,
+        has_one = funder,
+        close = funder
     )]
-    pub property: Account<'info, Property>,
+    pub liquidation: Account<'info, Liquidation>,
+    
+    /// CHECK: receives the liquidation account's rent and any unredeemed
+    /// proceeds; checked against `liquidation.funder`
+    #[account(mut)]
+    pub funder: AccountInfo<'info>,
 }
 
 #[account]
@@ -176,6 +1653,7 @@ pub struct Property {
     pub token_price: u64,
     pub status: PropertyStatus,
     pub details: PropertyDetails,
+    pub pending_owner: Option<Pubkey>,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -191,15 +1669,207 @@ impl Property {
         8 + // token_price
         1 + // status
         PropertyDetails::LEN + // details
+        (1 + 32) + // Option<Pubkey> pending_owner
+        8 + // created_at
+        8; // updated_at
+}
+
+#[account]
+pub struct OwnerMultisig {
+    pub property: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl OwnerMultisig {
+    pub const MAX_OWNERS: usize = 10;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // property pubkey
+        (4 + 32 * Self::MAX_OWNERS) + // owners
+        1 + // threshold
+        1 + // bump
+        8 + // created_at
+        8; // updated_at
+}
+
+#[account]
+pub struct Governance {
+    pub property: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub quorum_bps: u16, // share of total_tokens that must vote, in basis points
+    pub voting_period: i64, // seconds
+    pub proposal_threshold: u64, // escrowed tokens needed to create a proposal
+    pub proposal_count: u64,
+    pub total_deposited: u64,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Governance {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // property pubkey
+        32 + // mint pubkey
+        32 + // vault pubkey
+        2 + // quorum_bps
+        8 + // voting_period
+        8 + // proposal_threshold
+        8 + // proposal_count
+        8 + // total_deposited
+        1 + // bump
+        8 + // created_at
+        8; // updated_at
+}
+
+#[account]
+pub struct VoterRecord {
+    pub governance: Pubkey,
+    pub voter: Pubkey,
+    pub deposited_amount: u64,
+    pub locked_until: i64, // latest voting_ends_at of any proposal voted on
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl VoterRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance pubkey
+        32 + // voter pubkey
+        8 + // deposited_amount
+        8 + // locked_until
+        8 + // created_at
+        8; // updated_at
+}
+
+#[account]
+pub struct Proposal {
+    pub governance: Pubkey,
+    pub property: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u64,
+    pub action: ProposalAction,
+    pub description_uri: String,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub voting_starts_at: i64,
+    pub voting_ends_at: i64,
+    pub status: ProposalStatus,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Proposal {
+    pub const MAX_DESCRIPTION_URI_LEN: usize = 128;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // governance pubkey
+        32 + // property pubkey
+        32 + // proposer pubkey
+        8 + // index
+        ProposalAction::LEN + // action
+        (4 + Self::MAX_DESCRIPTION_URI_LEN) + // description_uri
+        8 + // votes_for
+        8 + // votes_against
+        8 + // voting_starts_at
+        8 + // voting_ends_at
+        1 + // status
+        8 + // created_at
+        8; // updated_at
+}
+
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub created_at: i64,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // proposal pubkey
+        32 + // voter pubkey
+        1 + // support
+        8 + // weight
+        8; // created_at
+}
+
+#[account]
+pub struct Liquidation {
+    pub property: Pubkey,
+    pub mint: Pubkey,
+    pub funder: Pubkey,
+    pub proceeds: u64, // lamports deposited for holders
+    pub supply: u64, // mint supply when the liquidation started
+    pub redeem_until: i64, // after this the owner can close with tokens outstanding
+    pub redeemed_tokens: u64,
+    pub paid_out: u64,
+    pub status: LiquidationStatus,
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+impl Liquidation {
+    pub const REDEMPTION_PERIOD: i64 = 365 * 24 * 60 * 60;
+
+    pub const LEN: usize = 8 + // discriminator
+        32 + // property pubkey
+        32 + // mint pubkey
+        32 + // funder pubkey
+        8 + // proceeds
+        8 + // supply
+        8 + // redeem_until
+        8 + // redeemed_tokens
+        8 + // paid_out
+        1 + // status
+        1 + // bump
         8 + // created_at
         8; // updated_at
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
 pub enum PropertyStatus {
     Available,
     Pending,
     Sold,
+    Liquidating,
+    Liquidated,
+}
+
+/// Instructions a passed proposal is allowed to execute
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum ProposalAction {
+    Signal,
+    UpdatePropertyStatus { status: PropertyStatus },
+    StartLiquidation { min_proceeds: u64 },
+}
+
+impl ProposalAction {
+    pub const LEN: usize = 
+        1 + // variant
+        8; // largest payload (min_proceeds)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum ProposalStatus {
+    Active,
+    Executed,
+    Defeated,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum LiquidationStatus {
+    Pending,
+    Active,
+    Completed,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -238,6 +1908,223 @@ impl PropertyDetails {
         2; // appreciation_potential
 }
 
+#[event]
+pub struct PropertyInitialized {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub property_id: String,
+    pub property_uri: String,
+    pub total_tokens: u64,
+    pub token_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TokensPurchased {
+    pub property: Pubkey,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub token_price: u64,
+    pub available_tokens: u64, // remaining after this purchase
+    pub status: PropertyStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyDetailsUpdated {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub details: PropertyDetails,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyStatusUpdated {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub previous_status: PropertyStatus,
+    pub status: PropertyStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnerTransferProposed {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnerTransferCancelled {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub pending_owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnerTransferred {
+    pub property: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OwnerMultisigUpdated {
+    pub owner_multisig: Pubkey,
+    pub property: Pubkey,
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceInitialized {
+    pub governance: Pubkey,
+    pub property: Pubkey,
+    pub mint: Pubkey,
+    pub quorum_bps: u16,
+    pub voting_period: i64,
+    pub proposal_threshold: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceTokensDeposited {
+    pub governance: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub deposited_amount: u64, // voter's escrow after this deposit
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GovernanceTokensWithdrawn {
+    pub governance: Pubkey,
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub deposited_amount: u64, // voter's escrow after this withdrawal
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub property: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u64,
+    pub action: ProposalAction,
+    pub description_uri: String,
+    pub voting_ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalFinalized {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub property: Pubkey,
+    pub status: ProposalStatus,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalCancelled {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProposalClosed {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub status: ProposalStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoteRecordClosed {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VoterRecordClosed {
+    pub governance: Pubkey,
+    pub voter: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationFunded {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub funder: Pubkey,
+    pub proceeds: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationCancelled {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub funder: Pubkey,
+    pub proceeds: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationStarted {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub proceeds: u64,
+    pub supply: u64,
+    pub redeem_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationRedeemed {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub payout: u64,
+    pub redeemed_tokens: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LiquidationClosed {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub unredeemed_proceeds: u64, // returned to the funder with the rent
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum PropertyError {
     #[msg("Property is not available")]
@@ -251,4 +2138,70 @@ pub enum PropertyError {
     
     #[msg("Unauthorized operation")]
     Unauthorized,
+    
+    #[msg("New owner must differ from the current owner")]
+    InvalidNewOwner,
+    
+    #[msg("No ownership transfer is pending")]
+    NoPendingOwner,
+    
+    #[msg("Owner set must contain 1 to 10 distinct keys")]
+    InvalidOwnerSet,
+    
+    #[msg("Threshold must be between 1 and the number of owners")]
+    InvalidThreshold,
+    
+    #[msg("Not enough multisig owners signed")]
+    NotEnoughSigners,
+    
+    #[msg("Quorum must be 1-10000 basis points and the voting period positive")]
+    InvalidGovernanceConfig,
+    
+    #[msg("Description URI is too long")]
+    DescriptionTooLong,
+    
+    #[msg("Not enough escrowed tokens")]
+    InsufficientVotingPower,
+    
+    #[msg("Proposal is not active")]
+    ProposalNotActive,
+    
+    #[msg("Voting has closed")]
+    VotingClosed,
+    
+    #[msg("Voting is still open")]
+    VotingStillOpen,
+    
+    #[msg("Escrowed tokens are locked until voting closes")]
+    TokensLocked,
+    
+    #[msg("Liquidation states can only be entered through a liquidation")]
+    InvalidStatusChange,
+    
+    #[msg("Liquidation proceeds are too low")]
+    ProceedsTooLow,
+    
+    #[msg("Liquidation and mint accounts are required for this action")]
+    MissingLiquidationAccounts,
+    
+    #[msg("Liquidation is not pending")]
+    LiquidationNotPending,
+    
+    #[msg("Liquidation is not active")]
+    LiquidationNotActive,
+    
+    #[msg("Liquidation still has tokens to redeem")]
+    LiquidationNotComplete,
+    
+    #[msg("Proposal is still active")]
+    ProposalStillActive,
+    
+    #[msg("Voter record still holds escrowed tokens")]
+    VoterRecordNotEmpty,
+    
+    #[msg("Proposal already has votes")]
+    ProposalHasVotes,
+    
+    #[msg("Mint authority must be revoked or held by the liquidation account")]
+    MintAuthorityNotRevoked,
 } 
\ No newline at end of file
@@ -16,11 +16,17 @@ pub mod registry {
         registry.created_at = Clock::get()?.unix_timestamp;
         registry.updated_at = Clock::get()?.unix_timestamp;
 
+        emit!(RegistryInitialized {
+            registry: registry.key(),
+            authority: registry.authority,
+            timestamp: registry.created_at,
+        });
+
         Ok(())
     }
 
     pub fn add_verifier(
-        ctx: Context<ManageVerifier>,
+        ctx: Context<AddVerifier>,
         verifier_name: String,
         verifier_url: String,
     ) -> Result<()> {
@@ -33,12 +39,20 @@ pub mod registry {
             RegistryError::Unauthorized
         );
         
+        // A removed verifier can be added again, but an active one would be
+        // counted twice
+        require!(
+            !verifier_account.is_active,
+            RegistryError::VerifierAlreadyActive
+        );
+        
         verifier_account.name = verifier_name;
         verifier_account.url = verifier_url;
-        verifier_account.authority = ctx.accounts.authority.key();
+        verifier_account.authority = ctx.accounts.verifier_authority.key();
         verifier_account.is_active = true;
-        verifier_account.verified_properties = 0;
-        verifier_account.created_at = Clock::get()?.unix_timestamp;
+        if verifier_account.created_at == 0 {
+            verifier_account.created_at = Clock::get()?.unix_timestamp;
+        }
         verifier_account.updated_at = Clock::get()?.unix_timestamp;
         
         // Update registry stats
@@ -47,19 +61,52 @@ pub mod registry {
             .ok_or(RegistryError::ArithmeticError)?;
         registry.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(VerifierAdded {
+            verifier: verifier_account.key(),
+            authority: verifier_account.authority,
+            name: verifier_account.name.clone(),
+            url: verifier_account.url.clone(),
+            verifier_count: registry.verifier_count,
+            timestamp: verifier_account.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn update_verifier(
+        ctx: Context<UpdateVerifier>,
+        verifier_name: String,
+        verifier_url: String,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let verifier_account = &mut ctx.accounts.verifier_account;
+        
+        verifier_account.name = verifier_name;
+        verifier_account.url = verifier_url;
+        verifier_account.updated_at = Clock::get()?.unix_timestamp;
+        registry.updated_at = Clock::get()?.unix_timestamp;
+        
+        emit!(VerifierUpdated {
+            verifier: verifier_account.key(),
+            authority: verifier_account.authority,
+            name: verifier_account.name.clone(),
+            url: verifier_account.url.clone(),
+            timestamp: verifier_account.updated_at,
+        });
+        
         Ok(())
     }
 
     pub fn remove_verifier(
-        ctx: Context<ManageVerifier>
+        ctx: Context<RemoveVerifier>
     ) -> Result<()> {
         let registry = &mut ctx.accounts.registry;
         let verifier_account = &mut ctx.accounts.verifier_account;
         
-        // Only registry authority can remove verifiers
+        // Removing twice would decrement `verifier_count` twice
         require!(
-            registry.authority == ctx.accounts.authority.key(),
-            RegistryError::Unauthorized
+            verifier_account.is_active,
+            RegistryError::VerifierNotActive
         );
         
         // Mark as inactive instead of completely removing
@@ -72,6 +119,13 @@ pub mod registry {
             .ok_or(RegistryError::ArithmeticError)?;
         registry.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(VerifierRemoved {
+            verifier: verifier_account.key(),
+            authority: verifier_account.authority,
+            verifier_count: registry.verifier_count,
+            timestamp: verifier_account.updated_at,
+        });
+        
         Ok(())
     }
 
@@ -90,6 +144,7 @@ pub mod registry {
         property_record.address = property_address;
         property_record.legal_details = legal_details;
         property_record.verification_status = VerificationStatus::Pending;
+        property_record.amendment_count = 0;
         property_record.created_at = Clock::get()?.unix_timestamp;
         property_record.updated_at = Clock::get()?.unix_timestamp;
         
@@ -99,6 +154,15 @@ pub mod registry {
             .ok_or(RegistryError::ArithmeticError)?;
         registry.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(PropertyRegistered {
+            property_record: property_record.key(),
+            property_id: property_record.property_id.clone(),
+            token_mint: property_record.token_mint,
+            owner: property_record.owner,
+            address: property_record.address.clone(),
+            timestamp: property_record.created_at,
+        });
+        
         Ok(())
     }
 
@@ -124,8 +188,8 @@ pub mod registry {
         
         // Update property record
         property_record.verification_status = VerificationStatus::Verified;
-        property_record.verification_details = Some(verification_details);
-        property_record.verifier = Some(ctx.accounts.verifier.key());
+        property_record.verification_details = Some(verification_details.clone());
+        property_record.verifier = Some(verifier.key());
         property_record.verified_at = Some(Clock::get()?.unix_timestamp);
         property_record.updated_at = Clock::get()?.unix_timestamp;
         
@@ -138,6 +202,15 @@ pub mod registry {
         // Update registry stats
         registry.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(PropertyVerified {
+            property_record: property_record.key(),
+            token_mint: property_record.token_mint,
+            verifier: verifier.key(),
+            verifier_authority: ctx.accounts.verifier_authority.key(),
+            verification_details: verification_details.clone(),
+            timestamp: registry.updated_at,
+        });
+        
         Ok(())
     }
 
@@ -154,14 +227,282 @@ pub mod registry {
             RegistryError::Unauthorized
         );
         
+        let previous_status = property_record.verification_status;
         property_record.verification_status = status;
         property_record.updated_at = Clock::get()?.unix_timestamp;
         registry.updated_at = Clock::get()?.unix_timestamp;
         
+        emit!(PropertyStatusUpdated {
+            property_record: property_record.key(),
+            token_mint: property_record.token_mint,
+            authority: ctx.accounts.authority.key(),
+            previous_status,
+            status: property_record.verification_status,
+            timestamp: property_record.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn amend_property_record(
+        ctx: Context<AmendPropertyRecord>,
+        property_address: Option<String>,
+        legal_details: Option<LegalDetails>,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let property_record = &mut ctx.accounts.property_record;
+        let amendment = &mut ctx.accounts.amendment;
+        let now = Clock::get()?.unix_timestamp;
+        
+        let address_changed = match &property_address {
+            Some(address) => *address != property_record.address,
+            None => false,
+        };
+        let (details_changed, material_details_change) = match &legal_details {
+            Some(details) => (
+                *details != property_record.legal_details,
+                details.is_material_change(&property_record.legal_details),
+            ),
+            None => (false, false),
+        };
+        require!(
+            address_changed || details_changed,
+            RegistryError::NothingToAmend
+        );
+        
+        record_amendment(
+            amendment,
+            property_record,
+            AmendmentKind::Details,
+            ctx.accounts.owner.key(),
+            None,
+            now,
+        )?;
+        
+        // A verification vouches for the address and legal identity of the
+        // property, so changing either needs a fresh one. Title deed links
+        // and sale history can be corrected without losing it.
+        let material_change = address_changed || material_details_change;
+        
+        if let Some(address) = property_address {
+            property_record.address = address;
+        }
+        if let Some(details) = legal_details {
+            property_record.legal_details = details;
+        }
+        
+        let previous_status = property_record.verification_status;
+        if material_change {
+            property_record.verification_status = VerificationStatus::Pending;
+            property_record.verification_details = None;
+            property_record.verifier = None;
+            property_record.verified_at = None;
+        }
+        property_record.updated_at = now;
+        registry.updated_at = now;
+        
+        emit!(PropertyRecordAmended {
+            property_record: property_record.key(),
+            token_mint: property_record.token_mint,
+            owner: property_record.owner,
+            amendment: amendment.key(),
+            index: amendment.index,
+            address: property_record.address.clone(),
+            previous_status,
+            status: property_record.verification_status,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn transfer_record_ownership(
+        ctx: Context<TransferRecordOwnership>,
+        owner_name: String,
+        owner_id: String,
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let property_record = &mut ctx.accounts.property_record;
+        let amendment = &mut ctx.accounts.amendment;
+        let new_owner = ctx.accounts.new_owner.key();
+        let now = Clock::get()?.unix_timestamp;
+        
+        require!(
+            new_owner != property_record.owner,
+            RegistryError::InvalidNewOwner
+        );
+        
+        // Only the verifier that verified the record can attest the change of
+        // legal owner and let the record keep its verification
+        let previous_status = property_record.verification_status;
+        if previous_status == VerificationStatus::Verified {
+            require!(
+                property_record.verifier == Some(ctx.accounts.verifier.key()),
+                RegistryError::VerifierMismatch
+            );
+        }
+        
+        record_amendment(
+            amendment,
+            property_record,
+            AmendmentKind::OwnershipTransfer,
+            ctx.accounts.owner.key(),
+            Some(ctx.accounts.verifier.key()),
+            now,
+        )?;
+        
+        let previous_owner = property_record.owner;
+        property_record.owner = new_owner;
+        property_record.legal_details.owner_name = owner_name;
+        property_record.legal_details.owner_id = owner_id;
+        
+        // A record that was not verified only had the transfer witnessed, so
+        // it goes back to `Pending` as after a material amendment
+        if previous_status != VerificationStatus::Verified {
+            property_record.verification_status = VerificationStatus::Pending;
+            property_record.verification_details = None;
+            property_record.verifier = None;
+            property_record.verified_at = None;
+        }
+        property_record.updated_at = now;
+        registry.updated_at = now;
+        
+        emit!(RecordOwnershipTransferred {
+            property_record: property_record.key(),
+            token_mint: property_record.token_mint,
+            previous_owner,
+            owner: property_record.owner,
+            verifier: ctx.accounts.verifier.key(),
+            amendment: amendment.key(),
+            index: amendment.index,
+            previous_status,
+            status: property_record.verification_status,
+            timestamp: now,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_verifier(
+        ctx: Context<CloseVerifier>
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let verifier_account = &ctx.accounts.verifier_account;
+        
+        // Active verifiers have to be removed first, which keeps
+        // `verifier_count` in step with the accounts that can verify
+        require!(
+            !verifier_account.is_active,
+            RegistryError::VerifierStillActive
+        );
+        
+        registry.updated_at = Clock::get()?.unix_timestamp;
+        
+        // The account and its rent go back to the registry authority
+        emit!(VerifierClosed {
+            verifier: verifier_account.key(),
+            authority: verifier_account.authority,
+            timestamp: registry.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_property_record(
+        ctx: Context<ClosePropertyRecord>
+    ) -> Result<()> {
+        let registry = &mut ctx.accounts.registry;
+        let property_record = &ctx.accounts.property_record;
+        
+        // Either the record's owner or the registry authority can clean up
+        require!(
+            ctx.accounts.authority.key() == property_record.owner
+                || ctx.accounts.authority.key() == registry.authority,
+            RegistryError::Unauthorized
+        );
+        
+        // Only a rejected record is final; every other status can still be
+        // verified or re-verified
+        require!(
+            property_record.verification_status == VerificationStatus::Rejected,
+            RegistryError::RecordNotRejected
+        );
+        
+        registry.property_count = registry.property_count
+            .checked_sub(1)
+            .ok_or(RegistryError::ArithmeticError)?;
+        registry.updated_at = Clock::get()?.unix_timestamp;
+        
+        // The record's rent goes back to the owner who paid for it
+        emit!(PropertyRecordClosed {
+            property_record: property_record.key(),
+            property_id: property_record.property_id.clone(),
+            token_mint: property_record.token_mint,
+            owner: property_record.owner,
+            property_count: registry.property_count,
+            timestamp: registry.updated_at,
+        });
+        
+        Ok(())
+    }
+
+    pub fn close_amendment(
+        ctx: Context<CloseAmendment>,
+    ) -> Result<()> {
+        let amendment = &ctx.accounts.amendment;
+        
+        // History is kept for as long as its record exists. A record that was
+        // closed and registered again restarts at index 0, and cannot reach
+        // this index while the old amendment still holds its address.
+        if !ctx.accounts.property_record.data_is_empty() {
+            let property_record = Account::<PropertyRecord>::try_from(&ctx.accounts.property_record)?;
+            require!(
+                property_record.amendment_count <= amendment.index,
+                RegistryError::RecordStillOpen
+            );
+        }
+        
+        // The amendment's rent goes back to the owner who paid for it
+        emit!(AmendmentClosed {
+            amendment: amendment.key(),
+            property_record: amendment.property_record,
+            index: amendment.index,
+            amended_by: amendment.amended_by,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        
         Ok(())
     }
 }
 
+/// Snapshots `property_record` into the next amendment history entry, before
+/// the caller changes it
+fn record_amendment(
+    amendment: &mut Account<PropertyAmendment>,
+    property_record: &mut Account<PropertyRecord>,
+    kind: AmendmentKind,
+    amended_by: Pubkey,
+    verifier: Option<Pubkey>,
+    now: i64,
+) -> Result<()> {
+    amendment.property_record = property_record.key();
+    amendment.index = property_record.amendment_count;
+    amendment.kind = kind;
+    amendment.amended_by = amended_by;
+    amendment.verifier = verifier;
+    amendment.previous_owner = property_record.owner;
+    amendment.previous_address = property_record.address.clone();
+    amendment.previous_legal_details = property_record.legal_details.clone();
+    amendment.previous_status = property_record.verification_status;
+    amendment.created_at = now;
+    
+    property_record.amendment_count = property_record.amendment_count
+        .checked_add(1)
+        .ok_or(RegistryError::ArithmeticError)?;
+    
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct InitializeRegistry<'info> {
     #[account(mut)]
@@ -181,7 +522,7 @@ pub struct InitializeRegistry<'info> {
 }
 
 #[derive(Accounts)]
-pub struct ManageVerifier<'info> {
+pub struct AddVerifier<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -193,11 +534,14 @@ pub struct ManageVerifier<'info> {
     )]
     pub registry: Account<'info, Registry>,
     
+    /// CHECK: Only the key is used; it signs the verifier's verifications
+    pub verifier_authority: AccountInfo<'info>,
+    
     #[account(
         init_if_needed,
         payer = authority,
         space = Verifier::LEN,
-        seeds = [b"verifier", authority.key().as_ref()],
+        seeds = [b"verifier", verifier_authority.key().as_ref()],
         bump
     )]
     pub verifier_account: Account<'info, Verifier>,
@@ -206,6 +550,46 @@ pub struct ManageVerifier<'info> {
     pub rent: Sysvar<'info, Rent>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateVerifier<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump,
+        constraint = registry.authority == authority.key() @ RegistryError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        seeds = [b"verifier", verifier_account.authority.as_ref()],
+        bump
+    )]
+    pub verifier_account: Account<'info, Verifier>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveVerifier<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump,
+        constraint = registry.authority == authority.key() @ RegistryError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        seeds = [b"verifier", verifier_account.authority.as_ref()],
+        bump
+    )]
+    pub verifier_account: Account<'info, Verifier>,
+}
+
 #[derive(Accounts)]
 #[instruction(property_id: String)]
 pub struct RegisterProperty<'info> {
@@ -274,6 +658,146 @@ pub struct UpdatePropertyStatus<'info> {
     pub property_record: Account<'info, PropertyRecord>,
 }
 
+#[derive(Accounts)]
+pub struct AmendPropertyRecord<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        has_one = owner @ RegistryError::Unauthorized
+    )]
+    pub property_record: Account<'info, PropertyRecord>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = PropertyAmendment::LEN,
+        seeds = [
+            b"amendment",
+            property_record.key().as_ref(),
+            &property_record.amendment_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub amendment: Account<'info, PropertyAmendment>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TransferRecordOwnership<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    /// Signs to accept the record, so it cannot be handed to a key nobody
+    /// controls
+    pub new_owner: Signer<'info>,
+    
+    pub verifier_authority: Signer<'info>,
+    
+    #[account(
+        constraint = verifier.authority == verifier_authority.key() @ RegistryError::Unauthorized,
+        constraint = verifier.is_active @ RegistryError::VerifierNotActive
+    )]
+    pub verifier: Account<'info, Verifier>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        has_one = owner @ RegistryError::Unauthorized
+    )]
+    pub property_record: Account<'info, PropertyRecord>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = PropertyAmendment::LEN,
+        seeds = [
+            b"amendment",
+            property_record.key().as_ref(),
+            &property_record.amendment_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub amendment: Account<'info, PropertyAmendment>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseVerifier<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump,
+        constraint = registry.authority == authority.key() @ RegistryError::Unauthorized
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(mut, close = authority)]
+    pub verifier_account: Account<'info, Verifier>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePropertyRecord<'info> {
+    /// The record's owner or the registry authority
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+    
+    #[account(
+        mut,
+        has_one = owner,
+        close = owner
+    )]
+    pub property_record: Account<'info, PropertyRecord>,
+    
+    /// CHECK: receives the record's rent; checked against `property_record.owner`
+    #[account(mut)]
+    pub owner: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseAmendment<'info> {
+    #[account(
+        mut,
+        has_one = property_record,
+        has_one = amended_by,
+        close = amended_by
+    )]
+    pub amendment: Account<'info, PropertyAmendment>,
+    
+    /// CHECK: the amended record, which may already be closed; checked
+    /// against `amendment.property_record`
+    pub property_record: AccountInfo<'info>,
+    
+    /// CHECK: receives the amendment's rent; checked against `amendment.amended_by`
+    #[account(mut)]
+    pub amended_by: AccountInfo<'info>,
+}
+
 #[account]
 pub struct Registry {
     pub authority: Pubkey,
@@ -325,6 +849,7 @@ pub struct PropertyRecord {
     pub verification_details: Option<VerificationDetails>,
     pub verifier: Option<Pubkey>,
     pub verified_at: Option<i64>,
+    pub amendment_count: u64,
     pub created_at: i64,
     pub updated_at: i64,
 }
@@ -340,11 +865,42 @@ impl PropertyRecord {
         (1 + VerificationDetails::LEN) + // Option<VerificationDetails>
         (1 + 32) + // Option<Pubkey>
         (1 + 8) + // Option<i64>
+        8 + // amendment_count
         8 + // created_at
         8; // updated_at
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+/// One entry in a property record's amendment history, holding the record's
+/// state from before the change
+#[account]
+pub struct PropertyAmendment {
+    pub property_record: Pubkey,
+    pub index: u64,
+    pub kind: AmendmentKind,
+    pub amended_by: Pubkey,
+    pub verifier: Option<Pubkey>, // co-signing verifier for ownership transfers
+    pub previous_owner: Pubkey,
+    pub previous_address: String,
+    pub previous_legal_details: LegalDetails,
+    pub previous_status: VerificationStatus,
+    pub created_at: i64,
+}
+
+impl PropertyAmendment {
+    pub const LEN: usize = 8 + // discriminator
+        32 + // property_record pubkey
+        8 + // index
+        1 + // kind
+        32 + // amended_by pubkey
+        (1 + 32) + // Option<Pubkey>
+        32 + // previous_owner pubkey
+        256 + // previous_address (max 256 chars)
+        LegalDetails::LEN + // previous_legal_details
+        1 + // previous_status
+        8; // created_at
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
 pub struct LegalDetails {
     pub title_deed_url: String,
     pub owner_name: String,
@@ -368,6 +924,17 @@ impl LegalDetails {
         32 + // zoning (max 32 chars)
         8 + // last_sale_date
         8; // last_sale_amount
+
+    /// Whether `other` describes a different property or legal owner. The
+    /// title deed link and sale history are not material.
+    pub fn is_material_change(&self, other: &LegalDetails) -> bool {
+        self.owner_name != other.owner_name
+            || self.owner_id != other.owner_id
+            || self.legal_description != other.legal_description
+            || self.jurisdiction != other.jurisdiction
+            || self.property_type != other.property_type
+            || self.zoning != other.zoning
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
@@ -392,6 +959,12 @@ impl VerificationDetails {
         8; // verification_expiry
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
+pub enum AmendmentKind {
+    Details,
+    OwnershipTransfer,
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq)]
 pub enum VerificationStatus {
     Pending,
@@ -401,6 +974,123 @@ pub enum VerificationStatus {
     Expired,
 }
 
+#[event]
+pub struct RegistryInitialized {
+    pub registry: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerifierAdded {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub url: String,
+    pub verifier_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerifierUpdated {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub url: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerifierRemoved {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub verifier_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyRegistered {
+    pub property_record: Pubkey,
+    pub property_id: String,
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub address: String,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyVerified {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub verifier: Pubkey,
+    pub verifier_authority: Pubkey,
+    pub verification_details: VerificationDetails,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyStatusUpdated {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub previous_status: VerificationStatus,
+    pub status: VerificationStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyRecordAmended {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub amendment: Pubkey,
+    pub index: u64,
+    pub address: String,
+    pub previous_status: VerificationStatus,
+    pub status: VerificationStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct RecordOwnershipTransferred {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub verifier: Pubkey,
+    pub amendment: Pubkey,
+    pub index: u64,
+    pub previous_status: VerificationStatus,
+    pub status: VerificationStatus,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VerifierClosed {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PropertyRecordClosed {
+    pub property_record: Pubkey,
+    pub property_id: String,
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub property_count: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AmendmentClosed {
+    pub amendment: Pubkey,
+    pub property_record: Pubkey,
+    pub index: u64,
+    pub amended_by: Pubkey,
+    pub timestamp: i64,
+}
+
 #[error_code]
 pub enum RegistryError {
     #[msg("Unauthorized operation")]
@@ -411,4 +1101,25 @@ pub enum RegistryError {
     
     #[msg("Verifier is not active")]
     VerifierNotActive,
+    
+    #[msg("Verifier is already active")]
+    VerifierAlreadyActive,
+    
+    #[msg("Verifier must be removed before it is closed")]
+    VerifierStillActive,
+    
+    #[msg("Only rejected property records can be closed")]
+    RecordNotRejected,
+    
+    #[msg("Amendment does not change the record")]
+    NothingToAmend,
+    
+    #[msg("New owner must differ from the current owner")]
+    InvalidNewOwner,
+    
+    #[msg("Amendment history is kept while its property record exists")]
+    RecordStillOpen,
+    
+    #[msg("Only the verifier of a verified record can co-sign its transfer")]
+    VerifierMismatch,
 } 
\ No newline at end of file
@@ -0,0 +1,361 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { createAccount, createMint, getAccount, mintTo, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+
+import {
+  findPoolAddress,
+  findPoolLpMintAddress,
+  findPoolPropertyVaultAddress,
+  findPoolQuoteVaultAddress,
+} from '../../src/amm/pda';
+import { findMarketplaceAddress } from '../../src/marketplace/pda';
+
+const POOL_FEE_BPS = 30; // 0.30%
+const MARKETPLACE_FEE_BPS = 100; // the marketplace suite expects this fee
+const MINIMUM_LIQUIDITY = 1000;
+const PROPERTY_DECIMALS = 2;
+const USDC_DECIMALS = 6;
+
+describe('amm', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Amm as Program;
+  const marketplaceProgram = anchor.workspace.Marketplace as Program;
+  const [marketplacePda] = findMarketplaceAddress(marketplaceProgram.programId);
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const liquidityProvider = Keypair.generate();
+  const trader = Keypair.generate();
+
+  let propertyMint: PublicKey;
+  let usdc: PublicKey;
+  let pool: PublicKey;
+  let lpMint: PublicKey;
+  let propertyVault: PublicKey;
+  let quoteVault: PublicKey;
+
+  let providerProperty: PublicKey;
+  let providerUsdc: PublicKey;
+  let providerLp: PublicKey;
+  let traderProperty: PublicKey;
+  let traderUsdc: PublicKey;
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    const latest = await connection.getLatestBlockhash();
+    await connection.confirmTransaction({ signature, ...latest });
+  }
+
+  async function amount(account: PublicKey): Promise<number> {
+    return Number((await getAccount(connection, account)).amount);
+  }
+
+  async function reserves(): Promise<[number, number, number]> {
+    const state = await program.account.pool.fetch(pool);
+    return [
+      (state.propertyReserve as anchor.BN).toNumber(),
+      (state.quoteReserve as anchor.BN).toNumber(),
+      (state.lpSupply as anchor.BN).toNumber(),
+    ];
+  }
+
+  function addLiquidity(maxProperty: number, maxQuote: number, minLp = 0) {
+    return program.methods
+      .addLiquidity(new anchor.BN(maxProperty), new anchor.BN(maxQuote), new anchor.BN(minLp))
+      .accounts({
+        provider: liquidityProvider.publicKey,
+        marketplace: marketplacePda,
+        propertyRecord: null,
+        pool,
+        lpMint,
+        propertyVault,
+        quoteVault,
+        providerPropertyAccount: providerProperty,
+        providerQuoteAccount: providerUsdc,
+        providerLpAccount: providerLp,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([liquidityProvider])
+      .rpc();
+  }
+
+  function initializePool(authority: Keypair, fee: number) {
+    return program.methods
+      .initializePool(fee)
+      .accounts({
+        authority: authority.publicKey,
+        marketplace: marketplacePda,
+        propertyTokenMint: propertyMint,
+        quoteMint: usdc,
+        pool,
+        lpMint,
+        propertyVault,
+        quoteVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([authority])
+      .rpc();
+  }
+
+  function updatePoolFee(authority: Keypair, fee: number) {
+    return program.methods
+      .updatePoolFee(fee)
+      .accounts({ authority: authority.publicKey, marketplace: marketplacePda, pool })
+      .signers([authority])
+      .rpc();
+  }
+
+  function setQuoteMintAllowed(allowed: boolean) {
+    const method = allowed ? marketplaceProgram.methods.addQuoteMint : marketplaceProgram.methods.removeQuoteMint;
+    return method(usdc).accounts({ authority: payer.publicKey, marketplace: marketplacePda }).rpc();
+  }
+
+  function setPaused(paused: boolean) {
+    return marketplaceProgram.methods
+      .setPaused(paused)
+      .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+      .rpc();
+  }
+
+  function swap(side: 'buy' | 'sell', amountIn: number, minAmountOut: number) {
+    return program.methods
+      .swap({ [side]: {} }, new anchor.BN(amountIn), new anchor.BN(minAmountOut))
+      .accounts({
+        trader: trader.publicKey,
+        marketplace: marketplacePda,
+        propertyRecord: null,
+        pool,
+        propertyVault,
+        quoteVault,
+        traderPropertyAccount: traderProperty,
+        traderQuoteAccount: traderUsdc,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([trader])
+      .rpc();
+  }
+
+  // `a * b / c` without losing precision past 2^53
+  function mulDiv(a: number, b: number, c: number, roundUp = false): number {
+    const product = new anchor.BN(a).mul(new anchor.BN(b));
+    const quotient = product.div(new anchor.BN(c));
+    const exact = quotient.mul(new anchor.BN(c)).eq(product);
+    return quotient.toNumber() + (roundUp && !exact ? 1 : 0);
+  }
+
+  // Mirrors Pool::swap_output
+  function expectedOutput(amountIn: number, reserveIn: number, reserveOut: number): [number, number] {
+    const feeAmount = Math.floor((amountIn * POOL_FEE_BPS) / 10000);
+    const netIn = amountIn - feeAmount;
+    return [mulDiv(reserveOut, netIn, reserveIn + netIn), feeAmount];
+  }
+
+  before(async () => {
+    await airdrop(liquidityProvider.publicKey, LAMPORTS_PER_SOL);
+    await airdrop(trader.publicKey, LAMPORTS_PER_SOL);
+
+    propertyMint = await createMint(connection, payer, payer.publicKey, null, PROPERTY_DECIMALS);
+    usdc = await createMint(connection, payer, payer.publicKey, null, USDC_DECIMALS);
+
+    [pool] = findPoolAddress(program.programId, propertyMint, usdc);
+    [lpMint] = findPoolLpMintAddress(program.programId, pool);
+    [propertyVault] = findPoolPropertyVaultAddress(program.programId, pool);
+    [quoteVault] = findPoolQuoteVaultAddress(program.programId, pool);
+
+    // Pools are opened by the marketplace authority, so the marketplace has
+    // to exist first. It is a singleton shared with the marketplace suite.
+    const existing = await marketplaceProgram.account.marketplace.fetchNullable(marketplacePda);
+    if (!existing) {
+      await marketplaceProgram.methods
+        .initializeMarketplace(MARKETPLACE_FEE_BPS)
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          treasury: Keypair.generate().publicKey,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    }
+
+    try {
+      await initializePool(trader, POOL_FEE_BPS);
+      expect.fail('pool creation should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('Unauthorized');
+    }
+    try {
+      await initializePool(payer, POOL_FEE_BPS);
+      expect.fail('pool creation should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('QuoteMintNotAllowed');
+    }
+    await setQuoteMintAllowed(true);
+    await initializePool(payer, POOL_FEE_BPS);
+
+    providerProperty = await createAccount(connection, payer, propertyMint, liquidityProvider.publicKey);
+    providerUsdc = await createAccount(connection, payer, usdc, liquidityProvider.publicKey);
+    providerLp = await createAccount(connection, payer, lpMint, liquidityProvider.publicKey);
+    traderProperty = await createAccount(connection, payer, propertyMint, trader.publicKey);
+    traderUsdc = await createAccount(connection, payer, usdc, trader.publicKey);
+
+    await mintTo(connection, payer, propertyMint, providerProperty, payer, 200_000);
+    await mintTo(connection, payer, usdc, providerUsdc, payer, 2_000_000_000);
+    await mintTo(connection, payer, propertyMint, traderProperty, payer, 10_000);
+    await mintTo(connection, payer, usdc, traderUsdc, payer, 100_000_000);
+  });
+
+  // Leave the shared marketplace's quote mints as the other suites expect them
+  after(async () => {
+    await setQuoteMintAllowed(false);
+  });
+
+  it('sets the price with the first deposit and locks the minimum liquidity', async () => {
+    // 1,000 whole tokens against 1,000 USDC
+    await addLiquidity(100_000, 1_000_000_000);
+
+    const liquidity = Math.sqrt(100_000 * 1_000_000_000);
+    expect(await amount(providerLp)).to.equal(liquidity - MINIMUM_LIQUIDITY);
+    expect(await reserves()).to.deep.equal([100_000, 1_000_000_000, liquidity]);
+    expect(await amount(propertyVault)).to.equal(100_000);
+    expect(await amount(quoteVault)).to.equal(1_000_000_000);
+
+    // 1 USDC per whole property token
+    const price = await program.methods.getPrice().accounts({ pool }).view();
+    expect((price as anchor.BN).toNumber()).to.equal(1_000_000);
+  });
+
+  it('quotes and executes a swap, keeping the fee in the pool', async () => {
+    const [propertyReserve, quoteReserve] = await reserves();
+    const [amountOut, feeAmount] = expectedOutput(1_000, propertyReserve, quoteReserve);
+    expect(feeAmount).to.equal(3);
+
+    const quoted = await program.methods
+      .quoteSwap({ sell: {} }, new anchor.BN(1_000))
+      .accounts({ pool })
+      .view();
+    expect((quoted as anchor.BN).toNumber()).to.equal(amountOut);
+
+    const usdcBefore = await amount(traderUsdc);
+    await swap('sell', 1_000, amountOut);
+
+    expect(await amount(traderUsdc)).to.equal(usdcBefore + amountOut);
+    expect(await amount(traderProperty)).to.equal(9_000);
+    // The whole input, fee included, joins the reserve
+    const [propertyAfter, quoteAfter] = await reserves();
+    expect(propertyAfter).to.equal(propertyReserve + 1_000);
+    expect(quoteAfter).to.equal(quoteReserve - amountOut);
+    expect(propertyAfter * quoteAfter).to.be.greaterThan(propertyReserve * quoteReserve);
+  });
+
+  it('rejects a swap worse than the trader\'s minimum', async () => {
+    const [propertyReserve, quoteReserve] = await reserves();
+    const [amountOut] = expectedOutput(10_000_000, quoteReserve, propertyReserve);
+
+    try {
+      await swap('buy', 10_000_000, amountOut + 1);
+      expect.fail('swap should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('SlippageExceeded');
+    }
+
+    await swap('buy', 10_000_000, amountOut);
+    expect(await amount(traderProperty)).to.equal(9_000 + amountOut);
+  });
+
+  it('stops swaps and deposits while the marketplace is paused', async () => {
+    await setPaused(true);
+    try {
+      try {
+        await swap('buy', 1_000_000, 0);
+        expect.fail('swap should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('MarketplacePaused');
+      }
+      try {
+        await addLiquidity(1_000, 10_000_000);
+        expect.fail('deposit should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('MarketplacePaused');
+      }
+    } finally {
+      await setPaused(false);
+    }
+  });
+
+  it('takes deposits at the reserve ratio', async () => {
+    const [propertyReserve, quoteReserve, lpSupply] = await reserves();
+    const lpBefore = await amount(providerLp);
+    const propertyBefore = await amount(providerProperty);
+    const usdcBefore = await amount(providerUsdc);
+
+    // Offer far more USDC than the property side supports
+    await addLiquidity(10_000, 1_000_000_000);
+
+    const lpAmount = mulDiv(10_000, lpSupply, propertyReserve);
+    const quoteTaken = mulDiv(lpAmount, quoteReserve, lpSupply, true);
+    expect(await amount(providerLp)).to.equal(lpBefore + lpAmount);
+    expect(propertyBefore - (await amount(providerProperty))).to.be.at.most(10_000);
+    expect(usdcBefore - (await amount(providerUsdc))).to.equal(quoteTaken);
+  });
+
+  it('pays withdrawals pro rata from both reserves', async () => {
+    const [propertyReserve, quoteReserve, lpSupply] = await reserves();
+    const lpAmount = await amount(providerLp);
+    const propertyBefore = await amount(providerProperty);
+    const usdcBefore = await amount(providerUsdc);
+
+    await program.methods
+      .removeLiquidity(new anchor.BN(lpAmount), new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        provider: liquidityProvider.publicKey,
+        pool,
+        lpMint,
+        propertyVault,
+        quoteVault,
+        providerLpAccount: providerLp,
+        providerPropertyAccount: providerProperty,
+        providerQuoteAccount: providerUsdc,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([liquidityProvider])
+      .rpc();
+
+    const propertyOut = mulDiv(lpAmount, propertyReserve, lpSupply);
+    const quoteOut = mulDiv(lpAmount, quoteReserve, lpSupply);
+    expect(await amount(providerLp)).to.equal(0);
+    expect(await amount(providerProperty)).to.equal(propertyBefore + propertyOut);
+    expect(await amount(providerUsdc)).to.equal(usdcBefore + quoteOut);
+
+    // The locked minimum keeps a sliver of both reserves in the pool
+    const [propertyAfter, quoteAfter, lpAfter] = await reserves();
+    expect(lpAfter).to.equal(MINIMUM_LIQUIDITY);
+    expect(propertyAfter).to.be.greaterThan(0);
+    expect(quoteAfter).to.be.greaterThan(0);
+  });
+
+  it('only lets the marketplace authority change the fee', async () => {
+    try {
+      await updatePoolFee(trader, 100);
+      expect.fail('fee update should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('Unauthorized');
+    }
+
+    try {
+      await updatePoolFee(payer, 1001);
+      expect.fail('fee update should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('FeeTooHigh');
+    }
+
+    await updatePoolFee(payer, 100);
+    expect((await program.account.pool.fetch(pool)).fee).to.equal(100);
+  });
+});
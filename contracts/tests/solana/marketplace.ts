@@ -0,0 +1,2238 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import {
+  createAccount,
+  createMint,
+  getAccount,
+  mintTo,
+  NATIVE_MINT,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js';
+import { expect } from 'chai';
+
+import {
+  fetchNextListingIndex,
+  findAuctionAddress,
+  findAuctionBidAddress,
+  findEscrowAddress,
+  findListingAddress,
+  findMarketplaceAddress,
+  findNextListingAddress,
+  findOfferAddress,
+  findOpenOrdersAddress,
+  findOrderBookAddress,
+  findOrderBookVaultAddresses,
+  findMintFeeConfigAddress,
+  findMintStatsAddress,
+  findPriceOracleAddress,
+  findSellerListingsAddress,
+  findTraderVolumeAddress,
+} from '../../src/marketplace/pda';
+
+const MARKETPLACE_FEE_BPS = 100; // 1.00%
+const PRICE_PER_TOKEN = 1_000_000; // lamports
+const TRADER_VOLUME_LEN = 129; // TraderVolume::LEN
+const MINT_STATS_LEN = 145; // MintStats::LEN
+const PRICE_ORACLE_LEN = 2447; // PriceOracle::LEN
+
+describe('marketplace', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Marketplace as Program;
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const seller = Keypair.generate();
+  const buyer = Keypair.generate();
+
+  const [marketplacePda] = findMarketplaceAddress(program.programId);
+
+  let treasury: PublicKey;
+  let mint: PublicKey;
+  let sellerTokenAccount: PublicKey;
+  let buyerTokenAccount: PublicKey;
+  let listing: PublicKey;
+  let escrow: PublicKey;
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    const latest = await connection.getLatestBlockhash();
+    await connection.confirmTransaction({ signature, ...latest });
+  }
+
+  async function balance(account: PublicKey): Promise<number> {
+    return connection.getBalance(account, 'confirmed');
+  }
+
+  async function createListing(
+    propertyMint: PublicKey,
+    sourceTokenAccount: PublicKey,
+    quoteMint: PublicKey,
+    pricePerToken: number,
+    tokenAmount: number,
+    expiresAt = 0,
+    propertyRecord: PublicKey | null = null
+  ): Promise<[PublicKey, PublicKey]> {
+    const [sellerListings] = findSellerListingsAddress(program.programId, seller.publicKey);
+    const [listingPda] = await findNextListingAddress(connection, program.programId, seller.publicKey);
+    const [escrowPda] = findEscrowAddress(program.programId, listingPda);
+
+    await program.methods
+      .createListing(new anchor.BN(pricePerToken), new anchor.BN(tokenAmount), new anchor.BN(expiresAt))
+      .accounts({
+        seller: seller.publicKey,
+        sellerListings,
+        listing: listingPda,
+        propertyTokenMint: propertyMint,
+        quoteMint,
+        propertyRecord,
+        sellerTokenAccount: sourceTokenAccount,
+        escrowTokenAccount: escrowPda,
+        marketplace: marketplacePda,
+        sellerVolume: findTraderVolumeAddress(program.programId, seller.publicKey, quoteMint)[0],
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([seller])
+      .rpc();
+    return [listingPda, escrowPda];
+  }
+
+  // Auctions `seller` a lot of the main mint, settled in SOL
+  async function createAuction(
+    kind: object,
+    tokenAmount: number,
+    startPrice: number,
+    startTime: number,
+    endTime: number
+  ): Promise<[PublicKey, PublicKey]> {
+    const index = await fetchNextListingIndex(connection, program.programId, seller.publicKey);
+    const [auctionPda] = findAuctionAddress(program.programId, seller.publicKey, index);
+    const [escrowPda] = findEscrowAddress(program.programId, auctionPda);
+
+    await program.methods
+      .createAuction(
+        kind,
+        new anchor.BN(tokenAmount),
+        new anchor.BN(startPrice),
+        new anchor.BN(startTime),
+        new anchor.BN(endTime)
+      )
+      .accounts({
+        seller: seller.publicKey,
+        sellerListings: findSellerListingsAddress(program.programId, seller.publicKey)[0],
+        auction: auctionPda,
+        propertyTokenMint: mint,
+        quoteMint: NATIVE_MINT,
+        propertyRecord: null,
+        sellerTokenAccount,
+        escrowTokenAccount: escrowPda,
+        quoteVault: null,
+        marketplace: marketplacePda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([seller])
+      .rpc();
+    return [auctionPda, escrowPda];
+  }
+
+  // Fee schedule and stats accounts for a trade against one of `seller`'s
+  // listings, with no royalty or referrer
+  function tradeAccounts(
+    tradeBuyer: PublicKey,
+    quoteMint: PublicKey = NATIVE_MINT,
+    propertyMint: PublicKey = mint
+  ) {
+    return {
+      mintFeeConfig: findMintFeeConfigAddress(program.programId, propertyMint)[0],
+      royaltyRecipient: null,
+      royaltyQuoteAccount: null,
+      referrer: null,
+      referrerQuoteAccount: null,
+      buyerVolume: findTraderVolumeAddress(program.programId, tradeBuyer, quoteMint)[0],
+      sellerVolume: findTraderVolumeAddress(program.programId, seller.publicKey, quoteMint)[0],
+      mintStats: findMintStatsAddress(program.programId, propertyMint, quoteMint)[0],
+      priceOracle: findPriceOracleAddress(program.programId, propertyMint, quoteMint)[0],
+    };
+  }
+
+  function executeTrade(
+    tokenAmount: number,
+    maxPricePerToken = PRICE_PER_TOKEN,
+    target: [PublicKey, PublicKey] = [listing, escrow]
+  ) {
+    // The provider wallet pays transaction fees so the buyer's balance only
+    // reflects settlement
+    return program.methods
+      .executeTrade(
+        new anchor.BN(tokenAmount),
+        new anchor.BN(maxPricePerToken),
+        new anchor.BN(tokenAmount),
+        false
+      )
+      .accounts({
+        buyer: buyer.publicKey,
+        seller: seller.publicKey,
+        listing: target[0],
+        marketplace: marketplacePda,
+        marketplaceTreasury: treasury,
+        escrowTokenAccount: target[1],
+        buyerTokenAccount,
+        buyerQuoteAccount: null,
+        sellerQuoteAccount: null,
+        treasuryQuoteAccount: null,
+        propertyRecord: null,
+        ...tradeAccounts(buyer.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([buyer])
+      .rpc();
+  }
+
+  before(async () => {
+    await airdrop(seller.publicKey, 2 * LAMPORTS_PER_SOL);
+    await airdrop(buyer.publicKey, 2 * LAMPORTS_PER_SOL);
+
+    // The marketplace is a singleton, so reuse it if another suite created it
+    const existing = await program.account.marketplace.fetchNullable(marketplacePda);
+    if (existing) {
+      treasury = existing.treasury as PublicKey;
+      expect(existing.fee).to.equal(MARKETPLACE_FEE_BPS);
+    } else {
+      treasury = Keypair.generate().publicKey;
+      await program.methods
+        .initializeMarketplace(MARKETPLACE_FEE_BPS)
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          treasury,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    }
+    // Keep the treasury rent exempt so small fee transfers are accepted
+    await airdrop(treasury, LAMPORTS_PER_SOL);
+
+    mint = await createMint(connection, payer, payer.publicKey, null, 0);
+    sellerTokenAccount = await createAccount(connection, payer, mint, seller.publicKey);
+    buyerTokenAccount = await createAccount(connection, payer, mint, buyer.publicKey);
+    await mintTo(connection, payer, mint, sellerTokenAccount, payer, 100);
+
+    [listing, escrow] = await createListing(
+      mint,
+      sellerTokenAccount,
+      NATIVE_MINT,
+      PRICE_PER_TOKEN,
+      100
+    );
+  });
+
+  it('numbers listings per seller so their addresses can be derived', async () => {
+    expect(listing.equals(findListingAddress(program.programId, seller.publicKey, 0)[0])).to.be.true;
+
+    const state = await program.account.listing.fetch(listing);
+    expect((state.index as anchor.BN).toNumber()).to.equal(0);
+
+    const [sellerListings] = findSellerListingsAddress(program.programId, seller.publicKey);
+    const counter = await program.account.sellerListings.fetch(sellerListings);
+    expect((counter.listingCount as anchor.BN).toNumber()).to.equal(1);
+  });
+
+  it('escrows the listed tokens', async () => {
+    expect(Number((await getAccount(connection, escrow)).amount)).to.equal(100);
+    expect(Number((await getAccount(connection, sellerTokenAccount)).amount)).to.equal(0);
+  });
+
+  it('charges the buyer exactly total_price and pays the fee to the treasury', async () => {
+    const tokenAmount = 40;
+    const totalPrice = tokenAmount * PRICE_PER_TOKEN;
+    const feeAmount = Math.floor((totalPrice * MARKETPLACE_FEE_BPS) / 10000);
+
+    const buyerBefore = await balance(buyer.publicKey);
+    const sellerBefore = await balance(seller.publicKey);
+    const treasuryBefore = await balance(treasury);
+
+    // The first trade in a quote mint also opens both traders' volume accounts
+    // and the mint's stats and price oracle
+    const statsRent =
+      2 * (await connection.getMinimumBalanceForRentExemption(TRADER_VOLUME_LEN)) +
+      (await connection.getMinimumBalanceForRentExemption(MINT_STATS_LEN)) +
+      (await connection.getMinimumBalanceForRentExemption(PRICE_ORACLE_LEN));
+
+    await executeTrade(tokenAmount);
+
+    expect(buyerBefore - (await balance(buyer.publicKey))).to.equal(totalPrice + statsRent);
+    expect((await balance(seller.publicKey)) - sellerBefore).to.equal(totalPrice - feeAmount);
+    expect((await balance(treasury)) - treasuryBefore).to.equal(feeAmount);
+
+    expect(Number((await getAccount(connection, buyerTokenAccount)).amount)).to.equal(tokenAmount);
+    const state = await program.account.listing.fetch(listing);
+    expect((state.tokenAmount as anchor.BN).toNumber()).to.equal(60);
+  });
+
+  it('records market data for the mint and both traders', async () => {
+    const [mintStats] = findMintStatsAddress(program.programId, mint, NATIVE_MINT);
+    const stats = await program.account.mintStats.fetch(mintStats);
+    expect((stats.tradeCount as anchor.BN).toNumber()).to.equal(1);
+    expect((stats.tokenVolume as anchor.BN).toNumber()).to.equal(40);
+    expect((stats.volume as anchor.BN).toNumber()).to.equal(40 * PRICE_PER_TOKEN);
+    for (const price of [stats.lastPrice, stats.vwap, stats.highPrice, stats.lowPrice]) {
+      expect((price as anchor.BN).toNumber()).to.equal(PRICE_PER_TOKEN);
+    }
+
+    const [buyerVolume] = findTraderVolumeAddress(program.programId, buyer.publicKey, NATIVE_MINT);
+    const [sellerVolume] = findTraderVolumeAddress(program.programId, seller.publicKey, NATIVE_MINT);
+    const bought = await program.account.traderVolume.fetch(buyerVolume);
+    const sold = await program.account.traderVolume.fetch(sellerVolume);
+    expect((bought.tokensBought as anchor.BN).toNumber()).to.equal(40);
+    expect((bought.tokensSold as anchor.BN).toNumber()).to.equal(0);
+    expect((sold.soldVolume as anchor.BN).toNumber()).to.equal(40 * PRICE_PER_TOKEN);
+  });
+
+  it('derives a TWAP from the price oracle once its history covers the window', async () => {
+    const [priceOracle] = findPriceOracleAddress(program.programId, mint, NATIVE_MINT);
+    const oracle = await program.account.priceOracle.fetch(priceOracle);
+    expect((oracle.lastPrice as anchor.BN).toNumber()).to.equal(PRICE_PER_TOKEN);
+    expect(oracle.observations).to.have.length(1);
+
+    try {
+      await program.methods.getTwap(new anchor.BN(86400)).accounts({ priceOracle }).view();
+      expect.fail('TWAP should need a day of history');
+    } catch (err) {
+      expect(`${err}`).to.include('OracleHistoryTooShort');
+    }
+
+    // Let the clock move past the first observation
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    const twap = await program.methods.getTwap(new anchor.BN(1)).accounts({ priceOracle }).view();
+    expect((twap as anchor.BN).toNumber()).to.equal(PRICE_PER_TOKEN);
+  });
+
+  it('rejects a trade the buyer cannot pay for without moving funds', async () => {
+    const broke = Keypair.generate();
+    await airdrop(broke.publicKey, LAMPORTS_PER_SOL / 100);
+    const brokeTokenAccount = await createAccount(connection, payer, mint, broke.publicKey);
+
+    const sellerBefore = await balance(seller.publicKey);
+    const treasuryBefore = await balance(treasury);
+
+    try {
+      await program.methods
+        .executeTrade(new anchor.BN(60), new anchor.BN(PRICE_PER_TOKEN), new anchor.BN(60), false)
+        .accounts({
+          buyer: broke.publicKey,
+          seller: seller.publicKey,
+          listing,
+          marketplace: marketplacePda,
+          marketplaceTreasury: treasury,
+          escrowTokenAccount: escrow,
+          buyerTokenAccount: brokeTokenAccount,
+          buyerQuoteAccount: null,
+          sellerQuoteAccount: null,
+          treasuryQuoteAccount: null,
+          propertyRecord: null,
+          ...tradeAccounts(broke.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([broke])
+        .rpc();
+      expect.fail('trade should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('InsufficientFunds');
+    }
+
+    expect(await balance(seller.publicKey)).to.equal(sellerBefore);
+    expect(await balance(treasury)).to.equal(treasuryBefore);
+  });
+
+  it('rejects a treasury other than the marketplace treasury', async () => {
+    const impostor = Keypair.generate().publicKey;
+    try {
+      await program.methods
+        .executeTrade(new anchor.BN(1), new anchor.BN(PRICE_PER_TOKEN), new anchor.BN(1), false)
+        .accounts({
+          buyer: buyer.publicKey,
+          seller: seller.publicKey,
+          listing,
+          marketplace: marketplacePda,
+          marketplaceTreasury: impostor,
+          escrowTokenAccount: escrow,
+          buyerTokenAccount,
+          buyerQuoteAccount: null,
+          sellerQuoteAccount: null,
+          treasuryQuoteAccount: null,
+          propertyRecord: null,
+          ...tradeAccounts(buyer.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+      expect.fail('trade should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('Unauthorized');
+    }
+  });
+
+  it('rejects a fill above the buyer\'s maximum price', async () => {
+    try {
+      await executeTrade(1, PRICE_PER_TOKEN - 1);
+      expect.fail('trade should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('SlippageExceeded');
+    }
+  });
+
+  it('updates price and amount in place, moving tokens out of escrow', async () => {
+    const before = await program.account.listing.fetch(listing);
+    const remaining = (before.tokenAmount as anchor.BN).toNumber();
+    const sellerTokens = Number((await getAccount(connection, sellerTokenAccount)).amount);
+
+    await program.methods
+      .updateListing(new anchor.BN(PRICE_PER_TOKEN * 2), new anchor.BN(remaining - 5), null)
+      .accounts({
+        seller: seller.publicKey,
+        listing,
+        escrowTokenAccount: escrow,
+        sellerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([seller])
+      .rpc();
+
+    const state = await program.account.listing.fetch(listing);
+    expect((state.pricePerToken as anchor.BN).toNumber()).to.equal(PRICE_PER_TOKEN * 2);
+    expect((state.tokenAmount as anchor.BN).toNumber()).to.equal(remaining - 5);
+    expect(Number((await getAccount(connection, escrow)).amount)).to.equal(remaining - 5);
+    expect(Number((await getAccount(connection, sellerTokenAccount)).amount)).to.equal(sellerTokens + 5);
+
+    // Restore the original terms for the suites below
+    await program.methods
+      .updateListing(new anchor.BN(PRICE_PER_TOKEN), new anchor.BN(remaining), null)
+      .accounts({
+        seller: seller.publicKey,
+        listing,
+        escrowTokenAccount: escrow,
+        sellerTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([seller])
+      .rpc();
+  });
+
+  describe('sweeping listings', () => {
+    let sweepMint: PublicKey;
+    let sellerSweepAccount: PublicKey;
+    let buyerSweepAccount: PublicKey;
+
+    before(async () => {
+      sweepMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      sellerSweepAccount = await createAccount(connection, payer, sweepMint, seller.publicKey);
+      buyerSweepAccount = await createAccount(connection, payer, sweepMint, buyer.publicKey);
+      await mintTo(connection, payer, sweepMint, sellerSweepAccount, payer, 20);
+    });
+
+    it('fills the cheapest listings first within the budget', async () => {
+      const [pricey, priceyEscrow] = await createListing(
+        sweepMint,
+        sellerSweepAccount,
+        NATIVE_MINT,
+        PRICE_PER_TOKEN * 2,
+        10
+      );
+      const [cheap, cheapEscrow] = await createListing(
+        sweepMint,
+        sellerSweepAccount,
+        NATIVE_MINT,
+        PRICE_PER_TOKEN,
+        10
+      );
+
+      // 10 cheap tokens cost 10 * PRICE, leaving budget for 5 of the pricey ones
+      const budget = 20 * PRICE_PER_TOKEN;
+      const treasuryBefore = await balance(treasury);
+
+      const { buyerVolume, sellerVolume, mintStats, priceOracle, mintFeeConfig } = tradeAccounts(
+        buyer.publicKey,
+        NATIVE_MINT,
+        sweepMint
+      );
+      const sellerBefore = await program.account.traderVolume.fetch(sellerVolume);
+      await program.methods
+        .sweepListings(new anchor.BN(20), new anchor.BN(budget), new anchor.BN(1))
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplace: marketplacePda,
+          marketplaceTreasury: treasury,
+          propertyTokenMint: sweepMint,
+          quoteMint: NATIVE_MINT,
+          propertyRecord: null,
+          mintFeeConfig,
+          royaltyRecipient: null,
+          royaltyQuoteAccount: null,
+          buyerTokenAccount: buyerSweepAccount,
+          buyerQuoteAccount: null,
+          treasuryQuoteAccount: null,
+          buyerVolume,
+          mintStats,
+          priceOracle,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          [
+            ...[pricey, priceyEscrow, seller.publicKey, sellerVolume],
+            ...[cheap, cheapEscrow, seller.publicKey, sellerVolume],
+          ].map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: true,
+          }))
+        )
+        .signers([buyer])
+        .rpc();
+
+      expect(Number((await getAccount(connection, buyerSweepAccount)).amount)).to.equal(15);
+      const cheapState = await program.account.listing.fetch(cheap);
+      expect(cheapState.status).to.deep.equal({ completed: {} });
+      const priceyState = await program.account.listing.fetch(pricey);
+      expect((priceyState.tokenAmount as anchor.BN).toNumber()).to.equal(5);
+
+      const feeAmount =
+        Math.floor((10 * PRICE_PER_TOKEN * MARKETPLACE_FEE_BPS) / 10000) +
+        Math.floor((10 * PRICE_PER_TOKEN * MARKETPLACE_FEE_BPS) / 10000);
+      expect((await balance(treasury)) - treasuryBefore).to.equal(feeAmount);
+
+      // Both fills are recorded on the seller's volume, passed with each listing
+      const sellerAfter = await program.account.traderVolume.fetch(sellerVolume);
+      expect((sellerAfter.tradeCount as anchor.BN).toNumber() - (sellerBefore.tradeCount as anchor.BN).toNumber()).to.equal(2);
+      expect((sellerAfter.soldVolume as anchor.BN).toNumber() - (sellerBefore.soldVolume as anchor.BN).toNumber()).to.equal(
+        20 * PRICE_PER_TOKEN
+      );
+    });
+  });
+
+  describe('listing expiry', () => {
+    let expiringListing: PublicKey;
+    let expiringEscrow: PublicKey;
+
+    before(async () => {
+      await mintTo(connection, payer, mint, sellerTokenAccount, payer, 5);
+      [expiringListing, expiringEscrow] = await createListing(
+        mint,
+        sellerTokenAccount,
+        NATIVE_MINT,
+        PRICE_PER_TOKEN,
+        5,
+        Math.floor(Date.now() / 1000) + 2
+      );
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+    });
+
+    it('rejects trades once the listing has expired', async () => {
+      try {
+        await program.methods
+          .executeTrade(new anchor.BN(1), new anchor.BN(PRICE_PER_TOKEN), new anchor.BN(1), false)
+          .accounts({
+            buyer: buyer.publicKey,
+            seller: seller.publicKey,
+            listing: expiringListing,
+            marketplace: marketplacePda,
+            marketplaceTreasury: treasury,
+            escrowTokenAccount: expiringEscrow,
+            buyerTokenAccount,
+            buyerQuoteAccount: null,
+            sellerQuoteAccount: null,
+            treasuryQuoteAccount: null,
+            propertyRecord: null,
+            ...tradeAccounts(buyer.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+        expect.fail('trade should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('ListingExpired');
+      }
+    });
+
+    it('lets anyone close an expired listing, returning tokens and rent to the seller', async () => {
+      const sellerBefore = await balance(seller.publicKey);
+      const rent = (await balance(expiringListing)) + (await balance(expiringEscrow));
+      const tokensBefore = Number((await getAccount(connection, sellerTokenAccount)).amount);
+      const activeBefore = (await program.account.marketplace.fetch(marketplacePda))
+        .activeListings as anchor.BN;
+
+      // Signed only by the provider wallet, not the seller
+      await program.methods
+        .closeListing()
+        .accounts({
+          seller: seller.publicKey,
+          listing: expiringListing,
+          marketplace: marketplacePda,
+          escrowTokenAccount: expiringEscrow,
+          sellerTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+      expect(await connection.getAccountInfo(expiringListing)).to.be.null;
+      expect(await connection.getAccountInfo(expiringEscrow)).to.be.null;
+      expect((await balance(seller.publicKey)) - sellerBefore).to.equal(rent);
+      expect(Number((await getAccount(connection, sellerTokenAccount)).amount)).to.equal(tokensBefore + 5);
+
+      const marketplace = await program.account.marketplace.fetch(marketplacePda);
+      expect((marketplace.activeListings as anchor.BN).toNumber()).to.equal(activeBefore.toNumber() - 1);
+    });
+  });
+
+  describe('cancelling listings', () => {
+    let cancelled: PublicKey;
+    let cancelledEscrow: PublicKey;
+
+    function cancelListing(signer: Keypair, tokenAccount: PublicKey) {
+      return program.methods
+        .cancelListing()
+        .accounts({
+          seller: signer.publicKey,
+          listing: cancelled,
+          marketplace: marketplacePda,
+          escrowTokenAccount: cancelledEscrow,
+          sellerTokenAccount: tokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([signer])
+        .rpc();
+    }
+
+    before(async () => {
+      await mintTo(connection, payer, mint, sellerTokenAccount, payer, 5);
+      [cancelled, cancelledEscrow] = await createListing(
+        mint,
+        sellerTokenAccount,
+        NATIVE_MINT,
+        PRICE_PER_TOKEN,
+        5
+      );
+      await executeTrade(2, PRICE_PER_TOKEN, [cancelled, cancelledEscrow]);
+    });
+
+    it('lets only the seller cancel', async () => {
+      try {
+        await cancelListing(buyer, buyerTokenAccount);
+        expect.fail('cancel should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+      expect(Number((await getAccount(connection, cancelledEscrow)).amount)).to.equal(3);
+    });
+
+    it('returns the unsold tokens and the escrow rent to the seller', async () => {
+      const sellerBefore = await balance(seller.publicKey);
+      const escrowRent = await balance(cancelledEscrow);
+      const tokensBefore = Number((await getAccount(connection, sellerTokenAccount)).amount);
+
+      await cancelListing(seller, sellerTokenAccount);
+
+      expect(await connection.getAccountInfo(cancelledEscrow)).to.be.null;
+      expect((await balance(seller.publicKey)) - sellerBefore).to.equal(escrowRent);
+      expect(Number((await getAccount(connection, sellerTokenAccount)).amount)).to.equal(tokensBefore + 3);
+      const state = await program.account.listing.fetch(cancelled);
+      expect(state.status).to.deep.equal({ cancelled: {} });
+    });
+
+    it('cannot be filled or cancelled again once its escrow is closed', async () => {
+      // The escrow is gone, so both fail on their account constraints
+      try {
+        await executeTrade(1, PRICE_PER_TOKEN, [cancelled, cancelledEscrow]);
+        expect.fail('trade should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('AccountNotInitialized');
+      }
+
+      try {
+        await cancelListing(seller, sellerTokenAccount);
+        expect.fail('cancel should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('AccountNotInitialized');
+      }
+      const state = await program.account.listing.fetch(cancelled);
+      expect((state.tokenAmount as anchor.BN).toNumber()).to.equal(3);
+    });
+  });
+
+  describe('closing finished auctions', () => {
+    let auction: PublicKey;
+    let auctionEscrow: PublicKey;
+
+    function closeAuction() {
+      // Signed only by the provider wallet, not the seller
+      return program.methods
+        .closeAuction()
+        .accounts({
+          seller: seller.publicKey,
+          auction,
+          quoteVault: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      await mintTo(connection, payer, mint, sellerTokenAccount, payer, 5);
+      const now = Math.floor(Date.now() / 1000);
+      [auction, auctionEscrow] = await createAuction(
+        {
+          english: {
+            reservePrice: new anchor.BN(5 * PRICE_PER_TOKEN),
+            minIncrement: new anchor.BN(PRICE_PER_TOKEN),
+            extension: new anchor.BN(60),
+          },
+        },
+        5,
+        5 * PRICE_PER_TOKEN,
+        now,
+        now + 3600
+      );
+    });
+
+    it('keeps a running auction open', async () => {
+      try {
+        await closeAuction();
+        expect.fail('close should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('AuctionStillRunning');
+      }
+    });
+
+    it('returns a cancelled auction\'s rent to the seller', async () => {
+      await program.methods
+        .cancelAuction()
+        .accounts({
+          seller: seller.publicKey,
+          auction,
+          escrowTokenAccount: auctionEscrow,
+          sellerTokenAccount,
+          quoteVault: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([seller])
+        .rpc();
+
+      const sellerBefore = await balance(seller.publicKey);
+      const rent = await balance(auction);
+
+      await closeAuction();
+
+      expect(await connection.getAccountInfo(auction)).to.be.null;
+      expect((await balance(seller.publicKey)) - sellerBefore).to.equal(rent);
+    });
+  });
+
+  describe('auctions', () => {
+    const EXTENSION = 5; // seconds
+    const rival = Keypair.generate();
+
+    const sleep = (seconds: number) => new Promise((resolve) => setTimeout(resolve, seconds * 1000));
+
+    function english(reservePrice: number) {
+      return {
+        english: {
+          reservePrice: new anchor.BN(reservePrice),
+          minIncrement: new anchor.BN(PRICE_PER_TOKEN),
+          extension: new anchor.BN(EXTENSION),
+        },
+      };
+    }
+
+    function bidAddress(auction: PublicKey, bidder: PublicKey) {
+      return findAuctionBidAddress(program.programId, auction, bidder)[0];
+    }
+
+    function placeBid(auction: PublicKey, bidder: Keypair, amount: number) {
+      return program.methods
+        .placeBid(new anchor.BN(amount))
+        .accounts({
+          bidder: bidder.publicKey,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          auction,
+          auctionBid: bidAddress(auction, bidder.publicKey),
+          bidderQuoteAccount: null,
+          quoteVault: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bidder])
+        .rpc();
+    }
+
+    function refundBid(auction: PublicKey, bidder: Keypair) {
+      return program.methods
+        .refundBid()
+        .accounts({
+          bidder: bidder.publicKey,
+          auction,
+          auctionBid: bidAddress(auction, bidder.publicKey),
+          bidderQuoteAccount: null,
+          quoteVault: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([bidder])
+        .rpc();
+    }
+
+    // Permissionless, so signed only by the provider wallet
+    async function settleAuction(
+      auction: PublicKey,
+      escrowAccount: PublicKey,
+      winnerTokenAccount: PublicKey | null
+    ) {
+      const state = await program.account.auction.fetch(auction);
+      const winner = state.highestBidder as PublicKey;
+      const sold = winnerTokenAccount !== null;
+      return program.methods
+        .settleAuction()
+        .accounts({
+          payer: payer.publicKey,
+          seller: seller.publicKey,
+          auction,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          marketplaceTreasury: treasury,
+          escrowTokenAccount: escrowAccount,
+          sellerTokenAccount,
+          winner: sold ? winner : null,
+          winningBid: sold ? bidAddress(auction, winner) : null,
+          winnerTokenAccount,
+          quoteVault: null,
+          sellerQuoteAccount: null,
+          treasuryQuoteAccount: null,
+          mintFeeConfig: findMintFeeConfigAddress(program.programId, mint)[0],
+          royaltyRecipient: null,
+          royaltyQuoteAccount: null,
+          winnerVolume: sold ? findTraderVolumeAddress(program.programId, winner, NATIVE_MINT)[0] : null,
+          sellerVolume: sold ? findTraderVolumeAddress(program.programId, seller.publicKey, NATIVE_MINT)[0] : null,
+          mintStats: sold ? findMintStatsAddress(program.programId, mint, NATIVE_MINT)[0] : null,
+          priceOracle: sold ? findPriceOracleAddress(program.programId, mint, NATIVE_MINT)[0] : null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      await airdrop(rival.publicKey, LAMPORTS_PER_SOL);
+    });
+
+    describe('english', () => {
+      let auction: PublicKey;
+      let auctionEscrow: PublicKey;
+      let unmet: PublicKey;
+      let unmetEscrow: PublicKey;
+
+      // Ends inside the extension window, so every bid extends it
+      function createShortAuction(reservePrice: number) {
+        const now = Math.floor(Date.now() / 1000);
+        return createAuction(english(reservePrice), 5, 2 * PRICE_PER_TOKEN, now, now + EXTENSION - 1);
+      }
+
+      before(async () => {
+        await mintTo(connection, payer, mint, sellerTokenAccount, payer, 10);
+        [auction, auctionEscrow] = await createShortAuction(5 * PRICE_PER_TOKEN);
+      });
+
+      it('needs the start price, then the minimum increment', async () => {
+        try {
+          await placeBid(auction, buyer, 2 * PRICE_PER_TOKEN - 1);
+          expect.fail('bid should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('BidTooLow');
+        }
+
+        // Below the reserve is still a valid opening bid
+        await placeBid(auction, rival, 2 * PRICE_PER_TOKEN);
+        try {
+          await placeBid(auction, buyer, 3 * PRICE_PER_TOKEN - 1);
+          expect.fail('bid should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('BidTooLow');
+        }
+        await placeBid(auction, buyer, 3 * PRICE_PER_TOKEN);
+
+        const state = await program.account.auction.fetch(auction);
+        expect((state.highestBidder as PublicKey).equals(buyer.publicKey)).to.be.true;
+        expect((state.highestBid as anchor.BN).toNumber()).to.equal(3 * PRICE_PER_TOKEN);
+        expect(state.openBids).to.equal(2);
+      });
+
+      it('does not let the seller bid on their own lot', async () => {
+        try {
+          await placeBid(auction, seller, 10 * PRICE_PER_TOKEN);
+          expect.fail('bid should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('SelfTrade');
+        }
+      });
+
+      it('pushes the end back when a bid lands inside the extension window', async () => {
+        [unmet, unmetEscrow] = await createShortAuction(100 * PRICE_PER_TOKEN);
+        const before = (await program.account.auction.fetch(unmet)).endTime as anchor.BN;
+
+        await placeBid(unmet, buyer, 2 * PRICE_PER_TOKEN);
+
+        const after = (await program.account.auction.fetch(unmet)).endTime as anchor.BN;
+        expect(after.toNumber()).to.be.greaterThan(before.toNumber());
+        expect(after.toNumber()).to.be.at.least(Math.floor(Date.now() / 1000) + EXTENSION - 2);
+      });
+
+      it('refunds an outbid bidder but holds the leading bid', async () => {
+        try {
+          await refundBid(auction, buyer);
+          expect.fail('refund should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('BidNotRefundable');
+        }
+
+        const rivalBefore = await balance(rival.publicKey);
+        const escrowed = await balance(bidAddress(auction, rival.publicKey));
+
+        await refundBid(auction, rival);
+
+        // The bid and its account's rent both come back
+        expect(await connection.getAccountInfo(bidAddress(auction, rival.publicKey))).to.be.null;
+        expect((await balance(rival.publicKey)) - rivalBefore).to.equal(escrowed);
+        expect((await program.account.auction.fetch(auction)).openBids).to.equal(1);
+      });
+
+      it('tops up a raised bid and settles to the leader once the reserve is met', async () => {
+        await placeBid(auction, buyer, 5 * PRICE_PER_TOKEN);
+        const bid = await program.account.auctionBid.fetch(bidAddress(auction, buyer.publicKey));
+        expect((bid.amount as anchor.BN).toNumber()).to.equal(5 * PRICE_PER_TOKEN);
+
+        try {
+          await settleAuction(auction, auctionEscrow, buyerTokenAccount);
+          expect.fail('settle should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('AuctionStillRunning');
+        }
+        await sleep(EXTENSION + 2);
+
+        const totalPrice = 5 * PRICE_PER_TOKEN;
+        const feeAmount = Math.floor((totalPrice * MARKETPLACE_FEE_BPS) / 10000);
+        const sellerBefore = await balance(seller.publicKey);
+        const treasuryBefore = await balance(treasury);
+        const tokensBefore = Number((await getAccount(connection, buyerTokenAccount)).amount);
+        const escrowRent = await balance(auctionEscrow);
+
+        await settleAuction(auction, auctionEscrow, buyerTokenAccount);
+
+        // The seller also gets the lot escrow's rent back when it closes
+        expect((await balance(seller.publicKey)) - sellerBefore).to.equal(totalPrice - feeAmount + escrowRent);
+        expect((await balance(treasury)) - treasuryBefore).to.equal(feeAmount);
+        expect(Number((await getAccount(connection, buyerTokenAccount)).amount)).to.equal(tokensBefore + 5);
+        expect(await connection.getAccountInfo(bidAddress(auction, buyer.publicKey))).to.be.null;
+
+        const state = await program.account.auction.fetch(auction);
+        expect(state.status).to.deep.equal({ settled: {} });
+        expect(state.openBids).to.equal(0);
+      });
+
+      it('returns the lot when the reserve is not met and refunds the leader', async () => {
+        const tokensBefore = Number((await getAccount(connection, sellerTokenAccount)).amount);
+
+        await settleAuction(unmet, unmetEscrow, null);
+
+        expect((await program.account.auction.fetch(unmet)).status).to.deep.equal({ unsold: {} });
+        expect(Number((await getAccount(connection, sellerTokenAccount)).amount)).to.equal(tokensBefore + 5);
+
+        const buyerBefore = await balance(buyer.publicKey);
+        const escrowed = await balance(bidAddress(unmet, buyer.publicKey));
+        await refundBid(unmet, buyer);
+        expect((await balance(buyer.publicKey)) - buyerBefore).to.equal(escrowed);
+      });
+
+      it('returns the lot and the leading bid of an auction settled while paused', async () => {
+        await mintTo(connection, payer, mint, sellerTokenAccount, payer, 5);
+        const [paused, pausedEscrow] = await createShortAuction(2 * PRICE_PER_TOKEN);
+        await placeBid(paused, buyer, 2 * PRICE_PER_TOKEN);
+        await sleep(EXTENSION + 2);
+
+        const setPaused = (value: boolean) =>
+          program.methods
+            .setPaused(value)
+            .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+            .rpc();
+
+        await setPaused(true);
+        try {
+          // The reserve is met, but the sale cannot go through while paused
+          const tokensBefore = Number((await getAccount(connection, sellerTokenAccount)).amount);
+          await settleAuction(paused, pausedEscrow, null);
+
+          expect((await program.account.auction.fetch(paused)).status).to.deep.equal({ unsold: {} });
+          expect(Number((await getAccount(connection, sellerTokenAccount)).amount)).to.equal(tokensBefore + 5);
+
+          const buyerBefore = await balance(buyer.publicKey);
+          const escrowed = await balance(bidAddress(paused, buyer.publicKey));
+          await refundBid(paused, buyer);
+          expect((await balance(buyer.publicKey)) - buyerBefore).to.equal(escrowed);
+        } finally {
+          await setPaused(false);
+        }
+      });
+    });
+
+    describe('dutch', () => {
+      const START_PRICE = 10 * PRICE_PER_TOKEN;
+      const END_PRICE = 2 * PRICE_PER_TOKEN;
+      const DURATION = 100; // seconds
+
+      let auction: PublicKey;
+      let auctionEscrow: PublicKey;
+      let startTime: number;
+
+      before(async () => {
+        await mintTo(connection, payer, mint, sellerTokenAccount, payer, 5);
+        // Started halfway through, so the price has already decayed
+        startTime = Math.floor(Date.now() / 1000) - DURATION / 2;
+        [auction, auctionEscrow] = await createAuction(
+          { dutch: { endPrice: new anchor.BN(END_PRICE) } },
+          5,
+          START_PRICE,
+          startTime,
+          startTime + DURATION
+        );
+      });
+
+      it('takes no bids', async () => {
+        try {
+          await placeBid(auction, buyer, START_PRICE);
+          expect.fail('bid should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('WrongAuctionKind');
+        }
+      });
+
+      it('sells at the linearly decayed price', async () => {
+        const sellerBefore = await balance(seller.publicKey);
+        const escrowRent = await balance(auctionEscrow);
+
+        await program.methods
+          .buyDutchAuction()
+          .accounts({
+            buyer: buyer.publicKey,
+            seller: seller.publicKey,
+            auction,
+            marketplace: marketplacePda,
+            propertyRecord: null,
+            marketplaceTreasury: treasury,
+            escrowTokenAccount: auctionEscrow,
+            buyerTokenAccount,
+            buyerQuoteAccount: null,
+            sellerQuoteAccount: null,
+            treasuryQuoteAccount: null,
+            mintFeeConfig: findMintFeeConfigAddress(program.programId, mint)[0],
+            royaltyRecipient: null,
+            royaltyQuoteAccount: null,
+            buyerVolume: findTraderVolumeAddress(program.programId, buyer.publicKey, NATIVE_MINT)[0],
+            sellerVolume: findTraderVolumeAddress(program.programId, seller.publicKey, NATIVE_MINT)[0],
+            mintStats: findMintStatsAddress(program.programId, mint, NATIVE_MINT)[0],
+            priceOracle: findPriceOracleAddress(program.programId, mint, NATIVE_MINT)[0],
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+
+        const state = await program.account.auction.fetch(auction);
+        expect(state.status).to.deep.equal({ settled: {} });
+        const price = (state.highestBid as anchor.BN).toNumber();
+        expect(price).to.be.below(START_PRICE).and.above(END_PRICE);
+        // The price falls by (START - END) / DURATION per whole second
+        const step = (START_PRICE - END_PRICE) / DURATION;
+        expect((START_PRICE - price) % step).to.equal(0);
+
+        const feeAmount = Math.floor((price * MARKETPLACE_FEE_BPS) / 10000);
+        expect((await balance(seller.publicKey)) - sellerBefore).to.equal(price - feeAmount + escrowRent);
+      });
+    });
+  });
+
+  describe('with an SPL quote mint', () => {
+    const USDC_DECIMALS = 6;
+    const PROPERTY_DECIMALS = 2;
+    const PRICE = 1_500_000; // 1.50 USDC per whole property token
+
+    let usdc: PublicKey;
+    let propertyMint: PublicKey;
+    let sellerPropertyAccount: PublicKey;
+    let buyerPropertyAccount: PublicKey;
+    let buyerUsdc: PublicKey;
+    let sellerUsdc: PublicKey;
+    let treasuryUsdc: PublicKey;
+
+    before(async () => {
+      usdc = await createMint(connection, payer, payer.publicKey, null, USDC_DECIMALS);
+      propertyMint = await createMint(connection, payer, payer.publicKey, null, PROPERTY_DECIMALS);
+
+      sellerPropertyAccount = await createAccount(connection, payer, propertyMint, seller.publicKey);
+      buyerPropertyAccount = await createAccount(connection, payer, propertyMint, buyer.publicKey);
+      buyerUsdc = await createAccount(connection, payer, usdc, buyer.publicKey);
+      sellerUsdc = await createAccount(connection, payer, usdc, seller.publicKey);
+      treasuryUsdc = await createAccount(connection, payer, usdc, treasury);
+
+      await mintTo(connection, payer, propertyMint, sellerPropertyAccount, payer, 1_000);
+      await mintTo(connection, payer, usdc, buyerUsdc, payer, 100_000_000);
+    });
+
+    function buyWithUsdc(listing: PublicKey, escrow: PublicKey, tokenAmount: number, price: number) {
+      return program.methods
+        .executeTrade(new anchor.BN(tokenAmount), new anchor.BN(price), new anchor.BN(tokenAmount), false)
+        .accounts({
+          buyer: buyer.publicKey,
+          seller: seller.publicKey,
+          listing,
+          marketplace: marketplacePda,
+          marketplaceTreasury: treasury,
+          escrowTokenAccount: escrow,
+          buyerTokenAccount: buyerPropertyAccount,
+          buyerQuoteAccount: buyerUsdc,
+          sellerQuoteAccount: sellerUsdc,
+          treasuryQuoteAccount: treasuryUsdc,
+          propertyRecord: null,
+          ...tradeAccounts(buyer.publicKey, usdc, propertyMint),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([buyer])
+        .rpc();
+    }
+
+    it('rejects quote mints the marketplace has not allowed', async () => {
+      try {
+        await createListing(propertyMint, sellerPropertyAccount, usdc, PRICE, 1_000);
+        expect.fail('listing should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('QuoteMintNotAllowed');
+      }
+    });
+
+    it('settles in the quote mint, scaling by the property mint decimals', async () => {
+      await program.methods
+        .addQuoteMint(usdc)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+
+      const [usdcListing, usdcEscrow] = await createListing(
+        propertyMint,
+        sellerPropertyAccount,
+        usdc,
+        PRICE,
+        1_000
+      );
+
+      // 250 base units = 2.5 whole tokens = 3.75 USDC
+      const tokenAmount = 250;
+      const totalPrice = 3_750_000;
+      const feeAmount = Math.floor((totalPrice * MARKETPLACE_FEE_BPS) / 10000);
+      const buyerLamports = await balance(buyer.publicKey);
+      const statsRent =
+        2 * (await connection.getMinimumBalanceForRentExemption(TRADER_VOLUME_LEN)) +
+        (await connection.getMinimumBalanceForRentExemption(MINT_STATS_LEN)) +
+        (await connection.getMinimumBalanceForRentExemption(PRICE_ORACLE_LEN));
+
+      await buyWithUsdc(usdcListing, usdcEscrow, tokenAmount, PRICE);
+
+      const amount = async (account: PublicKey) => Number((await getAccount(connection, account)).amount);
+      expect(await amount(buyerUsdc)).to.equal(100_000_000 - totalPrice);
+      expect(await amount(sellerUsdc)).to.equal(totalPrice - feeAmount);
+      expect(await amount(treasuryUsdc)).to.equal(feeAmount);
+      expect(await amount(buyerPropertyAccount)).to.equal(tokenAmount);
+      // Only the rent for the USDC volume, stats and oracle accounts is paid in SOL
+      expect(buyerLamports - (await balance(buyer.publicKey))).to.equal(statsRent);
+    });
+
+    it('weights the oracle price by fill size, so a dust fill barely moves it', async () => {
+      await mintTo(connection, payer, propertyMint, sellerPropertyAccount, payer, 1);
+      const [dustListing, dustEscrow] = await createListing(
+        propertyMint,
+        sellerPropertyAccount,
+        usdc,
+        10 * PRICE,
+        1
+      );
+      await buyWithUsdc(dustListing, dustEscrow, 1, 10 * PRICE);
+
+      // Both fills land in the same observation interval: 250 units at PRICE
+      // and 1 unit at ten times that
+      const [priceOracle] = findPriceOracleAddress(program.programId, propertyMint, usdc);
+      const oracle = await program.account.priceOracle.fetch(priceOracle);
+      expect((oracle.lastPrice as anchor.BN).toNumber()).to.equal(Math.floor((250 * PRICE + 10 * PRICE) / 251));
+      const [mintStats] = findMintStatsAddress(program.programId, propertyMint, usdc);
+      expect(((await program.account.mintStats.fetch(mintStats)).lastPrice as anchor.BN).toNumber()).to.equal(
+        10 * PRICE
+      );
+    });
+  });
+
+  describe('order book', () => {
+    const trader = Keypair.generate();
+
+    let quoteMint: PublicKey;
+    let bookMint: PublicKey;
+    let orderBook: PublicKey;
+    let baseVault: PublicKey;
+    let quoteVault: PublicKey;
+    let treasuryQuote: PublicKey;
+    // Property and quote token accounts per trader
+    const accounts = new Map<string, { base: PublicKey; quote: PublicKey }>();
+
+    const tokens = async (account: PublicKey) => Number((await getAccount(connection, account)).amount);
+
+    function openOrdersOf(owner: PublicKey, book = orderBook) {
+      return findOpenOrdersAddress(program.programId, book, owner)[0];
+    }
+
+    function volumeOf(owner: PublicKey) {
+      return findTraderVolumeAddress(program.programId, owner, quoteMint)[0];
+    }
+
+    function placeOrder(
+      owner: Keypair,
+      side: 'bid' | 'ask',
+      price: number,
+      quantity: number,
+      evictedOpenOrders: PublicKey | null = null
+    ) {
+      const { base, quote } = accounts.get(owner.publicKey.toBase58())!;
+      return program.methods
+        .placeOrder({ [side]: {} }, new anchor.BN(price), new anchor.BN(quantity))
+        .accounts({
+          owner: owner.publicKey,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          orderBook,
+          openOrders: openOrdersOf(owner.publicKey),
+          traderVolume: volumeOf(owner.publicKey),
+          ownerTokenAccount: side === 'ask' ? base : quote,
+          baseVault,
+          quoteVault,
+          evictedOpenOrders,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    function cancelOrder(owner: Keypair, side: 'bid' | 'ask', orderId: number) {
+      return program.methods
+        .cancelOrder({ [side]: {} }, new anchor.BN(orderId))
+        .accounts({ owner: owner.publicKey, orderBook, openOrders: openOrdersOf(owner.publicKey) })
+        .signers([owner])
+        .rpc();
+    }
+
+    // Each trader is passed as [open_orders, trader_volume], with the volume
+    // account of the open orders' owner unless `volumes` names another
+    async function matchOrders(limit: number, openOrders: PublicKey[], volumes: PublicKey[] = []) {
+      const traders = await Promise.all(
+        openOrders.map(async (address, i) => {
+          const { owner } = await program.account.openOrders.fetch(address);
+          return [address, volumes[i] ?? volumeOf(owner as PublicKey)];
+        })
+      );
+      return program.methods
+        .matchOrders(limit)
+        .accounts({
+          payer: payer.publicKey,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          orderBook,
+          quoteVault,
+          treasuryQuoteAccount: treasuryQuote,
+          mintFeeConfig: findMintFeeConfigAddress(program.programId, bookMint)[0],
+          royaltyQuoteAccount: null,
+          mintStats: findMintStatsAddress(program.programId, bookMint, quoteMint)[0],
+          priceOracle: findPriceOracleAddress(program.programId, bookMint, quoteMint)[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(traders.flat().map((pubkey) => ({ pubkey, isSigner: false, isWritable: true })))
+        .rpc();
+    }
+
+    async function freeBalances(owner: PublicKey) {
+      const state = await program.account.openOrders.fetch(openOrdersOf(owner));
+      return [(state.baseFree as anchor.BN).toNumber(), (state.quoteFree as anchor.BN).toNumber()];
+    }
+
+    async function restingIds(side: 'bids' | 'asks') {
+      const book = await program.account.orderBook.fetch(orderBook);
+      return (book[side] as { orderId: anchor.BN }[]).map((order) => order.orderId.toNumber());
+    }
+
+    function createOrderBook(propertyMint: PublicKey) {
+      const [book] = findOrderBookAddress(program.programId, propertyMint, quoteMint);
+      return program.methods
+        .createOrderBook()
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          propertyTokenMint: propertyMint,
+          quoteMint,
+          propertyRecord: null,
+          orderBook: book,
+          ...findOrderBookVaultAddresses(program.programId, book),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      await airdrop(trader.publicKey, LAMPORTS_PER_SOL);
+
+      quoteMint = await createMint(connection, payer, payer.publicKey, null, 6);
+      bookMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      for (const owner of [seller, buyer, trader]) {
+        const base = await createAccount(connection, payer, bookMint, owner.publicKey);
+        const quote = await createAccount(connection, payer, quoteMint, owner.publicKey);
+        await mintTo(connection, payer, bookMint, base, payer, 100);
+        await mintTo(connection, payer, quoteMint, quote, payer, 100_000_000);
+        accounts.set(owner.publicKey.toBase58(), { base, quote });
+      }
+      treasuryQuote = await createAccount(connection, payer, quoteMint, treasury);
+
+      await program.methods
+        .addQuoteMint(quoteMint)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+      await createOrderBook(bookMint);
+      [orderBook] = findOrderBookAddress(program.programId, bookMint, quoteMint);
+      ({ baseVault, quoteVault } = findOrderBookVaultAddresses(program.programId, orderBook));
+    });
+
+    after(async () => {
+      await program.methods
+        .removeQuoteMint(quoteMint)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+    });
+
+    it('rests asks by price, then by time, escrowing the tokens', async () => {
+      await placeOrder(seller, 'ask', 1_200_000, 10); // order 0
+      await placeOrder(trader, 'ask', 1_100_000, 10); // order 1
+      await placeOrder(seller, 'ask', 1_100_000, 5); // order 2, same price but later than 1
+
+      expect(await restingIds('asks')).to.deep.equal([1, 2, 0]);
+      expect(await tokens(baseVault)).to.equal(25);
+    });
+
+    it('lets only the owner cancel, crediting the remainder to open orders', async () => {
+      try {
+        await cancelOrder(trader, 'ask', 2);
+        expect.fail('cancel should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+      try {
+        await cancelOrder(seller, 'bid', 2);
+        expect.fail('cancel should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('OrderNotFound');
+      }
+
+      await cancelOrder(seller, 'ask', 2);
+      expect(await restingIds('asks')).to.deep.equal([1, 0]);
+      expect(await freeBalances(seller.publicKey)).to.deep.equal([5, 0]);
+    });
+
+    it('does not match without the open orders of both owners', async () => {
+      await placeOrder(buyer, 'bid', 1_300_000, 15); // order 3
+
+      await matchOrders(10, [openOrdersOf(buyer.publicKey)]);
+      expect(await restingIds('bids')).to.deep.equal([3]);
+      expect(await restingIds('asks')).to.deep.equal([1, 0]);
+    });
+
+    it('rejects open orders that are duplicated, from another book or paired with another volume', async () => {
+      const sellerOpenOrders = openOrdersOf(seller.publicKey);
+      try {
+        await matchOrders(
+          10,
+          [openOrdersOf(buyer.publicKey), sellerOpenOrders],
+          [volumeOf(seller.publicKey), volumeOf(buyer.publicKey)]
+        );
+        expect.fail('match should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('InvalidOpenOrders');
+      }
+
+      try {
+        await matchOrders(10, [
+          openOrdersOf(buyer.publicKey),
+          sellerOpenOrders,
+          openOrdersOf(trader.publicKey),
+          sellerOpenOrders,
+        ]);
+        expect.fail('match should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('InvalidOpenOrders');
+      }
+
+      // The main property mint gets a book of its own in the same quote mint
+      await createOrderBook(mint);
+      const [otherBook] = findOrderBookAddress(program.programId, mint, quoteMint);
+      const otherVaults = findOrderBookVaultAddresses(program.programId, otherBook);
+      const sellerQuote = accounts.get(seller.publicKey.toBase58())!.quote;
+      await program.methods
+        .placeOrder({ bid: {} }, new anchor.BN(1), new anchor.BN(1))
+        .accounts({
+          owner: seller.publicKey,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          orderBook: otherBook,
+          openOrders: openOrdersOf(seller.publicKey, otherBook),
+          traderVolume: volumeOf(seller.publicKey),
+          ownerTokenAccount: sellerQuote,
+          ...otherVaults,
+          evictedOpenOrders: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+      try {
+        await matchOrders(10, [
+          openOrdersOf(buyer.publicKey),
+          openOrdersOf(trader.publicKey),
+          openOrdersOf(seller.publicKey, otherBook),
+        ]);
+        expect.fail('match should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('InvalidOpenOrders');
+      }
+
+      // Nothing was credited by the rejected matches
+      expect(await freeBalances(seller.publicKey)).to.deep.equal([5, 0]);
+      expect(await restingIds('bids')).to.deep.equal([3]);
+    });
+
+    it('fills at the resting price with price-time priority and refunds the bid', async () => {
+      const treasuryBefore = await tokens(treasuryQuote);
+
+      await matchOrders(10, [
+        openOrdersOf(buyer.publicKey),
+        openOrdersOf(seller.publicKey),
+        openOrdersOf(trader.publicKey),
+      ]);
+
+      // The bid crossed later than both asks, so each fill is at the ask's
+      // price: 10 from order 1 first, then 5 of order 0
+      const traderFee = Math.floor((11_000_000 * MARKETPLACE_FEE_BPS) / 10000);
+      const sellerFee = Math.floor((6_000_000 * MARKETPLACE_FEE_BPS) / 10000);
+      expect(await freeBalances(trader.publicKey)).to.deep.equal([0, 11_000_000 - traderFee]);
+      expect(await freeBalances(seller.publicKey)).to.deep.equal([5, 6_000_000 - sellerFee]);
+      // 15 tokens, and (1.3 - 1.1) * 10 + (1.3 - 1.2) * 5 of price improvement
+      expect(await freeBalances(buyer.publicKey)).to.deep.equal([15, 2_500_000]);
+      expect((await tokens(treasuryQuote)) - treasuryBefore).to.equal(traderFee + sellerFee);
+
+      expect(await restingIds('bids')).to.deep.equal([]);
+      expect(await restingIds('asks')).to.deep.equal([0]);
+      const book = await program.account.orderBook.fetch(orderBook);
+      expect((book.asks as { quantity: anchor.BN }[])[0].quantity.toNumber()).to.equal(5);
+
+      // Both fills count towards the mint's market data and both sides' volume
+      const stats = await program.account.mintStats.fetch(
+        findMintStatsAddress(program.programId, bookMint, quoteMint)[0]
+      );
+      expect((stats.tradeCount as anchor.BN).toNumber()).to.equal(2);
+      expect((stats.volume as anchor.BN).toNumber()).to.equal(17_000_000);
+      expect((stats.tokenVolume as anchor.BN).toNumber()).to.equal(15);
+      const buyerVolume = await program.account.traderVolume.fetch(volumeOf(buyer.publicKey));
+      expect((buyerVolume.boughtVolume as anchor.BN).toNumber()).to.equal(17_000_000);
+      const traderVolume = await program.account.traderVolume.fetch(volumeOf(trader.publicKey));
+      expect((traderVolume.soldVolume as anchor.BN).toNumber()).to.equal(11_000_000);
+
+      // The oracle weights both fills by size at their execution prices
+      const oracle = await program.account.priceOracle.fetch(
+        findPriceOracleAddress(program.programId, bookMint, quoteMint)[0]
+      );
+      expect((oracle.lastPrice as anchor.BN).toNumber()).to.equal(Math.floor(17_000_000 / 15));
+    });
+
+    it('withdraws credited funds with settle_funds', async () => {
+      const { base, quote } = accounts.get(buyer.publicKey.toBase58())!;
+      const baseBefore = await tokens(base);
+      const quoteBefore = await tokens(quote);
+
+      await program.methods
+        .settleFunds()
+        .accounts({
+          owner: buyer.publicKey,
+          orderBook,
+          openOrders: openOrdersOf(buyer.publicKey),
+          baseVault,
+          quoteVault,
+          ownerBaseAccount: base,
+          ownerQuoteAccount: quote,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      expect((await tokens(base)) - baseBefore).to.equal(15);
+      expect((await tokens(quote)) - quoteBefore).to.equal(2_500_000);
+      expect(await freeBalances(buyer.publicKey)).to.deep.equal([0, 0]);
+
+      // With nothing resting or unsettled, the open orders account can close
+      await program.methods
+        .closeOpenOrders()
+        .accounts({ owner: buyer.publicKey, orderBook, openOrders: openOrdersOf(buyer.publicKey) })
+        .signers([buyer])
+        .rpc();
+      expect(await connection.getAccountInfo(openOrdersOf(buyer.publicKey))).to.be.null;
+    });
+
+    it('prices orders per whole token and rejects orders below one token', async () => {
+      const decimalMint = await createMint(connection, payer, payer.publicKey, null, 2);
+      await createOrderBook(decimalMint);
+      const [decimalBook] = findOrderBookAddress(program.programId, decimalMint, quoteMint);
+      const decimalVaults = findOrderBookVaultAddresses(program.programId, decimalBook);
+      const bid = (quantity: number) =>
+        program.methods
+          .placeOrder({ bid: {} }, new anchor.BN(1_000_001), new anchor.BN(quantity))
+          .accounts({
+            owner: trader.publicKey,
+            marketplace: marketplacePda,
+            propertyRecord: null,
+            orderBook: decimalBook,
+            openOrders: openOrdersOf(trader.publicKey, decimalBook),
+            traderVolume: volumeOf(trader.publicKey),
+            ownerTokenAccount: accounts.get(trader.publicKey.toBase58())!.quote,
+            ...decimalVaults,
+            evictedOpenOrders: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([trader])
+          .rpc();
+
+      try {
+        await bid(99);
+        expect.fail('order should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('OrderTooSmall');
+      }
+
+      // 1.5 tokens at 1.000001 per token, rounded up
+      await bid(150);
+      expect(await tokens(decimalVaults.quoteVault)).to.equal(1_500_002);
+    });
+
+    it('evicts the worst order of a full side and credits it to its owner', async () => {
+      // Order 0 rests at 1.2; the trader fills the other 31 slots
+      for (let i = 0; i < 31; i++) {
+        await placeOrder(trader, 'ask', 1_500_000, 1);
+      }
+      const full = await restingIds('asks');
+      expect(full.length).to.equal(32);
+      const [traderBase] = await freeBalances(trader.publicKey);
+
+      try {
+        await placeOrder(seller, 'ask', 1_500_000, 1);
+        expect.fail('order should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('OrderBookFull');
+      }
+      try {
+        await placeOrder(seller, 'ask', 1_400_000, 1);
+        expect.fail('order should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('MissingEvictedOpenOrders');
+      }
+
+      // The latest order at the worst price goes first
+      await placeOrder(seller, 'ask', 1_400_000, 1, openOrdersOf(trader.publicKey));
+      let asks = await restingIds('asks');
+      expect(asks.length).to.equal(32);
+      expect(asks).to.not.include(full[31]);
+      expect((await freeBalances(trader.publicKey))[0]).to.equal(traderBase + 1);
+
+      // Evicting your own order needs no other account
+      await placeOrder(trader, 'ask', 1_450_000, 1);
+      asks = await restingIds('asks');
+      expect(asks).to.not.include(full[30]);
+      expect((await freeBalances(trader.publicKey))[0]).to.equal(traderBase + 2);
+    });
+  });
+
+  describe('offers', () => {
+    const OFFER_PRICE = 900_000; // below the listing's PRICE_PER_TOKEN
+    const OFFER_AMOUNT = 10;
+
+    let offer: PublicKey;
+
+    function makeOffer() {
+      return program.methods
+        .makeOffer(
+          new anchor.BN(OFFER_PRICE),
+          new anchor.BN(OFFER_AMOUNT),
+          new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+        )
+        .accounts({
+          buyer: buyer.publicKey,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          listing,
+          offer,
+          quoteMint: NATIVE_MINT,
+          buyerQuoteAccount: null,
+          offerVault: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([buyer])
+        .rpc();
+    }
+
+    before(() => {
+      [offer] = findOfferAddress(program.programId, listing, buyer.publicKey);
+    });
+
+    it('escrows the offer and returns it in full when withdrawn', async () => {
+      const buyerBefore = await balance(buyer.publicKey);
+
+      await makeOffer();
+      expect(await balance(offer)).to.be.greaterThan(OFFER_PRICE * OFFER_AMOUNT);
+
+      await program.methods
+        .withdrawOffer()
+        .accounts({
+          buyer: buyer.publicKey,
+          offer,
+          offerVault: null,
+          buyerQuoteAccount: null,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([buyer])
+        .rpc();
+
+      expect(await connection.getAccountInfo(offer)).to.be.null;
+      expect(await balance(buyer.publicKey)).to.equal(buyerBefore);
+    });
+
+    it('fills the offer at its price when the seller accepts', async () => {
+      const totalPrice = OFFER_PRICE * OFFER_AMOUNT;
+      const feeAmount = Math.floor((totalPrice * MARKETPLACE_FEE_BPS) / 10000);
+
+      const buyerBefore = await balance(buyer.publicKey);
+      const tokensBefore = Number((await getAccount(connection, buyerTokenAccount)).amount);
+      const remainingBefore = (await program.account.listing.fetch(listing)).tokenAmount as anchor.BN;
+
+      await makeOffer();
+
+      const sellerBefore = await balance(seller.publicKey);
+      const treasuryBefore = await balance(treasury);
+
+      await program.methods
+        .acceptOffer()
+        .accounts({
+          seller: seller.publicKey,
+          listing,
+          offer,
+          buyer: buyer.publicKey,
+          marketplace: marketplacePda,
+          propertyRecord: null,
+          marketplaceTreasury: treasury,
+          escrowTokenAccount: escrow,
+          buyerTokenAccount,
+          offerVault: null,
+          sellerQuoteAccount: null,
+          treasuryQuoteAccount: null,
+          mintFeeConfig: findMintFeeConfigAddress(program.programId, mint)[0],
+          royaltyRecipient: null,
+          royaltyQuoteAccount: null,
+          buyerVolume: findTraderVolumeAddress(program.programId, buyer.publicKey, NATIVE_MINT)[0],
+          sellerVolume: findTraderVolumeAddress(program.programId, seller.publicKey, NATIVE_MINT)[0],
+          mintStats: findMintStatsAddress(program.programId, mint, NATIVE_MINT)[0],
+          priceOracle: findPriceOracleAddress(program.programId, mint, NATIVE_MINT)[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([seller])
+        .rpc();
+
+      // The offer account's rent goes back to the buyer when it closes
+      expect(buyerBefore - (await balance(buyer.publicKey))).to.equal(totalPrice);
+      expect((await balance(seller.publicKey)) - sellerBefore).to.equal(totalPrice - feeAmount);
+      expect((await balance(treasury)) - treasuryBefore).to.equal(feeAmount);
+      expect(Number((await getAccount(connection, buyerTokenAccount)).amount)).to.equal(
+        tokensBefore + OFFER_AMOUNT
+      );
+
+      const state = await program.account.listing.fetch(listing);
+      expect((state.tokenAmount as anchor.BN).toNumber()).to.equal(remainingBefore.toNumber() - OFFER_AMOUNT);
+    });
+  });
+
+  describe('fee schedules', () => {
+    const MINT_FEE_BPS = 200;
+    const ROYALTY_SHARE_BPS = 2000; // of the fee
+    const REFERRER_SHARE_BPS = 1000; // of the fee
+
+    const propertyOwner = Keypair.generate();
+    const referrer = Keypair.generate();
+
+    function mintFeeConfigAddress() {
+      return findMintFeeConfigAddress(program.programId, mint)[0];
+    }
+
+    before(async () => {
+      // Both receive fee shares in lamports, so keep them rent exempt
+      await airdrop(propertyOwner.publicKey, LAMPORTS_PER_SOL / 10);
+      await airdrop(referrer.publicKey, LAMPORTS_PER_SOL / 10);
+    });
+
+    it('splits a per-mint fee between treasury, property owner and referrer', async () => {
+      await program.methods
+        .setMintFeeConfig(MINT_FEE_BPS, propertyOwner.publicKey, ROYALTY_SHARE_BPS)
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          propertyTokenMint: mint,
+          mintFeeConfig: mintFeeConfigAddress(),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      await program.methods
+        .setReferrerFeeShare(REFERRER_SHARE_BPS)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+
+      const tokenAmount = 10;
+      const totalPrice = tokenAmount * PRICE_PER_TOKEN;
+      const feeAmount = Math.floor((totalPrice * MINT_FEE_BPS) / 10000);
+      const royaltyAmount = Math.floor((feeAmount * ROYALTY_SHARE_BPS) / 10000);
+      const referrerAmount = Math.floor((feeAmount * REFERRER_SHARE_BPS) / 10000);
+
+      const sellerBefore = await balance(seller.publicKey);
+      const treasuryBefore = await balance(treasury);
+      const ownerBefore = await balance(propertyOwner.publicKey);
+      const referrerBefore = await balance(referrer.publicKey);
+
+      const trade = (tradeReferrer: PublicKey) =>
+        program.methods
+          .executeTrade(
+            new anchor.BN(tokenAmount),
+            new anchor.BN(PRICE_PER_TOKEN),
+            new anchor.BN(tokenAmount),
+            false
+          )
+          .accounts({
+            buyer: buyer.publicKey,
+            seller: seller.publicKey,
+            listing,
+            marketplace: marketplacePda,
+            marketplaceTreasury: treasury,
+            escrowTokenAccount: escrow,
+            buyerTokenAccount,
+            buyerQuoteAccount: null,
+            sellerQuoteAccount: null,
+            treasuryQuoteAccount: null,
+            propertyRecord: null,
+            ...tradeAccounts(buyer.publicKey),
+            royaltyRecipient: propertyOwner.publicKey,
+            referrer: tradeReferrer,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+
+      // Neither party can refer its own trade to claim part of the fee
+      for (const party of [buyer.publicKey, seller.publicKey]) {
+        try {
+          await trade(party);
+          expect.fail('trade should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('InvalidReferrer');
+        }
+      }
+
+      await trade(referrer.publicKey);
+
+      expect((await balance(seller.publicKey)) - sellerBefore).to.equal(totalPrice - feeAmount);
+      expect((await balance(propertyOwner.publicKey)) - ownerBefore).to.equal(royaltyAmount);
+      expect((await balance(referrer.publicKey)) - referrerBefore).to.equal(referrerAmount);
+      expect((await balance(treasury)) - treasuryBefore).to.equal(
+        feeAmount - royaltyAmount - referrerAmount
+      );
+
+      // Leave the singleton marketplace as the other suites expect it
+      await program.methods
+        .setReferrerFeeShare(0)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+      await program.methods
+        .removeMintFeeConfig()
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          mintFeeConfig: mintFeeConfigAddress(),
+        })
+        .rpc();
+      expect(await connection.getAccountInfo(mintFeeConfigAddress())).to.be.null;
+    });
+
+    it('applies the per-mint fee and royalty to auction sales', async () => {
+      await program.methods
+        .setMintFeeConfig(MINT_FEE_BPS, propertyOwner.publicKey, ROYALTY_SHARE_BPS)
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          propertyTokenMint: mint,
+          mintFeeConfig: mintFeeConfigAddress(),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      await mintTo(connection, payer, mint, sellerTokenAccount, payer, 1);
+      const startTime = Math.floor(Date.now() / 1000) - 10;
+      const [auction, auctionEscrow] = await createAuction(
+        { dutch: { endPrice: new anchor.BN(PRICE_PER_TOKEN) } },
+        1,
+        2 * PRICE_PER_TOKEN,
+        startTime,
+        startTime + 3600
+      );
+
+      const treasuryBefore = await balance(treasury);
+      const ownerBefore = await balance(propertyOwner.publicKey);
+
+      const buyDutchAuction = (royaltyRecipient: PublicKey | null) =>
+        program.methods
+          .buyDutchAuction()
+          .accounts({
+            buyer: buyer.publicKey,
+            seller: seller.publicKey,
+            auction,
+            marketplace: marketplacePda,
+            propertyRecord: null,
+            marketplaceTreasury: treasury,
+            escrowTokenAccount: auctionEscrow,
+            buyerTokenAccount,
+            buyerQuoteAccount: null,
+            sellerQuoteAccount: null,
+            treasuryQuoteAccount: null,
+            mintFeeConfig: mintFeeConfigAddress(),
+            royaltyRecipient,
+            royaltyQuoteAccount: null,
+            buyerVolume: findTraderVolumeAddress(program.programId, buyer.publicKey, NATIVE_MINT)[0],
+            sellerVolume: findTraderVolumeAddress(program.programId, seller.publicKey, NATIVE_MINT)[0],
+            mintStats: findMintStatsAddress(program.programId, mint, NATIVE_MINT)[0],
+            priceOracle: findPriceOracleAddress(program.programId, mint, NATIVE_MINT)[0],
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+
+      // The royalty cannot be skipped by leaving its recipient out
+      try {
+        await buyDutchAuction(null);
+        expect.fail('sale without the royalty recipient should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('MissingFeeAccounts');
+      }
+      await buyDutchAuction(propertyOwner.publicKey);
+
+      const price = ((await program.account.auction.fetch(auction)).highestBid as anchor.BN).toNumber();
+      const feeAmount = Math.floor((price * MINT_FEE_BPS) / 10000);
+      const royaltyAmount = Math.floor((feeAmount * ROYALTY_SHARE_BPS) / 10000);
+      expect((await balance(propertyOwner.publicKey)) - ownerBefore).to.equal(royaltyAmount);
+      expect((await balance(treasury)) - treasuryBefore).to.equal(feeAmount - royaltyAmount);
+
+      await program.methods
+        .removeMintFeeConfig()
+        .accounts({
+          authority: payer.publicKey,
+          marketplace: marketplacePda,
+          mintFeeConfig: mintFeeConfigAddress(),
+        })
+        .rpc();
+    });
+
+    it('charges the lowest fee tier the seller\'s volume qualifies for', async () => {
+      const TIER_FEE_BPS = 50;
+      const sellerVolume = findTraderVolumeAddress(program.programId, seller.publicKey, NATIVE_MINT)[0];
+      const volume = (await program.account.traderVolume.fetch(sellerVolume)).volume as anchor.BN;
+
+      await program.methods
+        .setFeeTiers([
+          { quoteMint: NATIVE_MINT, minVolume: volume, fee: TIER_FEE_BPS },
+          { quoteMint: NATIVE_MINT, minVolume: volume.addn(1), fee: 0 },
+        ])
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+
+      const totalPrice = PRICE_PER_TOKEN;
+      const feeAmount = Math.floor((totalPrice * TIER_FEE_BPS) / 10000);
+      const treasuryBefore = await balance(treasury);
+
+      await executeTrade(1);
+
+      expect((await balance(treasury)) - treasuryBefore).to.equal(feeAmount);
+      const after = await program.account.traderVolume.fetch(sellerVolume);
+      expect((after.volume as anchor.BN).toNumber()).to.equal(volume.toNumber() + totalPrice);
+
+      await program.methods
+        .setFeeTiers([])
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+    });
+  });
+
+  describe('admin controls', () => {
+    function setPaused(paused: boolean) {
+      return program.methods
+        .setPaused(paused)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+    }
+
+    it('halts trading while paused but still lets sellers cancel', async () => {
+      const [pausedListing, pausedEscrow] = await createListing(
+        mint,
+        sellerTokenAccount,
+        NATIVE_MINT,
+        PRICE_PER_TOKEN,
+        1
+      );
+
+      await setPaused(true);
+      try {
+        try {
+          await createListing(mint, sellerTokenAccount, NATIVE_MINT, PRICE_PER_TOKEN, 1);
+          expect.fail('listing should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('MarketplacePaused');
+        }
+
+        try {
+          await executeTrade(1);
+          expect.fail('trade should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('MarketplacePaused');
+        }
+
+        await program.methods
+          .cancelListing()
+          .accounts({
+            seller: seller.publicKey,
+            listing: pausedListing,
+            marketplace: marketplacePda,
+            escrowTokenAccount: pausedEscrow,
+            sellerTokenAccount,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([seller])
+          .rpc();
+        const state = await program.account.listing.fetch(pausedListing);
+        expect(state.status).to.deep.equal({ cancelled: {} });
+      } finally {
+        await setPaused(false);
+      }
+    });
+
+    it('freezes trading of a delisted mint', async () => {
+      await program.methods
+        .delistMint(mint)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+      try {
+        await executeTrade(1);
+        expect.fail('trade should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('MintDelisted');
+      } finally {
+        await program.methods
+          .relistMint(mint)
+          .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+          .rpc();
+      }
+
+      await executeTrade(1);
+    });
+
+    it('hands the authority over in two steps', async () => {
+      const successor = Keypair.generate();
+      await airdrop(successor.publicKey, LAMPORTS_PER_SOL / 10);
+
+      await program.methods
+        .proposeAuthority(successor.publicKey)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+
+      // Only the proposed account can accept
+      try {
+        await program.methods
+          .acceptAuthority()
+          .accounts({ newAuthority: buyer.publicKey, marketplace: marketplacePda })
+          .signers([buyer])
+          .rpc();
+        expect.fail('handover should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+
+      await program.methods
+        .acceptAuthority()
+        .accounts({ newAuthority: successor.publicKey, marketplace: marketplacePda })
+        .signers([successor])
+        .rpc();
+      let state = await program.account.marketplace.fetch(marketplacePda);
+      expect((state.authority as PublicKey).equals(successor.publicKey)).to.be.true;
+      expect((state.pendingAuthority as PublicKey).equals(PublicKey.default)).to.be.true;
+
+      // Hand it back so the provider wallet stays the authority for other suites
+      await program.methods
+        .proposeAuthority(payer.publicKey)
+        .accounts({ authority: successor.publicKey, marketplace: marketplacePda })
+        .signers([successor])
+        .rpc();
+      await program.methods
+        .acceptAuthority()
+        .accounts({ newAuthority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+      state = await program.account.marketplace.fetch(marketplacePda);
+      expect((state.authority as PublicKey).equals(payer.publicKey)).to.be.true;
+    });
+
+    it('sends fees to an updated treasury', async () => {
+      const newTreasury = Keypair.generate().publicKey;
+      await airdrop(newTreasury, LAMPORTS_PER_SOL);
+
+      await program.methods
+        .updateTreasury(newTreasury)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+      try {
+        const before = await balance(newTreasury);
+        await program.methods
+          .executeTrade(new anchor.BN(1), new anchor.BN(PRICE_PER_TOKEN), new anchor.BN(1), false)
+          .accounts({
+            buyer: buyer.publicKey,
+            seller: seller.publicKey,
+            listing,
+            marketplace: marketplacePda,
+            marketplaceTreasury: newTreasury,
+            escrowTokenAccount: escrow,
+            buyerTokenAccount,
+            buyerQuoteAccount: null,
+            sellerQuoteAccount: null,
+            treasuryQuoteAccount: null,
+            propertyRecord: null,
+            ...tradeAccounts(buyer.publicKey),
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+          })
+          .signers([buyer])
+          .rpc();
+        expect((await balance(newTreasury)) - before).to.equal(
+          Math.floor((PRICE_PER_TOKEN * MARKETPLACE_FEE_BPS) / 10000)
+        );
+      } finally {
+        await program.methods
+          .updateTreasury(treasury)
+          .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+          .rpc();
+      }
+    });
+  });
+
+  describe('registry verification', () => {
+    const registry = anchor.workspace.Registry as Program;
+    const [registryPda] = PublicKey.findProgramAddressSync([Buffer.from('registry')], registry.programId);
+    const verifierAuthority = Keypair.generate();
+    const [verifier] = PublicKey.findProgramAddressSync(
+      [Buffer.from('verifier'), verifierAuthority.publicKey.toBuffer()],
+      registry.programId
+    );
+    const propertyId = `MKT-${Date.now() % 1_000_000}`;
+    const [propertyRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('property'), Buffer.from(propertyId)],
+      registry.programId
+    );
+
+    let verifiedMint: PublicKey;
+    let sellerVerifiedAccount: PublicKey;
+    let verifiedListing: PublicKey;
+
+    function setRequireVerified(requireVerified: boolean) {
+      return program.methods
+        .setRequireVerified(requireVerified)
+        .accounts({ authority: payer.publicKey, marketplace: marketplacePda })
+        .rpc();
+    }
+
+    before(async () => {
+      if (!(await registry.account.registry.fetchNullable(registryPda))) {
+        await registry.methods
+          .initializeRegistry()
+          .accounts({
+            authority: payer.publicKey,
+            registry: registryPda,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .rpc();
+      }
+      await registry.methods
+        .addVerifier('Test Title Co', 'https://title.example')
+        .accounts({
+          authority: payer.publicKey,
+          registry: registryPda,
+          verifierAuthority: verifierAuthority.publicKey,
+          verifierAccount: verifier,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+
+      verifiedMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      sellerVerifiedAccount = await createAccount(connection, payer, verifiedMint, seller.publicKey);
+      await mintTo(connection, payer, verifiedMint, sellerVerifiedAccount, payer, 10);
+
+      await registry.methods
+        .registerProperty(propertyId, '1 Test St', {
+          titleDeedUrl: 'https://title.example/deed',
+          ownerName: 'Test Owner',
+          ownerId: 'ID-1',
+          legalDescription: 'Lot 1',
+          jurisdiction: 'FL',
+          propertyType: 'residential',
+          zoning: 'R1',
+          lastSaleDate: new anchor.BN(0),
+          lastSaleAmount: new anchor.BN(0),
+        })
+        .accounts({
+          owner: payer.publicKey,
+          registry: registryPda,
+          propertyRecord,
+          tokenMint: verifiedMint,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    });
+
+    it('only lists mints with an unexpired registry verification', async () => {
+      await setRequireVerified(true);
+      try {
+        for (const record of [null, propertyRecord]) {
+          try {
+            await createListing(verifiedMint, sellerVerifiedAccount, NATIVE_MINT, PRICE_PER_TOKEN, 5, 0, record);
+            expect.fail('listing should have been rejected');
+          } catch (err) {
+            expect(`${err}`).to.include('PropertyNotVerified');
+          }
+        }
+
+        const now = Math.floor(Date.now() / 1000);
+        await registry.methods
+          .verifyProperty({
+            verificationDate: new anchor.BN(now),
+            verificationMethod: 'site-visit',
+            verificationNotes: '',
+            isLegalComplianceVerified: true,
+            isPropertyConditionVerified: true,
+            isValuationVerified: true,
+            verificationExpiry: new anchor.BN(now + 86400),
+          })
+          .accounts({
+            verifierAuthority: verifierAuthority.publicKey,
+            verifier,
+            propertyRecord,
+            registry: registryPda,
+          })
+          .signers([verifierAuthority])
+          .rpc();
+
+        [verifiedListing] = await createListing(
+          verifiedMint,
+          sellerVerifiedAccount,
+          NATIVE_MINT,
+          PRICE_PER_TOKEN,
+          5,
+          0,
+          propertyRecord
+        );
+        const state = await program.account.listing.fetch(verifiedListing);
+        expect((state.mint as PublicKey).equals(verifiedMint)).to.be.true;
+      } finally {
+        await setRequireVerified(false);
+      }
+    });
+
+    it('re-checks the verification on offers, not just on listing', async () => {
+      const [offer] = findOfferAddress(program.programId, verifiedListing, buyer.publicKey);
+      const makeOffer = (record: PublicKey | null) =>
+        program.methods
+          .makeOffer(
+            new anchor.BN(PRICE_PER_TOKEN),
+            new anchor.BN(1),
+            new anchor.BN(Math.floor(Date.now() / 1000) + 3600)
+          )
+          .accounts({
+            buyer: buyer.publicKey,
+            marketplace: marketplacePda,
+            propertyRecord: record,
+            listing: verifiedListing,
+            offer,
+            quoteMint: NATIVE_MINT,
+            buyerQuoteAccount: null,
+            offerVault: null,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            rent: SYSVAR_RENT_PUBKEY,
+          })
+          .signers([buyer])
+          .rpc();
+
+      await setRequireVerified(true);
+      try {
+        try {
+          await makeOffer(null);
+          expect.fail('offer should have been rejected');
+        } catch (err) {
+          expect(`${err}`).to.include('PropertyNotVerified');
+        }
+        await makeOffer(propertyRecord);
+        const state = await program.account.offer.fetch(offer);
+        expect((state.listing as PublicKey).equals(verifiedListing)).to.be.true;
+      } finally {
+        await setRequireVerified(false);
+      }
+    });
+  });
+});
@@ -0,0 +1,708 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import {
+  AuthorityType,
+  createAccount,
+  createMint,
+  getMint,
+  mintTo,
+  setAuthority,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+
+const TOTAL_TOKENS = 100;
+const VOTING_PERIOD = 2; // seconds
+const LIQUIDATION_LEN = 162; // Liquidation::LEN
+
+describe('property_tokenization', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.PropertyTokenization as Program;
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const voter = Keypair.generate();
+
+  const propertyId = `PT-${Date.now() % 1_000_000}`;
+  const [property] = findPropertyAddress(propertyId);
+  const [governance] = findGovernanceAddress(property);
+  const [governanceVault] = findGovernanceVaultAddress(property);
+  const [voterRecord] = findVoterRecordAddress(governance, voter.publicKey);
+  const [proposal] = findProposalAddress(governance, 0);
+  const [voteRecord] = findVoteRecordAddress(proposal, voter.publicKey);
+
+  let mint: PublicKey;
+  let voterTokenAccount: PublicKey;
+
+  function findGovernanceAddress(propertyAddress: PublicKey) {
+    return PublicKey.findProgramAddressSync([Buffer.from('governance'), propertyAddress.toBuffer()], program.programId);
+  }
+
+  function findGovernanceVaultAddress(propertyAddress: PublicKey) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('governance_vault'), propertyAddress.toBuffer()],
+      program.programId
+    );
+  }
+
+  function findVoterRecordAddress(governanceAddress: PublicKey, holder: PublicKey) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('voter'), governanceAddress.toBuffer(), holder.toBuffer()],
+      program.programId
+    );
+  }
+
+  function findProposalAddress(governanceAddress: PublicKey, index: number) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('proposal'), governanceAddress.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+  }
+
+  function findVoteRecordAddress(proposalAddress: PublicKey, holder: PublicKey) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('vote'), proposalAddress.toBuffer(), holder.toBuffer()],
+      program.programId
+    );
+  }
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    const latest = await connection.getLatestBlockhash();
+    await connection.confirmTransaction({ signature, ...latest });
+  }
+
+  async function balance(account: PublicKey): Promise<number> {
+    return connection.getBalance(account, 'confirmed');
+  }
+
+  function findPropertyAddress(id: string) {
+    return PublicKey.findProgramAddressSync([Buffer.from('property'), Buffer.from(id)], program.programId);
+  }
+
+  function initializeProperty(id: string, propertyMint: PublicKey) {
+    return program.methods
+      .initializeProperty(id, `https://property.example/${id}`, new anchor.BN(TOTAL_TOKENS), new anchor.BN(1), {
+        title: 'Test Property',
+        description: '',
+        address: '3 Test St',
+        city: 'Miami',
+        state: 'FL',
+        country: 'US',
+        zipCode: '33101',
+        propertyType: 'residential',
+        squareFeet: 1000,
+        bedrooms: 2,
+        bathrooms: 1,
+        yearBuilt: 2000,
+        rentYield: 500,
+        appreciationPotential: 300,
+      })
+      .accounts({
+        owner: payer.publicKey,
+        property: findPropertyAddress(id)[0],
+        mint: propertyMint,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  // The provider wallet pays transaction fees, so the voter's balance only
+  // moves by the rent it gets back
+  function closeProposal() {
+    return program.methods
+      .closeProposal()
+      .accounts({ proposal, proposer: voter.publicKey })
+      .rpc();
+  }
+
+  function closeVoteRecord() {
+    return program.methods
+      .closeVoteRecord()
+      .accounts({ voteRecord, proposal, voter: voter.publicKey })
+      .rpc();
+  }
+
+  before(async () => {
+    await airdrop(voter.publicKey, LAMPORTS_PER_SOL);
+
+    mint = await createMint(connection, payer, payer.publicKey, null, 0);
+    voterTokenAccount = await createAccount(connection, payer, mint, voter.publicKey);
+    await mintTo(connection, payer, mint, voterTokenAccount, payer, TOTAL_TOKENS);
+
+    await initializeProperty(propertyId, mint);
+
+    await program.methods
+      .initializeGovernance(5000, new anchor.BN(VOTING_PERIOD), new anchor.BN(1))
+      .accounts({
+        owner: payer.publicKey,
+        property,
+        ownerMultisig: null,
+        mint,
+        governance,
+        governanceVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+
+    await program.methods
+      .depositGovernanceTokens(new anchor.BN(TOTAL_TOKENS))
+      .accounts({
+        voter: voter.publicKey,
+        governance,
+        voterRecord,
+        voterTokenAccount,
+        governanceVault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+
+    await program.methods
+      .createProposal({ signal: {} }, 'https://property.example/proposals/0')
+      .accounts({
+        proposer: voter.publicKey,
+        governance,
+        voterRecord,
+        proposal,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+
+    await program.methods
+      .castVote(true)
+      .accounts({
+        voter: voter.publicKey,
+        proposal,
+        governance,
+        voterRecord,
+        voteRecord,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([voter])
+      .rpc();
+  });
+
+  describe('closing governance accounts', () => {
+    it('keeps the proposal and vote record open while voting runs', async () => {
+      try {
+        await closeProposal();
+        expect.fail('close should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('ProposalStillActive');
+      }
+
+      try {
+        await closeVoteRecord();
+        expect.fail('close should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('VotingStillOpen');
+      }
+    });
+
+    it('returns a finalized proposal\'s rent to the proposer', async () => {
+      await new Promise((resolve) => setTimeout(resolve, (VOTING_PERIOD + 2) * 1000));
+      await program.methods
+        .executeProposal()
+        .accounts({ executor: payer.publicKey, proposal, governance, property, liquidation: null, mint: null })
+        .rpc();
+
+      const proposerBefore = await balance(voter.publicKey);
+      const rent = await balance(proposal);
+
+      await closeProposal();
+
+      expect(await connection.getAccountInfo(proposal)).to.be.null;
+      expect((await balance(voter.publicKey)) - proposerBefore).to.equal(rent);
+    });
+
+    it('returns a vote record\'s rent to the voter once its proposal is closed', async () => {
+      const voterBefore = await balance(voter.publicKey);
+      const rent = await balance(voteRecord);
+
+      await closeVoteRecord();
+
+      expect(await connection.getAccountInfo(voteRecord)).to.be.null;
+      expect((await balance(voter.publicKey)) - voterBefore).to.equal(rent);
+    });
+
+    it('closes an emptied voter record back to the voter', async () => {
+      const closeVoterRecord = () =>
+        program.methods
+          .closeVoterRecord()
+          .accounts({ voter: voter.publicKey, governance, voterRecord })
+          .signers([voter])
+          .rpc();
+
+      try {
+        await closeVoterRecord();
+        expect.fail('close should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('VoterRecordNotEmpty');
+      }
+
+      await program.methods
+        .withdrawGovernanceTokens(new anchor.BN(TOTAL_TOKENS))
+        .accounts({
+          voter: voter.publicKey,
+          governance,
+          voterRecord,
+          voterTokenAccount,
+          governanceVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([voter])
+        .rpc();
+
+      const voterBefore = await balance(voter.publicKey);
+      const rent = await balance(voterRecord);
+
+      await closeVoterRecord();
+
+      expect(await connection.getAccountInfo(voterRecord)).to.be.null;
+      expect((await balance(voter.publicKey)) - voterBefore).to.equal(rent);
+    });
+  });
+
+  describe('voting', () => {
+    const holder = Keypair.generate();
+
+    const votingId = `PV-${Date.now() % 1_000_000}`;
+    const [votingProperty] = findPropertyAddress(votingId);
+    const [votingGovernance] = findGovernanceAddress(votingProperty);
+    const [votingVault] = findGovernanceVaultAddress(votingProperty);
+    const [holderRecord] = findVoterRecordAddress(votingGovernance, holder.publicKey);
+
+    let holderTokenAccount: PublicKey;
+    let proposalCount = 0;
+
+    async function propose(): Promise<PublicKey> {
+      const [address] = findProposalAddress(votingGovernance, proposalCount);
+      await program.methods
+        .createProposal({ signal: {} }, `https://property.example/proposals/${proposalCount}`)
+        .accounts({
+          proposer: holder.publicKey,
+          governance: votingGovernance,
+          voterRecord: holderRecord,
+          proposal: address,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([holder])
+        .rpc();
+      proposalCount += 1;
+      return address;
+    }
+
+    function vote(address: PublicKey) {
+      return program.methods
+        .castVote(true)
+        .accounts({
+          voter: holder.publicKey,
+          proposal: address,
+          governance: votingGovernance,
+          voterRecord: holderRecord,
+          voteRecord: findVoteRecordAddress(address, holder.publicKey)[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([holder])
+        .rpc();
+    }
+
+    function cancel(address: PublicKey, proposer: Keypair) {
+      return program.methods
+        .cancelProposal()
+        .accounts({ proposer: proposer.publicKey, proposal: address })
+        .signers([proposer])
+        .rpc();
+    }
+
+    before(async () => {
+      await airdrop(holder.publicKey, LAMPORTS_PER_SOL);
+
+      const votingMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      holderTokenAccount = await createAccount(connection, payer, votingMint, holder.publicKey);
+      await mintTo(connection, payer, votingMint, holderTokenAccount, payer, TOTAL_TOKENS);
+      await initializeProperty(votingId, votingMint);
+
+      // Long enough that voting stays open for the whole suite
+      await program.methods
+        .initializeGovernance(5000, new anchor.BN(3600), new anchor.BN(1))
+        .accounts({
+          owner: payer.publicKey,
+          property: votingProperty,
+          ownerMultisig: null,
+          mint: votingMint,
+          governance: votingGovernance,
+          governanceVault: votingVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      await program.methods
+        .depositGovernanceTokens(new anchor.BN(TOTAL_TOKENS))
+        .accounts({
+          voter: holder.publicKey,
+          governance: votingGovernance,
+          voterRecord: holderRecord,
+          voterTokenAccount: holderTokenAccount,
+          governanceVault: votingVault,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([holder])
+        .rpc();
+    });
+
+    it('lets only the proposer cancel, and only before the first vote', async () => {
+      const unvoted = await propose();
+      try {
+        await cancel(unvoted, voter);
+        expect.fail('cancel should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+      await cancel(unvoted, holder);
+      expect((await program.account.proposal.fetch(unvoted)).status).to.deep.equal({ cancelled: {} });
+
+      const voted = await propose();
+      await vote(voted);
+      try {
+        await cancel(voted, holder);
+        expect.fail('cancel should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('ProposalHasVotes');
+      }
+      expect((await program.account.proposal.fetch(voted)).status).to.deep.equal({ active: {} });
+    });
+
+    it('counts a holder\'s escrow once per proposal', async () => {
+      const [voted] = findProposalAddress(votingGovernance, proposalCount - 1);
+
+      // The vote record already exists, so creating it again fails
+      try {
+        await vote(voted);
+        expect.fail('second vote should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('0x0');
+      }
+
+      // The voted tokens cannot leave escrow to be deposited and voted again
+      // from another wallet
+      try {
+        await program.methods
+          .withdrawGovernanceTokens(new anchor.BN(TOTAL_TOKENS))
+          .accounts({
+            voter: holder.publicKey,
+            governance: votingGovernance,
+            voterRecord: holderRecord,
+            voterTokenAccount: holderTokenAccount,
+            governanceVault: votingVault,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([holder])
+          .rpc();
+        expect.fail('withdrawal should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('TokensLocked');
+      }
+
+      const state = await program.account.proposal.fetch(voted);
+      expect((state.votesFor as anchor.BN).toNumber()).to.equal(TOTAL_TOKENS);
+      expect((state.votesAgainst as anchor.BN).toNumber()).to.equal(0);
+    });
+  });
+
+  describe('owner controls', () => {
+    const newOwner = Keypair.generate();
+    const cosigners = [Keypair.generate(), Keypair.generate(), Keypair.generate()];
+
+    const controlledId = `PO-${Date.now() % 1_000_000}`;
+    const [controlled] = findPropertyAddress(controlledId);
+    const [ownerMultisig] = PublicKey.findProgramAddressSync(
+      [Buffer.from('owner_multisig'), controlled.toBuffer()],
+      program.programId
+    );
+
+    // Co-signers beyond the transaction's signer go in remaining accounts
+    function asCosigners(keypairs: Keypair[]) {
+      return keypairs.map((keypair) => ({ pubkey: keypair.publicKey, isSigner: true, isWritable: false }));
+    }
+
+    function updateStatus(signer: Keypair, status: object, extra: Keypair[] = [], multisig: PublicKey | null = null) {
+      return program.methods
+        .updatePropertyStatus(status)
+        .accounts({ owner: signer.publicKey, property: controlled, ownerMultisig: multisig })
+        .remainingAccounts(asCosigners(extra))
+        .signers([signer, ...extra])
+        .rpc();
+    }
+
+    before(async () => {
+      await airdrop(newOwner.publicKey, LAMPORTS_PER_SOL);
+      const controlledMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      await initializeProperty(controlledId, controlledMint);
+    });
+
+    it('hands ownership over only once the pending owner accepts', async () => {
+      const propose = () =>
+        program.methods
+          .proposeOwnerTransfer(newOwner.publicKey)
+          .accounts({ owner: payer.publicKey, property: controlled, ownerMultisig: null })
+          .rpc();
+      const accept = (signer: Keypair) =>
+        program.methods
+          .acceptOwnerTransfer()
+          .accounts({ newOwner: signer.publicKey, property: controlled, ownerMultisig: null })
+          .signers([signer])
+          .rpc();
+
+      await propose();
+      let state = await program.account.property.fetch(controlled);
+      expect((state.owner as PublicKey).equals(payer.publicKey)).to.be.true;
+      expect((state.pendingOwner as PublicKey).equals(newOwner.publicKey)).to.be.true;
+
+      try {
+        await accept(voter);
+        expect.fail('accept should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+
+      await program.methods
+        .cancelOwnerTransfer()
+        .accounts({ owner: payer.publicKey, property: controlled, ownerMultisig: null })
+        .rpc();
+      try {
+        await accept(newOwner);
+        expect.fail('accept should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('NoPendingOwner');
+      }
+
+      await propose();
+      await accept(newOwner);
+      state = await program.account.property.fetch(controlled);
+      expect((state.owner as PublicKey).equals(newOwner.publicKey)).to.be.true;
+      expect(state.pendingOwner).to.be.null;
+
+      // The previous owner has lost control
+      try {
+        await updateStatus(payer, { pending: {} });
+        expect.fail('update should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+    });
+
+    it('needs the multisig threshold once a multisig owns the property', async () => {
+      await program.methods
+        .createOwnerMultisig(cosigners.map((keypair) => keypair.publicKey), 2)
+        .accounts({
+          owner: newOwner.publicKey,
+          property: controlled,
+          ownerMultisig,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([newOwner])
+        .rpc();
+      await program.methods
+        .proposeOwnerTransfer(ownerMultisig)
+        .accounts({ owner: newOwner.publicKey, property: controlled, ownerMultisig: null })
+        .signers([newOwner])
+        .rpc();
+
+      const accept = (extra: Keypair[]) =>
+        program.methods
+          .acceptOwnerTransfer()
+          .accounts({ newOwner: cosigners[0].publicKey, property: controlled, ownerMultisig })
+          .remainingAccounts(asCosigners(extra))
+          .signers([cosigners[0], ...extra])
+          .rpc();
+
+      // One owner alone, even listed twice, is below the threshold
+      try {
+        await accept([cosigners[0]]);
+        expect.fail('accept should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('NotEnoughSigners');
+      }
+
+      await accept([cosigners[1]]);
+      let state = await program.account.property.fetch(controlled);
+      expect((state.owner as PublicKey).equals(ownerMultisig)).to.be.true;
+
+      // Signers outside the owner set do not count towards the threshold
+      try {
+        await updateStatus(cosigners[2], { pending: {} }, [newOwner], ownerMultisig);
+        expect.fail('update should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('NotEnoughSigners');
+      }
+
+      await updateStatus(cosigners[2], { pending: {} }, [cosigners[1]], ownerMultisig);
+      state = await program.account.property.fetch(controlled);
+      expect(state.status).to.deep.equal({ pending: {} });
+    });
+  });
+
+  describe('liquidation', () => {
+    const PROCEEDS = 1_000; // lamports, deliberately not divisible by the supply
+    const holders = [Keypair.generate(), Keypair.generate()];
+    const funder = Keypair.generate();
+
+    const liquidatedId = `PL-${Date.now() % 1_000_000}`;
+    const [liquidated] = findPropertyAddress(liquidatedId);
+    const [liquidation] = PublicKey.findProgramAddressSync(
+      [Buffer.from('liquidation'), liquidated.toBuffer()],
+      program.programId
+    );
+
+    let liquidatedMint: PublicKey;
+    const holderAccounts: PublicKey[] = [];
+
+    function startLiquidation() {
+      return program.methods
+        .startLiquidation()
+        .accounts({
+          owner: payer.publicKey,
+          property: liquidated,
+          ownerMultisig: null,
+          mint: liquidatedMint,
+          liquidation,
+        })
+        .rpc();
+    }
+
+    function redeem(index: number, amount: number) {
+      return program.methods
+        .redeemLiquidation(new anchor.BN(amount))
+        .accounts({
+          holder: holders[index].publicKey,
+          property: liquidated,
+          mint: liquidatedMint,
+          holderTokenAccount: holderAccounts[index],
+          liquidation,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([holders[index]])
+        .rpc();
+    }
+
+    function closeLiquidation() {
+      return program.methods
+        .closeLiquidation()
+        .accounts({
+          owner: payer.publicKey,
+          property: liquidated,
+          ownerMultisig: null,
+          liquidation,
+          funder: funder.publicKey,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      liquidatedMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      for (const [index, holder] of holders.entries()) {
+        await airdrop(holder.publicKey, LAMPORTS_PER_SOL);
+        holderAccounts.push(await createAccount(connection, payer, liquidatedMint, holder.publicKey));
+        // A supply of 3 splits the proceeds unevenly
+        await mintTo(connection, payer, liquidatedMint, holderAccounts[index], payer, index + 1);
+      }
+      await initializeProperty(liquidatedId, liquidatedMint);
+
+      await airdrop(funder.publicKey, LAMPORTS_PER_SOL);
+      await program.methods
+        .fundLiquidation(new anchor.BN(PROCEEDS))
+        .accounts({
+          funder: funder.publicKey,
+          property: liquidated,
+          liquidation,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([funder])
+        .rpc();
+    });
+
+    it('will not start while someone can still mint', async () => {
+      try {
+        await startLiquidation();
+        expect.fail('start should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('MintAuthorityNotRevoked');
+      }
+
+      await setAuthority(connection, payer, liquidatedMint, payer, AuthorityType.MintTokens, null);
+      await startLiquidation();
+
+      const state = await program.account.liquidation.fetch(liquidation);
+      expect(state.status).to.deep.equal({ active: {} });
+      expect((state.supply as anchor.BN).toNumber()).to.equal(3);
+      expect((state.redeemUntil as anchor.BN).toNumber()).to.be.greaterThan(Math.floor(Date.now() / 1000));
+    });
+
+    it('pays pro rata, rounding down, and gives the last redemption the remainder', async () => {
+      // 1000 * 1 / 3 rounds down to 333 for each of the first two tokens
+      const payouts: number[] = [];
+      for (const [index, amount] of [[0, 1], [1, 1], [1, 1]]) {
+        const before = await balance(holders[index].publicKey);
+        await redeem(index, amount);
+        payouts.push((await balance(holders[index].publicKey)) - before);
+
+        if (payouts.length === 1) {
+          // Tokens are still outstanding, so the liquidation cannot close yet
+          try {
+            await closeLiquidation();
+            expect.fail('close should have been rejected');
+          } catch (err) {
+            expect(`${err}`).to.include('LiquidationNotComplete');
+          }
+        }
+      }
+      expect(payouts).to.deep.equal([333, 333, 334]);
+
+      const state = await program.account.liquidation.fetch(liquidation);
+      expect(state.status).to.deep.equal({ completed: {} });
+      expect((state.paidOut as anchor.BN).toNumber()).to.equal(PROCEEDS);
+      expect(Number((await getMint(connection, liquidatedMint)).supply)).to.equal(0);
+      expect((await program.account.property.fetch(liquidated)).status).to.deep.equal({ liquidated: {} });
+    });
+
+    it('closes a completed liquidation, returning only rent to the funder', async () => {
+      const rent = await connection.getMinimumBalanceForRentExemption(LIQUIDATION_LEN);
+      expect(await balance(liquidation)).to.equal(rent);
+      const funderBefore = await balance(funder.publicKey);
+      const ownerBefore = await balance(payer.publicKey);
+
+      await closeLiquidation();
+
+      expect(await connection.getAccountInfo(liquidation)).to.be.null;
+      expect((await balance(funder.publicKey)) - funderBefore).to.equal(rent);
+      // The owner only pays the transaction fee
+      expect(await balance(payer.publicKey)).to.be.at.most(ownerBefore);
+    });
+
+    it('keeps the liquidated property so its id cannot be reused', async () => {
+      expect((await program.account.property.fetch(liquidated)).status).to.deep.equal({ liquidated: {} });
+
+      // The property account still exists, so initializing the id again fails
+      const otherMint = await createMint(connection, payer, payer.publicKey, null, 0);
+      try {
+        await initializeProperty(liquidatedId, otherMint);
+        expect.fail('initialization should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('0x0');
+      }
+    });
+  });
+});
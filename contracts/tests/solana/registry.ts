@@ -0,0 +1,423 @@
+import * as anchor from '@coral-xyz/anchor';
+import { Program } from '@coral-xyz/anchor';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+
+describe('registry', () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Registry as Program;
+  const connection = provider.connection;
+  const payer = (provider.wallet as anchor.Wallet).payer;
+
+  const owner = Keypair.generate();
+  const verifierAuthority = Keypair.generate();
+
+  const [registryPda] = PublicKey.findProgramAddressSync([Buffer.from('registry')], program.programId);
+  const [verifier] = PublicKey.findProgramAddressSync(
+    [Buffer.from('verifier'), verifierAuthority.publicKey.toBuffer()],
+    program.programId
+  );
+  const propertyId = `REG-${Date.now() % 1_000_000}`;
+  const [propertyRecord] = PublicKey.findProgramAddressSync(
+    [Buffer.from('property'), Buffer.from(propertyId)],
+    program.programId
+  );
+
+  async function airdrop(to: PublicKey, lamports: number) {
+    const signature = await connection.requestAirdrop(to, lamports);
+    const latest = await connection.getLatestBlockhash();
+    await connection.confirmTransaction({ signature, ...latest });
+  }
+
+  async function balance(account: PublicKey): Promise<number> {
+    return connection.getBalance(account, 'confirmed');
+  }
+
+  function findAmendmentAddress(record: PublicKey, index: number): PublicKey {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('amendment'), record.toBuffer(), new anchor.BN(index).toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+  }
+
+  function legalDetails(ownerId: string, lastSaleAmount = 0) {
+    return {
+      titleDeedUrl: 'https://title.example/deed',
+      ownerName: 'Test Owner',
+      ownerId,
+      legalDescription: 'Lot 2',
+      jurisdiction: 'FL',
+      propertyType: 'residential',
+      zoning: 'R1',
+      lastSaleDate: new anchor.BN(0),
+      lastSaleAmount: new anchor.BN(lastSaleAmount),
+    };
+  }
+
+  function addVerifier() {
+    return program.methods
+      .addVerifier('Closing Title Co', 'https://closing.example')
+      .accounts({
+        authority: payer.publicKey,
+        registry: registryPda,
+        verifierAuthority: verifierAuthority.publicKey,
+        verifierAccount: verifier,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc();
+  }
+
+  function removeVerifier() {
+    return program.methods
+      .removeVerifier()
+      .accounts({ authority: payer.publicKey, registry: registryPda, verifierAccount: verifier })
+      .rpc();
+  }
+
+  async function propertyCount(): Promise<number> {
+    return ((await program.account.registry.fetch(registryPda)).propertyCount as anchor.BN).toNumber();
+  }
+
+  async function verifierCount(): Promise<number> {
+    return ((await program.account.registry.fetch(registryPda)).verifierCount as anchor.BN).toNumber();
+  }
+
+  function closePropertyRecord() {
+    // Signed by the registry authority; the rent still goes to the owner
+    return program.methods
+      .closePropertyRecord()
+      .accounts({
+        authority: payer.publicKey,
+        registry: registryPda,
+        propertyRecord,
+        owner: owner.publicKey,
+      })
+      .rpc();
+  }
+
+  function closeVerifier() {
+    return program.methods
+      .closeVerifier()
+      .accounts({ authority: payer.publicKey, registry: registryPda, verifierAccount: verifier })
+      .rpc({ commitment: 'confirmed' });
+  }
+
+  before(async () => {
+    await airdrop(owner.publicKey, LAMPORTS_PER_SOL);
+
+    // The registry is a singleton, so reuse it if another suite created it
+    if (!(await program.account.registry.fetchNullable(registryPda))) {
+      await program.methods
+        .initializeRegistry()
+        .accounts({
+          authority: payer.publicKey,
+          registry: registryPda,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+    }
+
+    await program.methods
+      .registerProperty(propertyId, '2 Test St', legalDetails('ID-2'))
+      .accounts({
+        owner: owner.publicKey,
+        registry: registryPda,
+        propertyRecord,
+        tokenMint: Keypair.generate().publicKey,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([owner])
+      .rpc();
+  });
+
+  it('only closes property records once they are rejected', async () => {
+    try {
+      await closePropertyRecord();
+      expect.fail('close should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('RecordNotRejected');
+    }
+
+    await program.methods
+      .updatePropertyStatus({ rejected: {} })
+      .accounts({ authority: payer.publicKey, registry: registryPda, propertyRecord })
+      .rpc();
+
+    const ownerBefore = await balance(owner.publicKey);
+    const rent = await balance(propertyRecord);
+    const countBefore = await propertyCount();
+
+    await closePropertyRecord();
+
+    expect(await connection.getAccountInfo(propertyRecord)).to.be.null;
+    expect((await balance(owner.publicKey)) - ownerBefore).to.equal(rent);
+    expect(await propertyCount()).to.equal(countBefore - 1);
+  });
+
+  it('keys verifiers by their own authority and updates them in place', async () => {
+    const countBefore = await verifierCount();
+    await addVerifier();
+
+    let account = await program.account.verifier.fetch(verifier);
+    expect((account.authority as PublicKey).equals(verifierAuthority.publicKey)).to.be.true;
+    expect(account.isActive).to.be.true;
+    expect(await verifierCount()).to.equal(countBefore + 1);
+
+    try {
+      await addVerifier();
+      expect.fail('add should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('VerifierAlreadyActive');
+    }
+
+    await program.methods
+      .updateVerifier('Closing Title Co LLC', 'https://closing.example/llc')
+      .accounts({ authority: payer.publicKey, registry: registryPda, verifierAccount: verifier })
+      .rpc();
+
+    account = await program.account.verifier.fetch(verifier);
+    expect(account.name).to.equal('Closing Title Co LLC');
+    expect(account.url).to.equal('https://closing.example/llc');
+    expect(await verifierCount()).to.equal(countBefore + 1);
+  });
+
+  it('returns a removed verifier\'s rent to the registry authority', async () => {
+    try {
+      await closeVerifier();
+      expect.fail('close should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('VerifierStillActive');
+    }
+
+    const countBefore = await verifierCount();
+    await removeVerifier();
+
+    // A second removal must not decrement the count again
+    try {
+      await removeVerifier();
+      expect.fail('remove should have been rejected');
+    } catch (err) {
+      expect(`${err}`).to.include('VerifierNotActive');
+    }
+    expect(await verifierCount()).to.equal(countBefore - 1);
+
+    // The authority also pays for the transaction, so net out its fee
+    const authorityBefore = await balance(payer.publicKey);
+    const rent = await balance(verifier);
+
+    const signature = await closeVerifier();
+    const transaction = await connection.getTransaction(signature, { commitment: 'confirmed' });
+
+    expect(await connection.getAccountInfo(verifier)).to.be.null;
+    expect((await balance(payer.publicKey)) - authorityBefore).to.equal(rent - transaction!.meta!.fee);
+  });
+
+  describe('amending property records', () => {
+    const amendedId = `AMD-${Date.now() % 1_000_000}`;
+    const [amendedRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('property'), Buffer.from(amendedId)],
+      program.programId
+    );
+    const newOwner = Keypair.generate();
+
+    function amend(propertyAddress: string | null, details: ReturnType<typeof legalDetails> | null, index: number) {
+      return program.methods
+        .amendPropertyRecord(propertyAddress, details)
+        .accounts({
+          owner: owner.publicKey,
+          registry: registryPda,
+          propertyRecord: amendedRecord,
+          amendment: findAmendmentAddress(amendedRecord, index),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+    }
+
+    function transferOwnership(cosigner: Keypair, cosigningVerifier = verifier) {
+      return program.methods
+        .transferRecordOwnership('New Owner', 'ID-3')
+        .accounts({
+          owner: owner.publicKey,
+          newOwner: newOwner.publicKey,
+          verifierAuthority: cosigner.publicKey,
+          verifier: cosigningVerifier,
+          registry: registryPda,
+          propertyRecord: amendedRecord,
+          amendment: findAmendmentAddress(amendedRecord, 2),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([owner, newOwner, cosigner])
+        .rpc();
+    }
+
+    function verify(authority: Keypair, verifierAccount: PublicKey) {
+      const now = Math.floor(Date.now() / 1000);
+      return program.methods
+        .verifyProperty({
+          verificationDate: new anchor.BN(now),
+          verificationMethod: 'site-visit',
+          verificationNotes: '',
+          isLegalComplianceVerified: true,
+          isPropertyConditionVerified: true,
+          isValuationVerified: true,
+          verificationExpiry: new anchor.BN(now + 86400),
+        })
+        .accounts({
+          verifierAuthority: authority.publicKey,
+          verifier: verifierAccount,
+          propertyRecord: amendedRecord,
+          registry: registryPda,
+        })
+        .signers([authority])
+        .rpc();
+    }
+
+    function closeAmendment(index: number) {
+      // Permissionless; the rent goes back to whoever paid for the amendment
+      return program.methods
+        .closeAmendment()
+        .accounts({
+          amendment: findAmendmentAddress(amendedRecord, index),
+          propertyRecord: amendedRecord,
+          amendedBy: owner.publicKey,
+        })
+        .rpc();
+    }
+
+    before(async () => {
+      await addVerifier();
+      await program.methods
+        .registerProperty(amendedId, '3 Test St', legalDetails('ID-2'))
+        .accounts({
+          owner: owner.publicKey,
+          registry: registryPda,
+          propertyRecord: amendedRecord,
+          tokenMint: Keypair.generate().publicKey,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([owner])
+        .rpc();
+
+      await verify(verifierAuthority, verifier);
+    });
+
+    it('keeps the verification when only the sale history changes', async () => {
+      try {
+        await amend('3 Test St', legalDetails('ID-2'), 0);
+        expect.fail('amendment should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('NothingToAmend');
+      }
+
+      await amend(null, legalDetails('ID-2', 450_000), 0);
+
+      const record = await program.account.propertyRecord.fetch(amendedRecord);
+      expect(record.verificationStatus).to.deep.equal({ verified: {} });
+      expect((record.legalDetails.lastSaleAmount as anchor.BN).toNumber()).to.equal(450_000);
+
+      const entry = await program.account.propertyAmendment.fetch(findAmendmentAddress(amendedRecord, 0));
+      expect(entry.kind).to.deep.equal({ details: {} });
+      expect((entry.previousLegalDetails.lastSaleAmount as anchor.BN).toNumber()).to.equal(0);
+    });
+
+    it('resets verification to pending when the address changes', async () => {
+      await amend('3A Test St', null, 1);
+
+      const record = await program.account.propertyRecord.fetch(amendedRecord);
+      expect(record.address).to.equal('3A Test St');
+      expect(record.verificationStatus).to.deep.equal({ pending: {} });
+      expect(record.verifier).to.be.null;
+      expect((record.amendmentCount as anchor.BN).toNumber()).to.equal(2);
+
+      const entry = await program.account.propertyAmendment.fetch(findAmendmentAddress(amendedRecord, 1));
+      expect(entry.previousAddress).to.equal('3 Test St');
+      expect(entry.previousStatus).to.deep.equal({ verified: {} });
+    });
+
+    it('transfers ownership only with the verifying verifier\'s co-signature', async () => {
+      try {
+        await transferOwnership(Keypair.generate());
+        expect.fail('transfer should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('Unauthorized');
+      }
+
+      // Another active verifier cannot vouch for a record it did not verify
+      const otherAuthority = Keypair.generate();
+      const [otherVerifier] = PublicKey.findProgramAddressSync(
+        [Buffer.from('verifier'), otherAuthority.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .addVerifier('Other Title Co', 'https://other.example')
+        .accounts({
+          authority: payer.publicKey,
+          registry: registryPda,
+          verifierAuthority: otherAuthority.publicKey,
+          verifierAccount: otherVerifier,
+          systemProgram: SystemProgram.programId,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .rpc();
+      await verify(verifierAuthority, verifier);
+      try {
+        await transferOwnership(otherAuthority, otherVerifier);
+        expect.fail('transfer should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('VerifierMismatch');
+      }
+
+      await transferOwnership(verifierAuthority);
+
+      const record = await program.account.propertyRecord.fetch(amendedRecord);
+      expect((record.owner as PublicKey).equals(newOwner.publicKey)).to.be.true;
+      expect(record.legalDetails.ownerName).to.equal('New Owner');
+      expect(record.verificationStatus).to.deep.equal({ verified: {} });
+
+      const entry = await program.account.propertyAmendment.fetch(findAmendmentAddress(amendedRecord, 2));
+      expect(entry.kind).to.deep.equal({ ownershipTransfer: {} });
+      expect((entry.previousOwner as PublicKey).equals(owner.publicKey)).to.be.true;
+      expect((entry.verifier as PublicKey).equals(verifier)).to.be.true;
+    });
+
+    it('closes the history only once its record is closed', async () => {
+      try {
+        await closeAmendment(0);
+        expect.fail('close should have been rejected');
+      } catch (err) {
+        expect(`${err}`).to.include('RecordStillOpen');
+      }
+
+      await program.methods
+        .updatePropertyStatus({ rejected: {} })
+        .accounts({ authority: payer.publicKey, registry: registryPda, propertyRecord: amendedRecord })
+        .rpc();
+      await program.methods
+        .closePropertyRecord()
+        .accounts({
+          authority: payer.publicKey,
+          registry: registryPda,
+          propertyRecord: amendedRecord,
+          owner: newOwner.publicKey,
+        })
+        .rpc();
+
+      // The previous owner paid for all three entries, including the transfer
+      const ownerBefore = await balance(owner.publicKey);
+      let rent = 0;
+      for (const index of [0, 1, 2]) {
+        rent += await balance(findAmendmentAddress(amendedRecord, index));
+        await closeAmendment(index);
+        expect(await connection.getAccountInfo(findAmendmentAddress(amendedRecord, index))).to.be.null;
+      }
+      expect((await balance(owner.publicKey)) - ownerBefore).to.equal(rent);
+    });
+  });
+});
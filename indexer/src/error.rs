@@ -0,0 +1,21 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum IndexerError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("invalid ledger entry on line {line}: {source}")]
+    InvalidLedgerEntry {
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    #[error("ledger entry on line {0} has no transaction signature")]
+    MissingSignature(usize),
+
+    #[error("database error: {0}")]
+    Database(#[from] rusqlite::Error),
+}
+
+pub type Result<T> = std::result::Result<T, IndexerError>;
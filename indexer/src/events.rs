@@ -0,0 +1,1050 @@
+use borsh::BorshDeserialize;
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+use crate::programs::Program;
+
+// Mirrors of the `#[event]` structs declared in contracts/src/programs. Field
+// order must match the on-chain definitions exactly since events are Borsh
+// encoded without field names.
+
+#[derive(BorshDeserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pubkey(pub [u8; 32]);
+
+impl fmt::Display for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&bs58::encode(self.0).into_string())
+    }
+}
+
+impl fmt::Debug for Pubkey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum PropertyStatus {
+    Available,
+    Pending,
+    Sold,
+    Liquidating,
+    Liquidated,
+}
+
+impl PropertyStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PropertyStatus::Available => "available",
+            PropertyStatus::Pending => "pending",
+            PropertyStatus::Sold => "sold",
+            PropertyStatus::Liquidating => "liquidating",
+            PropertyStatus::Liquidated => "liquidated",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ProposalAction {
+    Signal,
+    UpdatePropertyStatus { status: PropertyStatus },
+    StartLiquidation { min_proceeds: u64 },
+}
+
+impl ProposalAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalAction::Signal => "signal",
+            ProposalAction::UpdatePropertyStatus { .. } => "update_property_status",
+            ProposalAction::StartLiquidation { .. } => "start_liquidation",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ProposalStatus {
+    Active,
+    Executed,
+    Defeated,
+    Cancelled,
+}
+
+impl ProposalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProposalStatus::Active => "active",
+            ProposalStatus::Executed => "executed",
+            ProposalStatus::Defeated => "defeated",
+            ProposalStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+impl OrderSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderSide::Bid => "bid",
+            OrderSide::Ask => "ask",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SwapSide {
+    Buy,
+    Sell,
+}
+
+impl SwapSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SwapSide::Buy => "buy",
+            SwapSide::Sell => "sell",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AuctionKind {
+    English {
+        reserve_price: u64,
+        min_increment: u64,
+        extension: i64,
+    },
+    Dutch {
+        end_price: u64,
+    },
+}
+
+impl AuctionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuctionKind::English { .. } => "english",
+            AuctionKind::Dutch { .. } => "dutch",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AuctionStatus {
+    Active,
+    Settled,
+    Unsold,
+    Cancelled,
+}
+
+impl AuctionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuctionStatus::Active => "active",
+            AuctionStatus::Settled => "settled",
+            AuctionStatus::Unsold => "unsold",
+            AuctionStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyDetails {
+    pub title: String,
+    pub description: String,
+    pub address: String,
+    pub city: String,
+    pub state: String,
+    pub country: String,
+    pub zip_code: String,
+    pub property_type: String,
+    pub square_feet: u32,
+    pub bedrooms: u8,
+    pub bathrooms: u8,
+    pub year_built: u16,
+    pub rent_yield: u16,
+    pub appreciation_potential: u16,
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub enum VerificationStatus {
+    Pending,
+    InProgress,
+    Verified,
+    Rejected,
+    Expired,
+}
+
+impl VerificationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VerificationStatus::Pending => "pending",
+            VerificationStatus::InProgress => "in_progress",
+            VerificationStatus::Verified => "verified",
+            VerificationStatus::Rejected => "rejected",
+            VerificationStatus::Expired => "expired",
+        }
+    }
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VerificationDetails {
+    pub verification_date: i64,
+    pub verification_method: String,
+    pub verification_notes: String,
+    pub is_legal_compliance_verified: bool,
+    pub is_property_condition_verified: bool,
+    pub is_valuation_verified: bool,
+    pub verification_expiry: i64,
+}
+
+// property_tokenization
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyInitialized {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub property_id: String,
+    pub property_uri: String,
+    pub total_tokens: u64,
+    pub token_price: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TokensPurchased {
+    pub property: Pubkey,
+    pub mint: Pubkey,
+    pub buyer: Pubkey,
+    pub amount: u64,
+    pub token_price: u64,
+    pub available_tokens: u64,
+    pub status: PropertyStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyDetailsUpdated {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub details: PropertyDetails,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyStatusUpdated {
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub previous_status: PropertyStatus,
+    pub status: PropertyStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OwnerTransferred {
+    pub property: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ProposalCreated {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub property: Pubkey,
+    pub proposer: Pubkey,
+    pub index: u64,
+    pub action: ProposalAction,
+    pub description_uri: String,
+    pub voting_ends_at: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ProposalFinalized {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub property: Pubkey,
+    pub status: ProposalStatus,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ProposalCancelled {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ProposalClosed {
+    pub proposal: Pubkey,
+    pub governance: Pubkey,
+    pub proposer: Pubkey,
+    pub status: ProposalStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidationFunded {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub funder: Pubkey,
+    pub proceeds: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidationCancelled {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub funder: Pubkey,
+    pub proceeds: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidationStarted {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub proceeds: u64,
+    pub supply: u64,
+    pub redeem_until: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidationRedeemed {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub holder: Pubkey,
+    pub amount: u64,
+    pub payout: u64,
+    pub redeemed_tokens: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidationClosed {
+    pub liquidation: Pubkey,
+    pub property: Pubkey,
+    pub owner: Pubkey,
+    pub unredeemed_proceeds: u64,
+    pub timestamp: i64,
+}
+
+// marketplace
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MarketplaceInitialized {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListingCreated {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub index: u64,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub price_per_token: u64,
+    pub token_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TradeExecuted {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_amount: u64,
+    pub price_per_token: u64,
+    pub total_price: u64,
+    pub fee_amount: u64,
+    pub seller_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListingCancelled {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListingsSwept {
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub listings_filled: u32,
+    pub token_amount: u64,
+    pub total_price: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListingUpdated {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub previous_price_per_token: u64,
+    pub price_per_token: u64,
+    pub token_amount: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct ListingClosed {
+    pub listing: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub returned_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MarketplaceFeeUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub previous_fee: u16,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct QuoteMintsUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub allowed_quote_mints: Vec<Pubkey>,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VerificationRequirementUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub require_verified: bool,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MarketplacePauseUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub paused: bool,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuthorityTransferProposed {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuthorityTransferred {
+    pub marketplace: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TreasuryUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub previous_treasury: Pubkey,
+    pub treasury: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MintDelistingUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub delisted: bool,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FeeTier {
+    pub quote_mint: Pubkey,
+    pub min_volume: u64,
+    pub fee: u16,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct FeeScheduleUpdated {
+    pub marketplace: Pubkey,
+    pub authority: Pubkey,
+    pub referrer_fee_share: u16,
+    pub fee_tiers: Vec<FeeTier>,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MintFeeConfigUpdated {
+    pub mint: Pubkey,
+    pub fee: u16,
+    pub royalty_recipient: Pubkey,
+    pub royalty_share: u16,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct MintFeeConfigRemoved {
+    pub mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct TradeFeeDistributed {
+    pub listing: Pubkey,
+    pub quote_mint: Pubkey,
+    pub fee: u16,
+    pub fee_amount: u64,
+    pub treasury_amount: u64,
+    pub royalty_recipient: Pubkey,
+    pub royalty_amount: u64,
+    pub referrer: Pubkey,
+    pub referrer_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OrderBookCreated {
+    pub order_book: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OrderPlaced {
+    pub order_book: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub price: u64,
+    pub quantity: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OrderCancelled {
+    pub order_book: Pubkey,
+    pub order_id: u64,
+    pub owner: Pubkey,
+    pub side: OrderSide,
+    pub remaining_quantity: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OrdersMatched {
+    pub order_book: Pubkey,
+    pub bid_id: u64,
+    pub ask_id: u64,
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub price: u64,
+    pub quantity: u64,
+    pub fee_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct FundsSettled {
+    pub order_book: Pubkey,
+    pub owner: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionCreated {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub index: u64,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub kind: AuctionKind,
+    pub token_amount: u64,
+    pub start_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionBidPlaced {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub end_time: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionBidRefunded {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionSettled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub token_amount: u64,
+    pub price: u64,
+    pub fee_amount: u64,
+    pub seller_amount: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionCancelled {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub token_amount: u64,
+    pub status: AuctionStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AuctionClosed {
+    pub auction: Pubkey,
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub status: AuctionStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OfferMade {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub price_per_token: u64,
+    pub token_amount: u64,
+    pub total_price: u64,
+    pub expires_at: i64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OfferAccepted {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct OfferWithdrawn {
+    pub offer: Pubkey,
+    pub listing: Pubkey,
+    pub buyer: Pubkey,
+    pub refund: u64,
+    pub timestamp: i64,
+}
+
+// registry
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RegistryInitialized {
+    pub registry: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VerifierAdded {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub url: String,
+    pub verifier_count: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VerifierUpdated {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub name: String,
+    pub url: String,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VerifierRemoved {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub verifier_count: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct VerifierClosed {
+    pub verifier: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyRegistered {
+    pub property_record: Pubkey,
+    pub property_id: String,
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub address: String,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyVerified {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub verifier: Pubkey,
+    pub verifier_authority: Pubkey,
+    pub verification_details: VerificationDetails,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RecordStatusUpdated {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub authority: Pubkey,
+    pub previous_status: VerificationStatus,
+    pub status: VerificationStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyRecordAmended {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub amendment: Pubkey,
+    pub index: u64,
+    pub address: String,
+    pub previous_status: VerificationStatus,
+    pub status: VerificationStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct RecordOwnershipTransferred {
+    pub property_record: Pubkey,
+    pub token_mint: Pubkey,
+    pub previous_owner: Pubkey,
+    pub owner: Pubkey,
+    pub verifier: Pubkey,
+    pub amendment: Pubkey,
+    pub index: u64,
+    pub previous_status: VerificationStatus,
+    pub status: VerificationStatus,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PropertyRecordClosed {
+    pub property_record: Pubkey,
+    pub property_id: String,
+    pub token_mint: Pubkey,
+    pub owner: Pubkey,
+    pub property_count: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct AmendmentClosed {
+    pub amendment: Pubkey,
+    pub property_record: Pubkey,
+    pub index: u64,
+    pub amended_by: Pubkey,
+    pub timestamp: i64,
+}
+
+// amm
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PoolInitialized {
+    pub pool: Pubkey,
+    pub authority: Pubkey,
+    pub property_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidityAdded {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub property_amount: u64,
+    pub quote_amount: u64,
+    pub lp_amount: u64,
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub lp_supply: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct LiquidityRemoved {
+    pub pool: Pubkey,
+    pub provider: Pubkey,
+    pub property_amount: u64,
+    pub quote_amount: u64,
+    pub lp_amount: u64,
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub lp_supply: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct Swapped {
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub side: SwapSide,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee_amount: u64,
+    pub property_reserve: u64,
+    pub quote_reserve: u64,
+    pub timestamp: i64,
+}
+
+#[derive(BorshDeserialize, Clone, Debug, PartialEq)]
+pub struct PoolFeeUpdated {
+    pub pool: Pubkey,
+    pub fee: u16,
+    pub timestamp: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgramEvent {
+    PropertyInitialized(PropertyInitialized),
+    TokensPurchased(TokensPurchased),
+    PropertyDetailsUpdated(PropertyDetailsUpdated),
+    PropertyStatusUpdated(PropertyStatusUpdated),
+    OwnerTransferred(OwnerTransferred),
+    ProposalCreated(ProposalCreated),
+    VoteCast(VoteCast),
+    ProposalFinalized(ProposalFinalized),
+    ProposalCancelled(ProposalCancelled),
+    ProposalClosed(ProposalClosed),
+    LiquidationFunded(LiquidationFunded),
+    LiquidationCancelled(LiquidationCancelled),
+    LiquidationStarted(LiquidationStarted),
+    LiquidationRedeemed(LiquidationRedeemed),
+    LiquidationClosed(LiquidationClosed),
+    MarketplaceInitialized(MarketplaceInitialized),
+    ListingCreated(ListingCreated),
+    TradeExecuted(TradeExecuted),
+    ListingsSwept(ListingsSwept),
+    ListingCancelled(ListingCancelled),
+    ListingUpdated(ListingUpdated),
+    ListingClosed(ListingClosed),
+    MarketplaceFeeUpdated(MarketplaceFeeUpdated),
+    QuoteMintsUpdated(QuoteMintsUpdated),
+    VerificationRequirementUpdated(VerificationRequirementUpdated),
+    MarketplacePauseUpdated(MarketplacePauseUpdated),
+    AuthorityTransferProposed(AuthorityTransferProposed),
+    AuthorityTransferred(AuthorityTransferred),
+    TreasuryUpdated(TreasuryUpdated),
+    MintDelistingUpdated(MintDelistingUpdated),
+    FeeScheduleUpdated(FeeScheduleUpdated),
+    MintFeeConfigUpdated(MintFeeConfigUpdated),
+    MintFeeConfigRemoved(MintFeeConfigRemoved),
+    TradeFeeDistributed(TradeFeeDistributed),
+    OrderBookCreated(OrderBookCreated),
+    OrderPlaced(OrderPlaced),
+    OrderCancelled(OrderCancelled),
+    OrdersMatched(OrdersMatched),
+    FundsSettled(FundsSettled),
+    AuctionCreated(AuctionCreated),
+    AuctionBidPlaced(AuctionBidPlaced),
+    AuctionBidRefunded(AuctionBidRefunded),
+    AuctionSettled(AuctionSettled),
+    AuctionCancelled(AuctionCancelled),
+    AuctionClosed(AuctionClosed),
+    OfferMade(OfferMade),
+    OfferAccepted(OfferAccepted),
+    OfferWithdrawn(OfferWithdrawn),
+    RegistryInitialized(RegistryInitialized),
+    VerifierAdded(VerifierAdded),
+    VerifierUpdated(VerifierUpdated),
+    VerifierRemoved(VerifierRemoved),
+    VerifierClosed(VerifierClosed),
+    PropertyRegistered(PropertyRegistered),
+    PropertyVerified(PropertyVerified),
+    // Registry's `PropertyStatusUpdated`; renamed here to keep it apart from
+    // the property_tokenization event of the same name.
+    RecordStatusUpdated(RecordStatusUpdated),
+    PropertyRecordAmended(PropertyRecordAmended),
+    RecordOwnershipTransferred(RecordOwnershipTransferred),
+    PropertyRecordClosed(PropertyRecordClosed),
+    AmendmentClosed(AmendmentClosed),
+    PoolInitialized(PoolInitialized),
+    LiquidityAdded(LiquidityAdded),
+    LiquidityRemoved(LiquidityRemoved),
+    Swapped(Swapped),
+    PoolFeeUpdated(PoolFeeUpdated),
+}
+
+/// Anchor event discriminator: the first 8 bytes of `sha256("event:<Name>")`.
+pub fn discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("event:{}", name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+fn decode_as<T: BorshDeserialize>(data: &[u8]) -> Option<T> {
+    T::try_from_slice(data).ok()
+}
+
+// Tries each `"EventName" => Variant` pair in turn against the payload's discriminator.
+macro_rules! decode_events {
+    ($tag:expr, $body:expr, { $($name:literal => $variant:ident),* $(,)? }) => {{
+        $(
+            if $tag == discriminator($name) {
+                return decode_as($body).map(ProgramEvent::$variant);
+            }
+        )*
+        None
+    }};
+}
+
+/// Decodes a `Program data:` payload emitted by `program`. Payloads that are not
+/// one of the known events (or fail to deserialize) yield `None`.
+pub fn decode_event(program: Program, data: &[u8]) -> Option<ProgramEvent> {
+    if data.len() < 8 {
+        return None;
+    }
+    let (tag, body) = data.split_at(8);
+
+    // Event names are only unique within a program, so the lookup is per program.
+    match program {
+        Program::PropertyTokenization => decode_events!(tag, body, {
+            "PropertyInitialized" => PropertyInitialized,
+            "TokensPurchased" => TokensPurchased,
+            "PropertyDetailsUpdated" => PropertyDetailsUpdated,
+            "PropertyStatusUpdated" => PropertyStatusUpdated,
+            "OwnerTransferred" => OwnerTransferred,
+            "ProposalCreated" => ProposalCreated,
+            "VoteCast" => VoteCast,
+            "ProposalFinalized" => ProposalFinalized,
+            "ProposalCancelled" => ProposalCancelled,
+            "ProposalClosed" => ProposalClosed,
+            "LiquidationFunded" => LiquidationFunded,
+            "LiquidationCancelled" => LiquidationCancelled,
+            "LiquidationStarted" => LiquidationStarted,
+            "LiquidationRedeemed" => LiquidationRedeemed,
+            "LiquidationClosed" => LiquidationClosed,
+        }),
+        Program::Marketplace => decode_events!(tag, body, {
+            "MarketplaceInitialized" => MarketplaceInitialized,
+            "ListingCreated" => ListingCreated,
+            "TradeExecuted" => TradeExecuted,
+            "ListingsSwept" => ListingsSwept,
+            "ListingCancelled" => ListingCancelled,
+            "ListingUpdated" => ListingUpdated,
+            "ListingClosed" => ListingClosed,
+            "MarketplaceFeeUpdated" => MarketplaceFeeUpdated,
+            "QuoteMintsUpdated" => QuoteMintsUpdated,
+            "VerificationRequirementUpdated" => VerificationRequirementUpdated,
+            "MarketplacePauseUpdated" => MarketplacePauseUpdated,
+            "AuthorityTransferProposed" => AuthorityTransferProposed,
+            "AuthorityTransferred" => AuthorityTransferred,
+            "TreasuryUpdated" => TreasuryUpdated,
+            "MintDelistingUpdated" => MintDelistingUpdated,
+            "FeeScheduleUpdated" => FeeScheduleUpdated,
+            "MintFeeConfigUpdated" => MintFeeConfigUpdated,
+            "MintFeeConfigRemoved" => MintFeeConfigRemoved,
+            "TradeFeeDistributed" => TradeFeeDistributed,
+            "OrderBookCreated" => OrderBookCreated,
+            "OrderPlaced" => OrderPlaced,
+            "OrderCancelled" => OrderCancelled,
+            "OrdersMatched" => OrdersMatched,
+            "FundsSettled" => FundsSettled,
+            "AuctionCreated" => AuctionCreated,
+            "AuctionBidPlaced" => AuctionBidPlaced,
+            "AuctionBidRefunded" => AuctionBidRefunded,
+            "AuctionSettled" => AuctionSettled,
+            "AuctionCancelled" => AuctionCancelled,
+            "AuctionClosed" => AuctionClosed,
+            "OfferMade" => OfferMade,
+            "OfferAccepted" => OfferAccepted,
+            "OfferWithdrawn" => OfferWithdrawn,
+        }),
+        Program::Registry => decode_events!(tag, body, {
+            "RegistryInitialized" => RegistryInitialized,
+            "VerifierAdded" => VerifierAdded,
+            "VerifierUpdated" => VerifierUpdated,
+            "VerifierRemoved" => VerifierRemoved,
+            "VerifierClosed" => VerifierClosed,
+            "PropertyRegistered" => PropertyRegistered,
+            "PropertyVerified" => PropertyVerified,
+            "PropertyStatusUpdated" => RecordStatusUpdated,
+            "PropertyRecordAmended" => PropertyRecordAmended,
+            "RecordOwnershipTransferred" => RecordOwnershipTransferred,
+            "PropertyRecordClosed" => PropertyRecordClosed,
+            "AmendmentClosed" => AmendmentClosed,
+        }),
+        Program::Amm => decode_events!(tag, body, {
+            "PoolInitialized" => PoolInitialized,
+            "LiquidityAdded" => LiquidityAdded,
+            "LiquidityRemoved" => LiquidityRemoved,
+            "Swapped" => Swapped,
+            "PoolFeeUpdated" => PoolFeeUpdated,
+        }),
+        Program::NativePropertyTokenization => None,
+    }
+}
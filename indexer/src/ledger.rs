@@ -0,0 +1,105 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::io::BufRead;
+
+use crate::error::{IndexerError, Result};
+
+/// A confirmed transaction as far as the indexer is concerned: where it landed
+/// and the log lines the runtime recorded for it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedTransaction {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+// Accepts both the flat fixture format
+// (`{"signature", "slot", "blockTime", "logs"}`) and the shape returned by
+// the `getTransaction` RPC method (`transaction.signatures` + `meta.logMessages`).
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawTransaction {
+    signature: Option<String>,
+    #[serde(default)]
+    slot: u64,
+    #[serde(alias = "block_time")]
+    block_time: Option<i64>,
+    #[serde(default)]
+    err: Value,
+    logs: Option<Vec<String>>,
+    transaction: Option<RawInnerTransaction>,
+    meta: Option<RawMeta>,
+}
+
+#[derive(Deserialize)]
+struct RawInnerTransaction {
+    #[serde(default)]
+    signatures: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMeta {
+    #[serde(default)]
+    err: Value,
+    #[serde(default)]
+    log_messages: Vec<String>,
+}
+
+impl RawTransaction {
+    fn normalize(self, line: usize) -> Result<RecordedTransaction> {
+        let signature = self
+            .signature
+            .or_else(|| {
+                self.transaction
+                    .and_then(|transaction| transaction.signatures.into_iter().next())
+            })
+            .ok_or(IndexerError::MissingSignature(line))?;
+
+        let (meta_err, meta_logs) = match self.meta {
+            Some(meta) => (meta.err, Some(meta.log_messages)),
+            None => (Value::Null, None),
+        };
+
+        Ok(RecordedTransaction {
+            signature,
+            slot: self.slot,
+            block_time: self.block_time,
+            failed: !self.err.is_null() || !meta_err.is_null(),
+            logs: self.logs.or(meta_logs).unwrap_or_default(),
+        })
+    }
+}
+
+/// Reads a recorded ledger: either a JSON array of transactions or one JSON
+/// transaction per line.
+pub fn read_ledger<R: BufRead>(mut reader: R) -> Result<Vec<RecordedTransaction>> {
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents)?;
+
+    if contents.trim_start().starts_with('[') {
+        let raw: Vec<RawTransaction> = serde_json::from_str(&contents)
+            .map_err(|source| IndexerError::InvalidLedgerEntry { line: 1, source })?;
+        return raw
+            .into_iter()
+            .enumerate()
+            .map(|(index, transaction)| transaction.normalize(index + 1))
+            .collect();
+    }
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let raw: RawTransaction =
+                serde_json::from_str(line).map_err(|source| IndexerError::InvalidLedgerEntry {
+                    line: index + 1,
+                    source,
+                })?;
+            raw.normalize(index + 1)
+        })
+        .collect()
+}
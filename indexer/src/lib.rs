@@ -0,0 +1,100 @@
+// Errors surfaced by the indexer
+pub mod error;
+
+// Anchor event mirrors and decoding
+pub mod events;
+
+// Recorded ledger loading
+pub mod ledger;
+
+// Program log parsing
+pub mod logs;
+
+// Native program `msg!` parsing
+pub mod native;
+
+// Program derived address helpers
+pub mod pda;
+
+// Program id configuration
+pub mod programs;
+
+// SQLite persistence
+pub mod store;
+
+use crate::error::Result;
+use crate::events::decode_event;
+use crate::ledger::RecordedTransaction;
+use crate::logs::{parse_logs, ProgramOutput};
+use crate::native::parse_message;
+use crate::programs::{Program, ProgramIds};
+use crate::store::{IndexedRecord, Store};
+
+/// Decodes everything the YieldHabitat programs reported in a transaction's logs.
+pub fn decode_transaction(
+    program_ids: &ProgramIds,
+    transaction: &RecordedTransaction,
+) -> Vec<IndexedRecord> {
+    // Logs of failed transactions describe state changes that were rolled back.
+    if transaction.failed {
+        return Vec::new();
+    }
+
+    parse_logs(&transaction.logs)
+        .into_iter()
+        .filter_map(|entry| {
+            let program = program_ids.classify(&entry.program_id)?;
+            match (program, entry.output) {
+                (Program::NativePropertyTokenization, ProgramOutput::Message(message)) => {
+                    parse_message(&message).map(IndexedRecord::Native)
+                }
+                (Program::NativePropertyTokenization, ProgramOutput::Data(_)) => None,
+                (program, ProgramOutput::Data(data)) => {
+                    decode_event(program, &data).map(|event| IndexedRecord::Event(Box::new(event)))
+                }
+                (_, ProgramOutput::Message(_)) => None,
+            }
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IndexSummary {
+    pub indexed: usize,
+    pub already_indexed: usize,
+    pub records: usize,
+}
+
+pub struct Indexer {
+    store: Store,
+    program_ids: ProgramIds,
+}
+
+impl Indexer {
+    pub fn new(store: Store, program_ids: ProgramIds) -> Self {
+        Indexer { store, program_ids }
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    pub fn index<'a, I>(&mut self, transactions: I) -> Result<IndexSummary>
+    where
+        I: IntoIterator<Item = &'a RecordedTransaction>,
+    {
+        let mut summary = IndexSummary::default();
+
+        for transaction in transactions {
+            let records = decode_transaction(&self.program_ids, transaction);
+            if self.store.record_transaction(transaction, &records)? {
+                summary.indexed += 1;
+                summary.records += records.len();
+            } else {
+                summary.already_indexed += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+}
@@ -0,0 +1,65 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// A single piece of program output found in a transaction's log messages,
+/// attributed to the program that was executing when it was written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogEntry {
+    pub program_id: String,
+    pub output: ProgramOutput,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProgramOutput {
+    // `sol_log_data` payloads, which is how Anchor's `emit!` writes events
+    Data(Vec<u8>),
+    // Plain `msg!` output
+    Message(String),
+}
+
+/// Walks the runtime's invoke/success/failed markers to attribute every
+/// `Program data:` and `Program log:` line to the program that wrote it,
+/// including programs reached through CPI.
+pub fn parse_logs(logs: &[String]) -> Vec<LogEntry> {
+    let mut stack: Vec<&str> = Vec::new();
+    let mut entries = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            let Some(program_id) = stack.last() else {
+                continue;
+            };
+            for chunk in data.split_whitespace() {
+                if let Ok(bytes) = STANDARD.decode(chunk) {
+                    entries.push(LogEntry {
+                        program_id: program_id.to_string(),
+                        output: ProgramOutput::Data(bytes),
+                    });
+                }
+            }
+        } else if let Some(message) = line.strip_prefix("Program log: ") {
+            let Some(program_id) = stack.last() else {
+                continue;
+            };
+            entries.push(LogEntry {
+                program_id: program_id.to_string(),
+                output: ProgramOutput::Message(message.to_string()),
+            });
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut parts = rest.split_whitespace();
+            let (Some(program_id), Some(action)) = (parts.next(), parts.next()) else {
+                continue;
+            };
+
+            match action {
+                "invoke" => stack.push(program_id),
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    entries
+}
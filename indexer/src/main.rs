@@ -0,0 +1,92 @@
+use clap::Parser;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use yieldhabitat_indexer::error::Result;
+use yieldhabitat_indexer::ledger::read_ledger;
+use yieldhabitat_indexer::programs::ProgramIds;
+use yieldhabitat_indexer::store::Store;
+use yieldhabitat_indexer::Indexer;
+
+/// Builds a queryable SQLite history of the YieldHabitat Solana programs from
+/// recorded transaction logs.
+#[derive(Parser)]
+#[command(version)]
+struct Args {
+    /// Recorded ledger (JSON array or one transaction per line); `-` reads stdin
+    #[arg(long, short)]
+    ledger: PathBuf,
+
+    /// SQLite database to create or append to
+    #[arg(long, short, default_value = "yieldhabitat-index.db")]
+    database: PathBuf,
+
+    #[arg(long)]
+    property_program: Option<String>,
+
+    #[arg(long)]
+    marketplace_program: Option<String>,
+
+    #[arg(long)]
+    registry_program: Option<String>,
+
+    #[arg(long)]
+    amm_program: Option<String>,
+
+    /// Address of the native property tokenization program, if deployed
+    #[arg(long)]
+    native_program: Option<String>,
+}
+
+const TABLES: &[&str] = &[
+    "properties",
+    "purchases",
+    "listings",
+    "trades",
+    "yields",
+    "verifications",
+];
+
+fn run(args: Args) -> Result<()> {
+    let defaults = ProgramIds::default();
+    let program_ids = ProgramIds {
+        property_tokenization: args
+            .property_program
+            .unwrap_or(defaults.property_tokenization),
+        marketplace: args.marketplace_program.unwrap_or(defaults.marketplace),
+        registry: args.registry_program.unwrap_or(defaults.registry),
+        amm: args.amm_program.unwrap_or(defaults.amm),
+        native_property_tokenization: args.native_program,
+    };
+
+    let transactions = if args.ledger.as_os_str() == "-" {
+        read_ledger(io::stdin().lock())?
+    } else {
+        read_ledger(BufReader::new(File::open(&args.ledger)?))?
+    };
+
+    let mut indexer = Indexer::new(Store::open(&args.database)?, program_ids);
+    let summary = indexer.index(&transactions)?;
+
+    println!(
+        "Indexed {} transactions ({} records), skipped {} already indexed",
+        summary.indexed, summary.records, summary.already_indexed
+    );
+    for table in TABLES {
+        println!("  {:<14}{}", table, indexer.store().count(table)?);
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    match run(Args::parse()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,36 @@
+/// Records recovered from the `msg!` output of the native property
+/// tokenization program (contracts/programs/property-tokenization/src/processor.rs).
+#[derive(Clone, Debug, PartialEq)]
+pub enum NativeRecord {
+    PropertyCreated { name: String },
+    TokensPurchased { amount: u64 },
+    YieldDistributed { amount: u64 },
+    PropertyValueUpdated { new_value: u64 },
+}
+
+fn parse_amount(rest: &str, suffix: &str) -> Option<u64> {
+    rest.strip_suffix(suffix)?.trim().parse().ok()
+}
+
+/// Parses a single `Program log:` message. Anything that is not one of the
+/// processor's result lines (e.g. the `Instruction: ...` banners) yields `None`.
+pub fn parse_message(message: &str) -> Option<NativeRecord> {
+    if let Some(name) = message.strip_prefix("Creating new property: ") {
+        return Some(NativeRecord::PropertyCreated {
+            name: name.to_string(),
+        });
+    }
+    if let Some(rest) = message.strip_prefix("Processing purchase of ") {
+        return parse_amount(rest, " tokens")
+            .map(|amount| NativeRecord::TokensPurchased { amount });
+    }
+    if let Some(rest) = message.strip_prefix("Distributing yield of ") {
+        return parse_amount(rest, " tokens")
+            .map(|amount| NativeRecord::YieldDistributed { amount });
+    }
+    if let Some(rest) = message.strip_prefix("Updating property value to ") {
+        return parse_amount(rest, "")
+            .map(|new_value| NativeRecord::PropertyValueUpdated { new_value });
+    }
+    None
+}
@@ -0,0 +1,38 @@
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use sha2::{Digest, Sha256};
+
+use crate::events::Pubkey;
+
+const PDA_MARKER: &[u8] = b"ProgramDerivedAddress";
+
+/// Same derivation as `Pubkey::find_program_address`: the first bump, counting
+/// down from 255, whose hash is not a valid ed25519 point.
+pub fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Option<(Pubkey, u8)> {
+    (0..=u8::MAX).rev().find_map(|bump| {
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update([bump]);
+        hasher.update(program_id.0);
+        hasher.update(PDA_MARKER);
+
+        let hash: [u8; 32] = hasher.finalize().into();
+        let on_curve = CompressedEdwardsY(hash).decompress().is_some();
+        (!on_curve).then_some((Pubkey(hash), bump))
+    })
+}
+
+/// Address of a seller's `index`-th marketplace listing (see `Listing::address`
+/// in contracts/src/programs/marketplace/lib.rs).
+pub fn listing_address(
+    marketplace_program: &Pubkey,
+    seller: &Pubkey,
+    index: u64,
+) -> Option<Pubkey> {
+    find_program_address(
+        &[b"listing", &seller.0, &index.to_le_bytes()],
+        marketplace_program,
+    )
+    .map(|(address, _)| address)
+}
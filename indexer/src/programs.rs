@@ -0,0 +1,63 @@
+/// The programs whose output the indexer understands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Program {
+    PropertyTokenization,
+    Marketplace,
+    Registry,
+    Amm,
+    // The native (non-Anchor) build in contracts/programs/property-tokenization,
+    // which only reports through `msg!`.
+    NativePropertyTokenization,
+}
+
+impl Program {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Program::PropertyTokenization => "property_tokenization",
+            Program::Marketplace => "marketplace",
+            Program::Registry => "registry",
+            Program::Amm => "amm",
+            Program::NativePropertyTokenization => "native_property_tokenization",
+        }
+    }
+}
+
+/// Deployed program addresses, defaulting to the ids in contracts/Anchor.toml.
+#[derive(Clone, Debug)]
+pub struct ProgramIds {
+    pub property_tokenization: String,
+    pub marketplace: String,
+    pub registry: String,
+    pub amm: String,
+    pub native_property_tokenization: Option<String>,
+}
+
+impl Default for ProgramIds {
+    fn default() -> Self {
+        ProgramIds {
+            property_tokenization: "PTok1111111111111111111111111111111111111".to_string(),
+            marketplace: "Mrkt1111111111111111111111111111111111111".to_string(),
+            registry: "Rgst1111111111111111111111111111111111111".to_string(),
+            amm: "Amm11111111111111111111111111111111111111".to_string(),
+            native_property_tokenization: None,
+        }
+    }
+}
+
+impl ProgramIds {
+    pub fn classify(&self, program_id: &str) -> Option<Program> {
+        if program_id == self.property_tokenization {
+            Some(Program::PropertyTokenization)
+        } else if program_id == self.marketplace {
+            Some(Program::Marketplace)
+        } else if program_id == self.registry {
+            Some(Program::Registry)
+        } else if program_id == self.amm {
+            Some(Program::Amm)
+        } else if self.native_property_tokenization.as_deref() == Some(program_id) {
+            Some(Program::NativePropertyTokenization)
+        } else {
+            None
+        }
+    }
+}
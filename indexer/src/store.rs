@@ -0,0 +1,1458 @@
+use rusqlite::{params, Connection, Transaction};
+use std::path::Path;
+
+use crate::error::Result;
+use crate::events::{AuctionKind, ProgramEvent, ProposalAction, Pubkey};
+use crate::ledger::RecordedTransaction;
+use crate::native::NativeRecord;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS transactions (
+    signature TEXT PRIMARY KEY,
+    slot INTEGER NOT NULL,
+    block_time INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS properties (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    property TEXT UNIQUE,
+    source TEXT NOT NULL,
+    property_id TEXT,
+    name TEXT,
+    owner TEXT,
+    mint TEXT,
+    property_uri TEXT,
+    total_tokens INTEGER,
+    available_tokens INTEGER,
+    token_price INTEGER,
+    status TEXT,
+    created_at INTEGER,
+    updated_at INTEGER,
+    signature TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS property_valuations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    property TEXT,
+    value INTEGER NOT NULL,
+    timestamp INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS purchases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    property TEXT,
+    mint TEXT,
+    buyer TEXT,
+    amount INTEGER NOT NULL,
+    token_price INTEGER,
+    timestamp INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS yields (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    source TEXT NOT NULL,
+    property TEXT,
+    amount INTEGER NOT NULL,
+    timestamp INTEGER
+);
+
+CREATE TABLE IF NOT EXISTS proposals (
+    proposal TEXT PRIMARY KEY,
+    governance TEXT NOT NULL,
+    property TEXT NOT NULL,
+    proposer TEXT NOT NULL,
+    proposal_index INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    action_status TEXT,
+    description_uri TEXT NOT NULL,
+    votes_for INTEGER NOT NULL,
+    votes_against INTEGER NOT NULL,
+    voting_ends_at INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    closed_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS votes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    proposal TEXT NOT NULL,
+    voter TEXT NOT NULL,
+    support INTEGER NOT NULL,
+    weight INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS liquidations (
+    liquidation TEXT PRIMARY KEY,
+    property TEXT NOT NULL,
+    funder TEXT NOT NULL,
+    proceeds INTEGER NOT NULL,
+    supply INTEGER,
+    redeem_until INTEGER,
+    redeemed_tokens INTEGER NOT NULL,
+    paid_out INTEGER NOT NULL,
+    unredeemed_proceeds INTEGER,
+    status TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS redemptions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    liquidation TEXT NOT NULL,
+    property TEXT NOT NULL,
+    holder TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    payout INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS marketplaces (
+    marketplace TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    treasury TEXT NOT NULL,
+    fee INTEGER NOT NULL,
+    referrer_fee_share INTEGER NOT NULL DEFAULT 0,
+    pending_authority TEXT,
+    paused INTEGER NOT NULL DEFAULT 0,
+    require_verified INTEGER NOT NULL DEFAULT 0,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS delisted_mints (
+    mint TEXT PRIMARY KEY,
+    marketplace TEXT NOT NULL,
+    delisted_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS mint_fee_configs (
+    mint TEXT PRIMARY KEY,
+    fee INTEGER NOT NULL,
+    royalty_recipient TEXT NOT NULL,
+    royalty_share INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS listings (
+    listing TEXT PRIMARY KEY,
+    seller TEXT NOT NULL,
+    listing_index INTEGER NOT NULL,
+    mint TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    price_per_token INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    remaining_amount INTEGER NOT NULL,
+    expires_at INTEGER,
+    status TEXT NOT NULL,
+    closed_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS listing_prices (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    price_per_token INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS trades (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    buyer TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    token_amount INTEGER NOT NULL,
+    price_per_token INTEGER NOT NULL,
+    total_price INTEGER NOT NULL,
+    fee_amount INTEGER NOT NULL,
+    seller_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS sweeps (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    buyer TEXT NOT NULL,
+    mint TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    listings_filled INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    total_price INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS trade_fees (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    listing TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    fee INTEGER NOT NULL,
+    fee_amount INTEGER NOT NULL,
+    treasury_amount INTEGER NOT NULL,
+    royalty_recipient TEXT,
+    royalty_amount INTEGER NOT NULL,
+    referrer TEXT,
+    referrer_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS offers (
+    offer TEXT PRIMARY KEY,
+    listing TEXT NOT NULL,
+    buyer TEXT NOT NULL,
+    price_per_token INTEGER NOT NULL,
+    token_amount INTEGER NOT NULL,
+    total_price INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS order_books (
+    order_book TEXT PRIMARY KEY,
+    mint TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS orders (
+    order_book TEXT NOT NULL,
+    order_id INTEGER NOT NULL,
+    owner TEXT NOT NULL,
+    side TEXT NOT NULL,
+    price INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    remaining_quantity INTEGER NOT NULL,
+    status TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (order_book, order_id)
+);
+
+CREATE TABLE IF NOT EXISTS order_fills (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    order_book TEXT NOT NULL,
+    bid_id INTEGER NOT NULL,
+    ask_id INTEGER NOT NULL,
+    buyer TEXT NOT NULL,
+    seller TEXT NOT NULL,
+    price INTEGER NOT NULL,
+    quantity INTEGER NOT NULL,
+    fee_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS auctions (
+    auction TEXT PRIMARY KEY,
+    seller TEXT NOT NULL,
+    auction_index INTEGER NOT NULL,
+    mint TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    token_amount INTEGER NOT NULL,
+    start_price INTEGER NOT NULL,
+    reserve_price INTEGER,
+    end_price INTEGER,
+    start_time INTEGER NOT NULL,
+    end_time INTEGER NOT NULL,
+    highest_bidder TEXT,
+    highest_bid INTEGER,
+    fee_amount INTEGER,
+    status TEXT NOT NULL,
+    closed_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS auction_bids (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    auction TEXT NOT NULL,
+    bidder TEXT NOT NULL,
+    amount INTEGER NOT NULL,
+    refunded INTEGER NOT NULL DEFAULT 0,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS verifiers (
+    verifier TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    name TEXT,
+    url TEXT,
+    is_active INTEGER NOT NULL,
+    closed_at INTEGER,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS property_records (
+    property_record TEXT PRIMARY KEY,
+    property_id TEXT NOT NULL,
+    token_mint TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    address TEXT NOT NULL,
+    verification_status TEXT NOT NULL,
+    closed_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS record_amendments (
+    amendment TEXT PRIMARY KEY,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    property_record TEXT NOT NULL,
+    amendment_index INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    owner TEXT NOT NULL,
+    previous_owner TEXT,
+    verifier TEXT,
+    status TEXT,
+    closed_at INTEGER,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS verifications (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    property_record TEXT NOT NULL,
+    token_mint TEXT NOT NULL,
+    verifier TEXT,
+    authority TEXT NOT NULL,
+    status TEXT NOT NULL,
+    verification_method TEXT,
+    verification_expiry INTEGER,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pools (
+    pool TEXT PRIMARY KEY,
+    authority TEXT NOT NULL,
+    property_mint TEXT NOT NULL,
+    quote_mint TEXT NOT NULL,
+    lp_mint TEXT NOT NULL,
+    fee INTEGER NOT NULL,
+    property_reserve INTEGER NOT NULL DEFAULT 0,
+    quote_reserve INTEGER NOT NULL DEFAULT 0,
+    lp_supply INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pool_liquidity (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    pool TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    action TEXT NOT NULL,
+    property_amount INTEGER NOT NULL,
+    quote_amount INTEGER NOT NULL,
+    lp_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS pool_swaps (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    signature TEXT NOT NULL,
+    slot INTEGER NOT NULL,
+    pool TEXT NOT NULL,
+    trader TEXT NOT NULL,
+    side TEXT NOT NULL,
+    amount_in INTEGER NOT NULL,
+    amount_out INTEGER NOT NULL,
+    fee_amount INTEGER NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS purchases_by_mint ON purchases (mint);
+CREATE INDEX IF NOT EXISTS trades_by_mint ON trades (mint);
+CREATE INDEX IF NOT EXISTS verifications_by_mint ON verifications (token_mint);
+";
+
+/// Output decoded from one transaction, in log order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum IndexedRecord {
+    Event(Box<ProgramEvent>),
+    Native(NativeRecord),
+}
+
+/// SQLite-backed store of normalized program history.
+pub struct Store {
+    conn: Connection,
+}
+
+impl Store {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Store { conn })
+    }
+
+    /// Read access for queries against the indexed tables.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Writes every record of `transaction` atomically. Returns `false` without
+    /// touching anything if the signature was already indexed, so ledgers can be
+    /// replayed safely.
+    pub fn record_transaction(
+        &mut self,
+        transaction: &RecordedTransaction,
+        records: &[IndexedRecord],
+    ) -> Result<bool> {
+        let tx = self.conn.transaction()?;
+
+        let inserted = tx.execute(
+            "INSERT OR IGNORE INTO transactions (signature, slot, block_time) VALUES (?1, ?2, ?3)",
+            params![
+                transaction.signature,
+                transaction.slot,
+                transaction.block_time
+            ],
+        )?;
+        if inserted == 0 {
+            return Ok(false);
+        }
+
+        for record in records {
+            match record {
+                IndexedRecord::Event(event) => apply_event(&tx, transaction, event)?,
+                IndexedRecord::Native(record) => apply_native(&tx, transaction, record)?,
+            }
+        }
+
+        tx.commit()?;
+        Ok(true)
+    }
+
+    pub fn count(&self, table: &str) -> Result<u64> {
+        let count = self
+            .conn
+            .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })?;
+        Ok(count)
+    }
+}
+
+fn apply_event(
+    tx: &Transaction,
+    transaction: &RecordedTransaction,
+    event: &ProgramEvent,
+) -> Result<()> {
+    let signature = &transaction.signature;
+    let slot = transaction.slot;
+
+    match event {
+        ProgramEvent::PropertyInitialized(e) => {
+            tx.execute(
+                "INSERT INTO properties (property, source, property_id, owner, mint, property_uri,
+                    total_tokens, available_tokens, token_price, status, created_at, updated_at, signature)
+                 VALUES (?1, 'anchor', ?2, ?3, ?4, ?5, ?6, ?6, ?7, 'available', ?8, ?8, ?9)
+                 ON CONFLICT(property) DO UPDATE SET
+                    property_id = excluded.property_id, owner = excluded.owner, mint = excluded.mint,
+                    property_uri = excluded.property_uri, total_tokens = excluded.total_tokens,
+                    available_tokens = excluded.available_tokens, token_price = excluded.token_price,
+                    status = excluded.status, created_at = excluded.created_at,
+                    updated_at = excluded.updated_at, signature = excluded.signature",
+                params![
+                    e.property.to_string(),
+                    e.property_id,
+                    e.owner.to_string(),
+                    e.mint.to_string(),
+                    e.property_uri,
+                    e.total_tokens,
+                    e.token_price,
+                    e.timestamp,
+                    signature,
+                ],
+            )?;
+        }
+        ProgramEvent::TokensPurchased(e) => {
+            tx.execute(
+                "INSERT INTO purchases (signature, slot, source, property, mint, buyer, amount, token_price, timestamp)
+                 VALUES (?1, ?2, 'anchor', ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    slot,
+                    e.property.to_string(),
+                    e.mint.to_string(),
+                    e.buyer.to_string(),
+                    e.amount,
+                    e.token_price,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE properties SET available_tokens = ?2, status = ?3, updated_at = ?4 WHERE property = ?1",
+                params![
+                    e.property.to_string(),
+                    e.available_tokens,
+                    e.status.as_str(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::PropertyDetailsUpdated(e) => {
+            tx.execute(
+                "UPDATE properties SET name = ?2, updated_at = ?3 WHERE property = ?1",
+                params![e.property.to_string(), e.details.title, e.timestamp],
+            )?;
+        }
+        ProgramEvent::PropertyStatusUpdated(e) => {
+            tx.execute(
+                "UPDATE properties SET status = ?2, updated_at = ?3 WHERE property = ?1",
+                params![e.property.to_string(), e.status.as_str(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::OwnerTransferred(e) => {
+            tx.execute(
+                "UPDATE properties SET owner = ?2, updated_at = ?3 WHERE property = ?1",
+                params![e.property.to_string(), e.owner.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::ProposalCreated(e) => {
+            let action_status = match e.action {
+                ProposalAction::Signal | ProposalAction::StartLiquidation { .. } => None,
+                ProposalAction::UpdatePropertyStatus { status } => Some(status.as_str()),
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO proposals (proposal, governance, property, proposer, proposal_index,
+                    action, action_status, description_uri, votes_for, votes_against, voting_ends_at,
+                    status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 0, 0, ?9, 'active', ?10, ?10)",
+                params![
+                    e.proposal.to_string(),
+                    e.governance.to_string(),
+                    e.property.to_string(),
+                    e.proposer.to_string(),
+                    e.index,
+                    e.action.as_str(),
+                    action_status,
+                    e.description_uri,
+                    e.voting_ends_at,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::VoteCast(e) => {
+            tx.execute(
+                "INSERT INTO votes (signature, slot, proposal, voter, support, weight, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    signature,
+                    slot,
+                    e.proposal.to_string(),
+                    e.voter.to_string(),
+                    e.support,
+                    e.weight,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE proposals SET votes_for = ?2, votes_against = ?3, updated_at = ?4 WHERE proposal = ?1",
+                params![
+                    e.proposal.to_string(),
+                    e.votes_for,
+                    e.votes_against,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::ProposalFinalized(e) => {
+            tx.execute(
+                "UPDATE proposals SET status = ?2, votes_for = ?3, votes_against = ?4, updated_at = ?5
+                 WHERE proposal = ?1",
+                params![
+                    e.proposal.to_string(),
+                    e.status.as_str(),
+                    e.votes_for,
+                    e.votes_against,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::ProposalCancelled(e) => {
+            tx.execute(
+                "UPDATE proposals SET status = 'cancelled', updated_at = ?2 WHERE proposal = ?1",
+                params![e.proposal.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::ProposalClosed(e) => {
+            tx.execute(
+                "UPDATE proposals SET closed_at = ?2, updated_at = ?2 WHERE proposal = ?1",
+                params![e.proposal.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::LiquidationFunded(e) => {
+            // The liquidation PDA is per property, so a cancelled funding can
+            // be followed by a new one at the same address
+            tx.execute(
+                "INSERT OR REPLACE INTO liquidations (liquidation, property, funder, proceeds,
+                    redeemed_tokens, paid_out, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, 0, 0, 'pending', ?5, ?5)",
+                params![
+                    e.liquidation.to_string(),
+                    e.property.to_string(),
+                    e.funder.to_string(),
+                    e.proceeds,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::LiquidationCancelled(e) => {
+            tx.execute(
+                "UPDATE liquidations SET status = 'cancelled', updated_at = ?2 WHERE liquidation = ?1",
+                params![e.liquidation.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::LiquidationStarted(e) => {
+            tx.execute(
+                "UPDATE liquidations SET status = 'active', supply = ?2, redeem_until = ?3, updated_at = ?4
+                 WHERE liquidation = ?1",
+                params![e.liquidation.to_string(), e.supply, e.redeem_until, e.timestamp],
+            )?;
+        }
+        ProgramEvent::LiquidationRedeemed(e) => {
+            tx.execute(
+                "INSERT INTO redemptions (signature, slot, liquidation, property, holder, amount, payout, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    slot,
+                    e.liquidation.to_string(),
+                    e.property.to_string(),
+                    e.holder.to_string(),
+                    e.amount,
+                    e.payout,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE liquidations SET redeemed_tokens = ?2, paid_out = paid_out + ?3,
+                    status = CASE WHEN ?2 = supply THEN 'completed' ELSE status END,
+                    updated_at = ?4
+                 WHERE liquidation = ?1",
+                params![
+                    e.liquidation.to_string(),
+                    e.redeemed_tokens,
+                    e.payout,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::LiquidationClosed(e) => {
+            tx.execute(
+                "UPDATE liquidations SET status = 'closed', unredeemed_proceeds = ?2, updated_at = ?3
+                 WHERE liquidation = ?1",
+                params![e.liquidation.to_string(), e.unredeemed_proceeds, e.timestamp],
+            )?;
+        }
+        ProgramEvent::MarketplaceInitialized(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO marketplaces (marketplace, authority, treasury, fee, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    e.marketplace.to_string(),
+                    e.authority.to_string(),
+                    e.treasury.to_string(),
+                    e.fee,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::MarketplaceFeeUpdated(e) => {
+            tx.execute(
+                "UPDATE marketplaces SET fee = ?2, updated_at = ?3 WHERE marketplace = ?1",
+                params![e.marketplace.to_string(), e.fee, e.timestamp],
+            )?;
+        }
+        ProgramEvent::VerificationRequirementUpdated(e) => {
+            tx.execute(
+                "UPDATE marketplaces SET require_verified = ?2, updated_at = ?3 WHERE marketplace = ?1",
+                params![e.marketplace.to_string(), e.require_verified, e.timestamp],
+            )?;
+        }
+        ProgramEvent::MarketplacePauseUpdated(e) => {
+            tx.execute(
+                "UPDATE marketplaces SET paused = ?2, updated_at = ?3 WHERE marketplace = ?1",
+                params![e.marketplace.to_string(), e.paused, e.timestamp],
+            )?;
+        }
+        ProgramEvent::AuthorityTransferProposed(e) => {
+            // Proposing the default pubkey withdraws a pending handover
+            let pending =
+                (e.pending_authority != Pubkey([0; 32])).then(|| e.pending_authority.to_string());
+            tx.execute(
+                "UPDATE marketplaces SET pending_authority = ?2, updated_at = ?3 WHERE marketplace = ?1",
+                params![e.marketplace.to_string(), pending, e.timestamp],
+            )?;
+        }
+        ProgramEvent::AuthorityTransferred(e) => {
+            tx.execute(
+                "UPDATE marketplaces SET authority = ?2, pending_authority = NULL, updated_at = ?3
+                 WHERE marketplace = ?1",
+                params![
+                    e.marketplace.to_string(),
+                    e.authority.to_string(),
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::TreasuryUpdated(e) => {
+            tx.execute(
+                "UPDATE marketplaces SET treasury = ?2, updated_at = ?3 WHERE marketplace = ?1",
+                params![
+                    e.marketplace.to_string(),
+                    e.treasury.to_string(),
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::MintDelistingUpdated(e) => {
+            if e.delisted {
+                tx.execute(
+                    "INSERT OR REPLACE INTO delisted_mints (mint, marketplace, delisted_at)
+                     VALUES (?1, ?2, ?3)",
+                    params![e.mint.to_string(), e.marketplace.to_string(), e.timestamp],
+                )?;
+            } else {
+                tx.execute(
+                    "DELETE FROM delisted_mints WHERE mint = ?1",
+                    params![e.mint.to_string()],
+                )?;
+            }
+        }
+        ProgramEvent::FeeScheduleUpdated(e) => {
+            tx.execute(
+                "UPDATE marketplaces SET referrer_fee_share = ?2, updated_at = ?3 WHERE marketplace = ?1",
+                params![e.marketplace.to_string(), e.referrer_fee_share, e.timestamp],
+            )?;
+        }
+        ProgramEvent::MintFeeConfigUpdated(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO mint_fee_configs (mint, fee, royalty_recipient, royalty_share,
+                    updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    e.mint.to_string(),
+                    e.fee,
+                    e.royalty_recipient.to_string(),
+                    e.royalty_share,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::MintFeeConfigRemoved(e) => {
+            tx.execute(
+                "DELETE FROM mint_fee_configs WHERE mint = ?1",
+                params![e.mint.to_string()],
+            )?;
+        }
+        ProgramEvent::ListingCreated(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO listings (listing, seller, listing_index, mint, quote_mint,
+                    price_per_token, token_amount, remaining_amount, expires_at, status, created_at,
+                    updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7, ?8, 'active', ?9, ?9)",
+                params![
+                    e.listing.to_string(),
+                    e.seller.to_string(),
+                    e.index,
+                    e.mint.to_string(),
+                    e.quote_mint.to_string(),
+                    e.price_per_token,
+                    e.token_amount,
+                    // 0 on-chain means the listing never expires
+                    (e.expires_at != 0).then_some(e.expires_at),
+                    e.timestamp,
+                ],
+            )?;
+            record_listing_price(
+                tx,
+                transaction,
+                &e.listing,
+                e.price_per_token,
+                e.token_amount,
+                e.timestamp,
+            )?;
+        }
+        ProgramEvent::ListingUpdated(e) => {
+            tx.execute(
+                "UPDATE listings SET price_per_token = ?2, remaining_amount = ?3, expires_at = ?4,
+                    updated_at = ?5
+                 WHERE listing = ?1",
+                params![
+                    e.listing.to_string(),
+                    e.price_per_token,
+                    e.token_amount,
+                    (e.expires_at != 0).then_some(e.expires_at),
+                    e.timestamp,
+                ],
+            )?;
+            record_listing_price(
+                tx,
+                transaction,
+                &e.listing,
+                e.price_per_token,
+                e.token_amount,
+                e.timestamp,
+            )?;
+        }
+        ProgramEvent::TradeExecuted(e) => {
+            tx.execute(
+                "INSERT INTO trades (signature, slot, listing, seller, buyer, mint, quote_mint, token_amount,
+                    price_per_token, total_price, fee_amount, seller_amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                params![
+                    signature,
+                    slot,
+                    e.listing.to_string(),
+                    e.seller.to_string(),
+                    e.buyer.to_string(),
+                    e.mint.to_string(),
+                    e.quote_mint.to_string(),
+                    e.token_amount,
+                    e.price_per_token,
+                    e.total_price,
+                    e.fee_amount,
+                    e.seller_amount,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE listings SET remaining_amount = ?2,
+                    status = CASE WHEN ?2 = 0 THEN 'completed' ELSE status END,
+                    updated_at = ?3
+                 WHERE listing = ?1",
+                params![e.listing.to_string(), e.remaining_amount, e.timestamp],
+            )?;
+        }
+        ProgramEvent::ListingCancelled(e) => {
+            tx.execute(
+                "UPDATE listings SET remaining_amount = ?2, status = 'cancelled', updated_at = ?3
+                 WHERE listing = ?1",
+                params![e.listing.to_string(), e.remaining_amount, e.timestamp],
+            )?;
+        }
+        ProgramEvent::ListingClosed(e) => {
+            // Only an active listing can still hold tokens when it is closed,
+            // and that only happens once it has expired
+            tx.execute(
+                "UPDATE listings SET
+                    remaining_amount = CASE WHEN status = 'active' THEN 0 ELSE remaining_amount END,
+                    status = CASE WHEN status = 'active' THEN 'expired' ELSE status END,
+                    closed_at = ?2, updated_at = ?2
+                 WHERE listing = ?1",
+                params![e.listing.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::OfferMade(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO offers (offer, listing, buyer, price_per_token, token_amount,
+                    total_price, expires_at, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, 'open', ?8, ?8)",
+                params![
+                    e.offer.to_string(),
+                    e.listing.to_string(),
+                    e.buyer.to_string(),
+                    e.price_per_token,
+                    e.token_amount,
+                    e.total_price,
+                    e.expires_at,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::OfferAccepted(e) => {
+            tx.execute(
+                "UPDATE offers SET status = 'accepted', updated_at = ?2 WHERE offer = ?1",
+                params![e.offer.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::ListingsSwept(e) => {
+            // Each fill is also recorded in `trades` through its TradeExecuted
+            tx.execute(
+                "INSERT INTO sweeps (signature, slot, buyer, mint, quote_mint, listings_filled,
+                    token_amount, total_price, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    signature,
+                    slot,
+                    e.buyer.to_string(),
+                    e.mint.to_string(),
+                    e.quote_mint.to_string(),
+                    e.listings_filled,
+                    e.token_amount,
+                    e.total_price,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::TradeFeeDistributed(e) => {
+            // The default pubkey stands for "no recipient" on-chain
+            let recipient = |key: &Pubkey| (*key != Pubkey([0; 32])).then(|| key.to_string());
+            tx.execute(
+                "INSERT INTO trade_fees (signature, slot, listing, quote_mint, fee, fee_amount,
+                    treasury_amount, royalty_recipient, royalty_amount, referrer, referrer_amount,
+                    timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+                params![
+                    signature,
+                    slot,
+                    e.listing.to_string(),
+                    e.quote_mint.to_string(),
+                    e.fee,
+                    e.fee_amount,
+                    e.treasury_amount,
+                    recipient(&e.royalty_recipient),
+                    e.royalty_amount,
+                    recipient(&e.referrer),
+                    e.referrer_amount,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::OfferWithdrawn(e) => {
+            tx.execute(
+                "UPDATE offers SET status = 'withdrawn', updated_at = ?2 WHERE offer = ?1",
+                params![e.offer.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::OrderBookCreated(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO order_books (order_book, mint, quote_mint, created_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    e.order_book.to_string(),
+                    e.mint.to_string(),
+                    e.quote_mint.to_string(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::OrderPlaced(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO orders (order_book, order_id, owner, side, price, quantity,
+                    remaining_quantity, status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6, 'open', ?7, ?7)",
+                params![
+                    e.order_book.to_string(),
+                    e.order_id,
+                    e.owner.to_string(),
+                    e.side.as_str(),
+                    e.price,
+                    e.quantity,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::OrderCancelled(e) => {
+            tx.execute(
+                "UPDATE orders SET remaining_quantity = ?3, status = 'cancelled', updated_at = ?4
+                 WHERE order_book = ?1 AND order_id = ?2",
+                params![
+                    e.order_book.to_string(),
+                    e.order_id,
+                    e.remaining_quantity,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::OrdersMatched(e) => {
+            tx.execute(
+                "INSERT INTO order_fills (signature, slot, order_book, bid_id, ask_id, buyer, seller,
+                    price, quantity, fee_amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+                params![
+                    signature,
+                    slot,
+                    e.order_book.to_string(),
+                    e.bid_id,
+                    e.ask_id,
+                    e.buyer.to_string(),
+                    e.seller.to_string(),
+                    e.price,
+                    e.quantity,
+                    e.fee_amount,
+                    e.timestamp,
+                ],
+            )?;
+            for order_id in [e.bid_id, e.ask_id] {
+                tx.execute(
+                    "UPDATE orders SET remaining_quantity = remaining_quantity - ?3,
+                        status = CASE WHEN remaining_quantity = ?3 THEN 'filled' ELSE status END,
+                        updated_at = ?4
+                     WHERE order_book = ?1 AND order_id = ?2",
+                    params![e.order_book.to_string(), order_id, e.quantity, e.timestamp],
+                )?;
+            }
+        }
+        ProgramEvent::AuctionCreated(e) => {
+            let (reserve_price, end_price) = match e.kind {
+                AuctionKind::English { reserve_price, .. } => (Some(reserve_price), None),
+                AuctionKind::Dutch { end_price } => (None, Some(end_price)),
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO auctions (auction, seller, auction_index, mint, quote_mint, kind,
+                    token_amount, start_price, reserve_price, end_price, start_time, end_time,
+                    status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, 'active', ?13, ?13)",
+                params![
+                    e.auction.to_string(),
+                    e.seller.to_string(),
+                    e.index,
+                    e.mint.to_string(),
+                    e.quote_mint.to_string(),
+                    e.kind.as_str(),
+                    e.token_amount,
+                    e.start_price,
+                    reserve_price,
+                    end_price,
+                    e.start_time,
+                    e.end_time,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::AuctionBidPlaced(e) => {
+            tx.execute(
+                "INSERT INTO auction_bids (signature, slot, auction, bidder, amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    signature,
+                    slot,
+                    e.auction.to_string(),
+                    e.bidder.to_string(),
+                    e.amount,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE auctions SET highest_bidder = ?2, highest_bid = ?3, end_time = ?4, updated_at = ?5
+                 WHERE auction = ?1",
+                params![
+                    e.auction.to_string(),
+                    e.bidder.to_string(),
+                    e.amount,
+                    e.end_time,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::AuctionBidRefunded(e) => {
+            tx.execute(
+                "UPDATE auction_bids SET refunded = 1 WHERE auction = ?1 AND bidder = ?2",
+                params![e.auction.to_string(), e.bidder.to_string()],
+            )?;
+        }
+        ProgramEvent::AuctionSettled(e) => {
+            tx.execute(
+                "UPDATE auctions SET highest_bidder = ?2, highest_bid = ?3, fee_amount = ?4,
+                    status = 'settled', updated_at = ?5
+                 WHERE auction = ?1",
+                params![
+                    e.auction.to_string(),
+                    e.buyer.to_string(),
+                    e.price,
+                    e.fee_amount,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::AuctionCancelled(e) => {
+            tx.execute(
+                "UPDATE auctions SET status = ?2, updated_at = ?3 WHERE auction = ?1",
+                params![e.auction.to_string(), e.status.as_str(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::AuctionClosed(e) => {
+            tx.execute(
+                "UPDATE auctions SET closed_at = ?2, updated_at = ?2 WHERE auction = ?1",
+                params![e.auction.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::QuoteMintsUpdated(_)
+        | ProgramEvent::FundsSettled(_)
+        | ProgramEvent::RegistryInitialized(_) => {}
+        ProgramEvent::VerifierAdded(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO verifiers (verifier, authority, name, url, is_active, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, 1, ?5)",
+                params![
+                    e.verifier.to_string(),
+                    e.authority.to_string(),
+                    e.name,
+                    e.url,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::VerifierUpdated(e) => {
+            tx.execute(
+                "UPDATE verifiers SET name = ?2, url = ?3, updated_at = ?4 WHERE verifier = ?1",
+                params![e.verifier.to_string(), e.name, e.url, e.timestamp],
+            )?;
+        }
+        ProgramEvent::VerifierRemoved(e) => {
+            tx.execute(
+                "UPDATE verifiers SET is_active = 0, updated_at = ?2 WHERE verifier = ?1",
+                params![e.verifier.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::VerifierClosed(e) => {
+            tx.execute(
+                "UPDATE verifiers SET closed_at = ?2, updated_at = ?2 WHERE verifier = ?1",
+                params![e.verifier.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::PropertyRegistered(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO property_records (property_record, property_id, token_mint, owner,
+                    address, verification_status, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'pending', ?6, ?6)",
+                params![
+                    e.property_record.to_string(),
+                    e.property_id,
+                    e.token_mint.to_string(),
+                    e.owner.to_string(),
+                    e.address,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::PropertyVerified(e) => {
+            tx.execute(
+                "INSERT INTO verifications (signature, slot, property_record, token_mint, verifier, authority,
+                    status, verification_method, verification_expiry, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 'verified', ?7, ?8, ?9)",
+                params![
+                    signature,
+                    slot,
+                    e.property_record.to_string(),
+                    e.token_mint.to_string(),
+                    e.verifier.to_string(),
+                    e.verifier_authority.to_string(),
+                    e.verification_details.verification_method,
+                    e.verification_details.verification_expiry,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE property_records SET verification_status = 'verified', updated_at = ?2
+                 WHERE property_record = ?1",
+                params![e.property_record.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::RecordStatusUpdated(e) => {
+            tx.execute(
+                "INSERT INTO verifications (signature, slot, property_record, token_mint, authority, status, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    signature,
+                    slot,
+                    e.property_record.to_string(),
+                    e.token_mint.to_string(),
+                    e.authority.to_string(),
+                    e.status.as_str(),
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE property_records SET verification_status = ?2, updated_at = ?3
+                 WHERE property_record = ?1",
+                params![
+                    e.property_record.to_string(),
+                    e.status.as_str(),
+                    e.timestamp
+                ],
+            )?;
+        }
+        ProgramEvent::PropertyRecordAmended(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO record_amendments (amendment, signature, slot, property_record,
+                    amendment_index, kind, owner, status, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'details', ?6, ?7, ?8)",
+                params![
+                    e.amendment.to_string(),
+                    signature,
+                    slot,
+                    e.property_record.to_string(),
+                    e.index,
+                    e.owner.to_string(),
+                    e.status.as_str(),
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE property_records SET address = ?2, verification_status = ?3, updated_at = ?4
+                 WHERE property_record = ?1",
+                params![
+                    e.property_record.to_string(),
+                    e.address,
+                    e.status.as_str(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::RecordOwnershipTransferred(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO record_amendments (amendment, signature, slot, property_record,
+                    amendment_index, kind, owner, previous_owner, verifier, status, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, 'ownership_transfer', ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    e.amendment.to_string(),
+                    signature,
+                    slot,
+                    e.property_record.to_string(),
+                    e.index,
+                    e.owner.to_string(),
+                    e.previous_owner.to_string(),
+                    e.verifier.to_string(),
+                    e.status.as_str(),
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE property_records SET owner = ?2, verification_status = ?3, updated_at = ?4
+                 WHERE property_record = ?1",
+                params![
+                    e.property_record.to_string(),
+                    e.owner.to_string(),
+                    e.status.as_str(),
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::PropertyRecordClosed(e) => {
+            // The record's address is derived from its property id, so
+            // registering the id again replaces this row
+            tx.execute(
+                "UPDATE property_records SET closed_at = ?2, updated_at = ?2 WHERE property_record = ?1",
+                params![e.property_record.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::AmendmentClosed(e) => {
+            tx.execute(
+                "UPDATE record_amendments SET closed_at = ?2 WHERE amendment = ?1",
+                params![e.amendment.to_string(), e.timestamp],
+            )?;
+        }
+        ProgramEvent::PoolInitialized(e) => {
+            tx.execute(
+                "INSERT OR REPLACE INTO pools (pool, authority, property_mint, quote_mint, lp_mint, fee,
+                    created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+                params![
+                    e.pool.to_string(),
+                    e.authority.to_string(),
+                    e.property_mint.to_string(),
+                    e.quote_mint.to_string(),
+                    e.lp_mint.to_string(),
+                    e.fee,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::LiquidityAdded(e) => {
+            tx.execute(
+                "INSERT INTO pool_liquidity (signature, slot, pool, provider, action, property_amount,
+                    quote_amount, lp_amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, 'add', ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    slot,
+                    e.pool.to_string(),
+                    e.provider.to_string(),
+                    e.property_amount,
+                    e.quote_amount,
+                    e.lp_amount,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE pools SET property_reserve = ?2, quote_reserve = ?3, lp_supply = ?4,
+                    updated_at = ?5
+                 WHERE pool = ?1",
+                params![
+                    e.pool.to_string(),
+                    e.property_reserve,
+                    e.quote_reserve,
+                    e.lp_supply,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::LiquidityRemoved(e) => {
+            tx.execute(
+                "INSERT INTO pool_liquidity (signature, slot, pool, provider, action, property_amount,
+                    quote_amount, lp_amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, 'remove', ?5, ?6, ?7, ?8)",
+                params![
+                    signature,
+                    slot,
+                    e.pool.to_string(),
+                    e.provider.to_string(),
+                    e.property_amount,
+                    e.quote_amount,
+                    e.lp_amount,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE pools SET property_reserve = ?2, quote_reserve = ?3, lp_supply = ?4,
+                    updated_at = ?5
+                 WHERE pool = ?1",
+                params![
+                    e.pool.to_string(),
+                    e.property_reserve,
+                    e.quote_reserve,
+                    e.lp_supply,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::Swapped(e) => {
+            tx.execute(
+                "INSERT INTO pool_swaps (signature, slot, pool, trader, side, amount_in, amount_out,
+                    fee_amount, timestamp)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    signature,
+                    slot,
+                    e.pool.to_string(),
+                    e.trader.to_string(),
+                    e.side.as_str(),
+                    e.amount_in,
+                    e.amount_out,
+                    e.fee_amount,
+                    e.timestamp,
+                ],
+            )?;
+            tx.execute(
+                "UPDATE pools SET property_reserve = ?2, quote_reserve = ?3, updated_at = ?4
+                 WHERE pool = ?1",
+                params![
+                    e.pool.to_string(),
+                    e.property_reserve,
+                    e.quote_reserve,
+                    e.timestamp,
+                ],
+            )?;
+        }
+        ProgramEvent::PoolFeeUpdated(e) => {
+            tx.execute(
+                "UPDATE pools SET fee = ?2, updated_at = ?3 WHERE pool = ?1",
+                params![e.pool.to_string(), e.fee, e.timestamp],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+// One row per price a listing has been offered at, so price history survives
+// `update_listing`.
+fn record_listing_price(
+    tx: &Transaction,
+    transaction: &RecordedTransaction,
+    listing: &Pubkey,
+    price_per_token: u64,
+    token_amount: u64,
+    timestamp: i64,
+) -> Result<()> {
+    tx.execute(
+        "INSERT INTO listing_prices (signature, slot, listing, price_per_token, token_amount, timestamp)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            transaction.signature,
+            transaction.slot,
+            listing.to_string(),
+            price_per_token,
+            token_amount,
+            timestamp,
+        ],
+    )?;
+
+    Ok(())
+}
+
+// The native program does not log account keys, so these rows only carry what
+// its messages expose plus the transaction they came from.
+fn apply_native(
+    tx: &Transaction,
+    transaction: &RecordedTransaction,
+    record: &NativeRecord,
+) -> Result<()> {
+    let signature = &transaction.signature;
+    let slot = transaction.slot;
+    let timestamp = transaction.block_time;
+
+    match record {
+        NativeRecord::PropertyCreated { name } => {
+            tx.execute(
+                "INSERT INTO properties (source, name, created_at, updated_at, signature)
+                 VALUES ('native', ?1, ?2, ?2, ?3)",
+                params![name, timestamp, signature],
+            )?;
+        }
+        NativeRecord::TokensPurchased { amount } => {
+            tx.execute(
+                "INSERT INTO purchases (signature, slot, source, amount, timestamp)
+                 VALUES (?1, ?2, 'native', ?3, ?4)",
+                params![signature, slot, amount, timestamp],
+            )?;
+        }
+        NativeRecord::YieldDistributed { amount } => {
+            tx.execute(
+                "INSERT INTO yields (signature, slot, source, amount, timestamp)
+                 VALUES (?1, ?2, 'native', ?3, ?4)",
+                params![signature, slot, amount, timestamp],
+            )?;
+        }
+        NativeRecord::PropertyValueUpdated { new_value } => {
+            tx.execute(
+                "INSERT INTO property_valuations (signature, slot, source, value, timestamp)
+                 VALUES (?1, ?2, 'native', ?3, ?4)",
+                params![signature, slot, new_value, timestamp],
+            )?;
+        }
+    }
+
+    Ok(())
+}
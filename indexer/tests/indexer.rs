@@ -0,0 +1,771 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+
+use yieldhabitat_indexer::events::{discriminator, ProgramEvent, Pubkey};
+use yieldhabitat_indexer::ledger::{read_ledger, RecordedTransaction};
+use yieldhabitat_indexer::pda::listing_address;
+use yieldhabitat_indexer::programs::ProgramIds;
+use yieldhabitat_indexer::store::{IndexedRecord, Store};
+use yieldhabitat_indexer::{decode_transaction, IndexSummary, Indexer};
+
+const NATIVE_PROGRAM: &str = "Nat1veProperty111111111111111111111111111111";
+const OWNER: &str = "8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR";
+const NEW_OWNER: &str = "p2Yicb86aZig616Eav2VWG9vuXR5mEqhtzshZYBxzsV";
+const PROPERTY: &str = "4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi";
+const MINT: &str = "CktRuQ2mttgRGkXJtyksdKHjUdc2C4TgDzyB98oEzy8";
+const BUYER: &str = "GgBaCs3NCBuZN12kCJgAW63ydqohFkHEdfdEXBPzLHq";
+const LISTING: &str = "QWmroo4YnnMqYW3cnxWkFdaTxGD3P7vMSzwMHGbUzwF";
+const OFFER: &str = "2Z8oHviEbrqDD5kg2sW8h8kYceqdVTnrrPL6Lk2nBfRG";
+const PROPERTY_2: &str = "25hjHpTATmkdET17ynDhf1MCuYNDn1z7wXfVw5iaxLAK";
+const RECORD: &str = "k7FaK87WHGVXzkaoHb7CdVPgkKDQhZ29VLDeBVbDfYn";
+const RECORD_2: &str = "2ktgiq7GNkitdMWCLmUtZm4qM8UEWerKXcL4WtAaRfPP";
+const RECORD_2_AMENDMENT: &str = "2xea9jWJ9eca3dFiefTeSPP85c6qXqunCqL2h2JNffMW";
+const VERIFIER: &str = "cGfHiC6Kgg3FpFZvgwGcswsCRtp4aBP2fzuXRQPizuN";
+const VERIFIER_AUTHORITY: &str = "gBxS1f6uyyGPuW5MzGBukidSb71jdsCb5fZaoSzULE5";
+const PROPOSAL: &str = "swqrv48gsrwpBFbftEwnP2vB4jckpvfGJfXkwaniLCC";
+const QUOTE_MINT: &str = "2HTciirCEfeJeikeHgCTXdfVe1zpoD3ackfU7DrPCL8S";
+const LIQUIDATION: &str = "29d2S7vB453rNYFdR5Ycwt7y9haRT5fwVwL9zTmBhfV2";
+const ORDER_BOOK: &str = "2MNus2KCpxwXnp19iyXNpWSFtBD2UGjQBAL8AbtywfT9";
+const AUCTION: &str = "2RJD1KnDRGEkvuFfAGrJ7PD28LRE9LRDjZznDywagzmr";
+const AUCTION_WINNER: &str = "2VDW9dFE1ZXz4zWAbaBDQFynNVdRpQ73HyfSHMzBSL6Z";
+const POOL: &str = "2d46SEBFCA8SMB1BUAq3z1XJrp3qAXUgQnzkQ85Nvzjy";
+
+fn fixture(name: &str) -> Vec<RecordedTransaction> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    read_ledger(BufReader::new(File::open(path).unwrap())).unwrap()
+}
+
+fn program_ids() -> ProgramIds {
+    ProgramIds {
+        native_property_tokenization: Some(NATIVE_PROGRAM.to_string()),
+        ..ProgramIds::default()
+    }
+}
+
+fn index(names: &[&str]) -> Indexer {
+    let mut indexer = Indexer::new(Store::open_in_memory().unwrap(), program_ids());
+    for name in names {
+        indexer.index(&fixture(name)).unwrap();
+    }
+    indexer
+}
+
+#[test]
+fn reads_flat_and_rpc_shaped_entries() {
+    let transactions = fixture("anchor_ledger.json");
+    assert_eq!(transactions.len(), 46);
+
+    let purchase = &transactions[1];
+    assert_eq!(purchase.signature, "sig-purchase");
+    assert_eq!(purchase.slot, 11);
+    assert!(!purchase.failed);
+    assert!(!purchase.logs.is_empty());
+
+    let failed = transactions
+        .iter()
+        .find(|transaction| transaction.signature == "sig-trade-failed")
+        .unwrap();
+    assert!(failed.failed);
+}
+
+#[test]
+fn anchor_discriminator_matches_sha256_prefix() {
+    // sha256("event:TradeExecuted")[..8]
+    assert_eq!(
+        discriminator("TradeExecuted"),
+        [0x29, 0x6e, 0x40, 0x81, 0x3c, 0x4f, 0xb3, 0x50]
+    );
+}
+
+#[test]
+fn derives_listing_addresses_like_the_runtime() {
+    // Reference values from solana_program::pubkey::Pubkey::find_program_address
+    let program = Pubkey([7; 32]);
+    let seller = Pubkey([5; 32]);
+    let expected = [
+        (0, "4kFPVofQSgroQJ8aLmSKjRttfh2QjFbbTU9KpZxDchr9"),
+        (1, "EUajcPzsKdWUN4ZkXTvurC3htsBMLrcrRs5K1PuHyL8y"),
+        (42, "6poQQ94WLLtBu8hYc6nxyk5Fv9CXfGQpxTdep6zwfhW"),
+    ];
+
+    for (index, address) in expected {
+        let derived = listing_address(&program, &seller, index).unwrap();
+        assert_eq!(derived.to_string(), address);
+    }
+}
+
+#[test]
+fn decodes_events_emitted_through_cpi() {
+    let transactions = fixture("anchor_ledger.json");
+    let verify = transactions
+        .iter()
+        .find(|transaction| transaction.signature == "sig-verify-cpi")
+        .unwrap();
+
+    let records = decode_transaction(&program_ids(), verify);
+    assert_eq!(records.len(), 1);
+    let IndexedRecord::Event(event) = &records[0] else {
+        panic!("unexpected record {:?}", records[0]);
+    };
+    let ProgramEvent::PropertyVerified(event) = event.as_ref() else {
+        panic!("unexpected event {:?}", event);
+    };
+    assert_eq!(event.property_record.to_string(), RECORD);
+    assert_eq!(event.verification_details.verification_method, "site-visit");
+}
+
+#[test]
+fn builds_property_and_purchase_history() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (available, status, name, owner): (u64, String, String, String) = conn
+        .query_row(
+            "SELECT available_tokens, status, name, owner FROM properties WHERE property = ?1",
+            [PROPERTY],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(available, 900);
+    assert_eq!(status, "available");
+    assert_eq!(name, "Harbor Lofts");
+    assert_eq!(owner, NEW_OWNER);
+
+    let (buyer, amount, mint): (String, u64, String) = conn
+        .query_row("SELECT buyer, amount, mint FROM purchases", [], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap();
+    assert_eq!(buyer, BUYER);
+    assert_eq!(amount, 100);
+    assert_eq!(mint, MINT);
+}
+
+#[test]
+fn ignores_failed_transactions_when_recording_trades() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    assert_eq!(indexer.store().count("trades").unwrap(), 2);
+
+    let (volume, fees): (u64, u64) = conn
+        .query_row(
+            "SELECT SUM(total_price), SUM(fee_amount) FROM trades WHERE mint = ?1",
+            [MINT],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(volume, 6000);
+    assert_eq!(fees, 60);
+
+    let quote_mints: Vec<String> = conn
+        .prepare("SELECT DISTINCT quote_mint FROM trades")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(quote_mints, vec![QUOTE_MINT]);
+
+    let (remaining, status, expires_at, closed_at): (u64, String, Option<i64>, Option<i64>) = conn
+        .query_row(
+            "SELECT remaining_amount, status, expires_at, closed_at FROM listings WHERE listing = ?1",
+            [LISTING],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(remaining, 0);
+    assert_eq!(status, "completed");
+    assert_eq!(expires_at, None);
+    assert_eq!(closed_at, Some(1_700_000_024));
+}
+
+#[test]
+fn records_sweeps() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (buyer, mint, listings_filled, token_amount, total_price): (String, String, u32, u64, u64) =
+        conn.query_row(
+            "SELECT buyer, mint, listings_filled, token_amount, total_price FROM sweeps",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(buyer, BUYER);
+    assert_eq!(mint, PROPERTY_2);
+    assert_eq!(listings_filled, 3);
+    assert_eq!(token_amount, 150);
+    assert_eq!(total_price, 9000);
+}
+
+#[test]
+fn records_listing_price_history() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let prices: Vec<u64> = conn
+        .prepare("SELECT price_per_token FROM listing_prices WHERE listing = ?1 ORDER BY id")
+        .unwrap()
+        .query_map([LISTING], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(prices, vec![50, 60]);
+
+    let current: u64 = conn
+        .query_row(
+            "SELECT price_per_token FROM listings WHERE listing = ?1",
+            [LISTING],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(current, 60);
+}
+
+#[test]
+fn records_fee_splits_and_mint_fee_configs() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    // (fee, treasury, royalty recipient, royalty, referrer, referrer amount)
+    type FeeSplit = (u64, u64, Option<String>, u64, Option<String>, u64);
+    let splits: Vec<FeeSplit> = conn
+        .prepare(
+            "SELECT fee_amount, treasury_amount, royalty_recipient, royalty_amount, referrer,
+                referrer_amount
+             FROM trade_fees WHERE listing = ?1 ORDER BY id",
+        )
+        .unwrap()
+        .query_map([LISTING], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        splits,
+        vec![
+            (
+                24,
+                17,
+                Some(OWNER.to_string()),
+                4,
+                Some(NEW_OWNER.to_string()),
+                3
+            ),
+            (36, 29, Some(OWNER.to_string()), 7, None, 0),
+        ]
+    );
+
+    let referrer_fee_share: u16 = conn
+        .query_row("SELECT referrer_fee_share FROM marketplaces", [], |row| {
+            row.get(0)
+        })
+        .unwrap();
+    assert_eq!(referrer_fee_share, 1000);
+
+    // The second config was removed again
+    let configs: Vec<(String, u16, u16)> = conn
+        .prepare("SELECT mint, fee, royalty_share FROM mint_fee_configs")
+        .unwrap()
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(configs, vec![(MINT.to_string(), 100, 2000)]);
+}
+
+#[test]
+fn tracks_marketplace_admin_changes() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (authority, pending_authority, treasury, paused, require_verified): (
+        String,
+        Option<String>,
+        String,
+        bool,
+        bool,
+    ) = conn
+        .query_row(
+            "SELECT authority, pending_authority, treasury, paused, require_verified
+             FROM marketplaces",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(authority, NEW_OWNER);
+    assert_eq!(pending_authority, None);
+    assert_eq!(treasury, "gBxS1f6uyyGPuW5MzGBukidSb71jdsCb5fZaoSzULE5");
+    assert!(!paused);
+    assert!(require_verified);
+
+    // MINT was delisted and relisted again
+    let delisted: Vec<String> = conn
+        .prepare("SELECT mint FROM delisted_mints")
+        .unwrap()
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(delisted, vec![PROPERTY_2.to_string()]);
+}
+
+#[test]
+fn tracks_offer_lifecycle() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (listing, buyer, total_price, status): (String, String, u64, String) = conn
+        .query_row(
+            "SELECT listing, buyer, total_price, status FROM offers WHERE offer = ?1",
+            [OFFER],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(listing, LISTING);
+    assert_eq!(buyer, NEW_OWNER);
+    assert_eq!(total_price, 550);
+    assert_eq!(status, "withdrawn");
+}
+
+#[test]
+fn tracks_order_book_fills() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (buyer, price, quantity, fee): (String, u64, u64, u64) = conn
+        .query_row(
+            "SELECT buyer, price, quantity, fee_amount FROM order_fills WHERE order_book = ?1",
+            [ORDER_BOOK],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .unwrap();
+    assert_eq!(buyer, BUYER);
+    assert_eq!((price, quantity, fee), (50, 40, 20));
+
+    let orders: Vec<(String, u64, String)> = conn
+        .prepare(
+            "SELECT side, remaining_quantity, status FROM orders
+             WHERE order_book = ?1 ORDER BY order_id",
+        )
+        .unwrap()
+        .query_map([ORDER_BOOK], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        orders,
+        vec![
+            ("ask".to_string(), 60, "cancelled".to_string()),
+            ("bid".to_string(), 0, "filled".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn tracks_auction_bids_and_settlement() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (kind, reserve, winner, price, end_time, status): (
+        String,
+        Option<u64>,
+        String,
+        u64,
+        i64,
+        String,
+    ) = conn
+        .query_row(
+            "SELECT kind, reserve_price, highest_bidder, highest_bid, end_time, status
+             FROM auctions WHERE auction = ?1",
+            [AUCTION],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(kind, "english");
+    assert_eq!(reserve, Some(5000));
+    assert_eq!(winner, AUCTION_WINNER);
+    assert_eq!(price, 5200);
+    // The late second bid extended the auction
+    assert_eq!(end_time, 1_700_000_036 + 3800);
+    assert_eq!(status, "settled");
+
+    let refunded: Vec<(String, bool)> = conn
+        .prepare("SELECT bidder, refunded FROM auction_bids WHERE auction = ?1 ORDER BY id")
+        .unwrap()
+        .query_map([AUCTION], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        refunded,
+        vec![
+            (BUYER.to_string(), true),
+            (AUCTION_WINNER.to_string(), false),
+        ]
+    );
+}
+
+#[test]
+fn tracks_pool_reserves_swaps_and_liquidity() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (mint, fee, property_reserve, quote_reserve, lp_supply): (String, u16, u64, u64, u64) =
+        conn.query_row(
+            "SELECT property_mint, fee, property_reserve, quote_reserve, lp_supply FROM pools
+             WHERE pool = ?1",
+            [POOL],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(mint, MINT);
+    assert_eq!(fee, 100);
+    assert_eq!(property_reserve, 101_000 - 50_494);
+    assert_eq!(quote_reserve, 990_128_420 - 495_014_703);
+    assert_eq!(lp_supply, 5_000_500);
+
+    let (trader, side, amount_in, amount_out, fee_amount): (String, String, u64, u64, u64) = conn
+        .query_row(
+            "SELECT trader, side, amount_in, amount_out, fee_amount FROM pool_swaps",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(trader, BUYER);
+    assert_eq!(side, "sell");
+    assert_eq!((amount_in, amount_out, fee_amount), (1_000, 9_871_580, 3));
+
+    let mut stmt = conn
+        .prepare("SELECT action, lp_amount FROM pool_liquidity ORDER BY id")
+        .unwrap();
+    let changes: Vec<(String, u64)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .unwrap()
+        .map(|row| row.unwrap())
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            ("add".to_string(), 9_999_000),
+            ("remove".to_string(), 4_999_500)
+        ]
+    );
+}
+
+#[test]
+fn tracks_governance_proposals_and_votes() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (action, action_status, votes_for, votes_against, status): (
+        String,
+        Option<String>,
+        u64,
+        u64,
+        String,
+    ) = conn
+        .query_row(
+            "SELECT action, action_status, votes_for, votes_against, status
+             FROM proposals WHERE proposal = ?1",
+            [PROPOSAL],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!(action, "update_property_status");
+    assert_eq!(action_status.as_deref(), Some("sold"));
+    assert_eq!((votes_for, votes_against), (100, 250));
+    assert_eq!(status, "defeated");
+
+    let voter: String = conn
+        .query_row(
+            "SELECT voter FROM votes WHERE proposal = ?1 AND support = 1",
+            [PROPOSAL],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(voter, BUYER);
+    assert_eq!(indexer.store().count("votes").unwrap(), 2);
+}
+
+#[test]
+fn tracks_liquidation_redemptions() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let (supply, redeemed, paid_out, status, redeem_until): (u64, u64, u64, String, i64) = conn
+        .query_row(
+            "SELECT supply, redeemed_tokens, paid_out, status, redeem_until FROM liquidations
+             WHERE liquidation = ?1",
+            [LIQUIDATION],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .unwrap();
+    assert_eq!((supply, redeemed, paid_out), (1000, 1000, 10000));
+    assert_eq!(status, "completed");
+    // The redemption window runs a year from the start
+    assert_eq!(redeem_until, 1_700_000_018 + 365 * 86_400);
+
+    let payout: u64 = conn
+        .query_row(
+            "SELECT payout FROM redemptions WHERE holder = ?1",
+            [BUYER],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(payout, 3000);
+}
+
+#[test]
+fn tracks_verification_history() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let statuses: Vec<String> = conn
+        .prepare("SELECT status FROM verifications WHERE property_record = ?1 ORDER BY slot")
+        .unwrap()
+        .query_map([RECORD], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(statuses, vec!["verified", "expired"]);
+
+    // The expired record went back to pending when its ownership was transferred
+    let status: String = conn
+        .query_row(
+            "SELECT verification_status FROM property_records WHERE property_record = ?1",
+            [RECORD],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(status, "pending");
+    assert_eq!(indexer.store().count("verifiers").unwrap(), 1);
+
+    let (authority, name): (String, String) = conn
+        .query_row(
+            "SELECT authority, name FROM verifiers WHERE verifier = ?1",
+            [VERIFIER],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!(authority, VERIFIER_AUTHORITY);
+    assert_eq!(name, "Acme Title & Escrow");
+}
+
+#[test]
+fn tracks_record_amendments_and_ownership() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+
+    let amendments: Vec<(u64, String, String, Option<String>)> = conn
+        .prepare(
+            "SELECT amendment_index, kind, owner, verifier FROM record_amendments
+             WHERE property_record = ?1 ORDER BY amendment_index",
+        )
+        .unwrap()
+        .query_map([RECORD], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(
+        amendments,
+        vec![
+            (0, "details".to_string(), OWNER.to_string(), None),
+            (
+                1,
+                "ownership_transfer".to_string(),
+                BUYER.to_string(),
+                Some(VERIFIER.to_string())
+            ),
+        ]
+    );
+
+    // The transfer of an unverified record resets it to pending
+    let statuses: Vec<String> = conn
+        .prepare(
+            "SELECT status FROM record_amendments WHERE property_record = ?1
+             ORDER BY amendment_index",
+        )
+        .unwrap()
+        .query_map([RECORD], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(statuses, vec!["expired", "pending"]);
+
+    let owner: String = conn
+        .query_row(
+            "SELECT owner FROM property_records WHERE property_record = ?1",
+            [RECORD],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(owner, BUYER);
+}
+
+#[test]
+fn marks_closed_accounts() {
+    let indexer = index(&["anchor_ledger.json"]);
+    let conn = indexer.store().connection();
+    let closed_at = |table: &str, key: &str, address: &str| -> Option<i64> {
+        conn.query_row(
+            &format!("SELECT closed_at FROM {table} WHERE {key} = ?1"),
+            [address],
+            |row| row.get(0),
+        )
+        .unwrap()
+    };
+
+    // Closed rows keep their final state for history
+    assert_eq!(
+        closed_at("auctions", "auction", AUCTION),
+        Some(1_700_000_038)
+    );
+    assert_eq!(
+        closed_at("proposals", "proposal", PROPOSAL),
+        Some(1_700_000_038)
+    );
+    assert_eq!(
+        closed_at("verifiers", "verifier", VERIFIER),
+        Some(1_700_000_038)
+    );
+    assert_eq!(
+        closed_at("property_records", "property_record", RECORD_2),
+        Some(1_700_000_038)
+    );
+    assert_eq!(
+        closed_at("property_records", "property_record", RECORD),
+        None
+    );
+    assert_eq!(
+        closed_at("record_amendments", "amendment", RECORD_2_AMENDMENT),
+        Some(1_700_000_038)
+    );
+
+    let status: String = conn
+        .query_row(
+            "SELECT verification_status FROM property_records WHERE property_record = ?1",
+            [RECORD_2],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(status, "rejected");
+}
+
+#[test]
+fn parses_native_program_messages() {
+    let indexer = index(&["native_ledger.jsonl"]);
+    let store = indexer.store();
+
+    assert_eq!(store.count("properties").unwrap(), 1);
+    assert_eq!(store.count("purchases").unwrap(), 1);
+    assert_eq!(store.count("property_valuations").unwrap(), 1);
+
+    let (amount, source): (u64, String) = store
+        .connection()
+        .query_row("SELECT amount, source FROM yields", [], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })
+        .unwrap();
+    assert_eq!(amount, 1500);
+    assert_eq!(source, "native");
+}
+
+#[test]
+fn native_logs_are_ignored_without_a_native_program_id() {
+    let mut indexer = Indexer::new(Store::open_in_memory().unwrap(), ProgramIds::default());
+    let summary = indexer.index(&fixture("native_ledger.jsonl")).unwrap();
+
+    assert_eq!(summary.records, 0);
+    assert_eq!(indexer.store().count("yields").unwrap(), 0);
+}
+
+#[test]
+fn replaying_a_ledger_is_idempotent() {
+    let mut indexer = index(&["anchor_ledger.json"]);
+    let summary = indexer.index(&fixture("anchor_ledger.json")).unwrap();
+
+    assert_eq!(
+        summary,
+        IndexSummary {
+            indexed: 0,
+            already_indexed: 46,
+            records: 0,
+        }
+    );
+    assert_eq!(indexer.store().count("purchases").unwrap(), 1);
+    assert_eq!(indexer.store().count("trades").unwrap(), 2);
+}